@@ -0,0 +1,59 @@
+// Compares the tree-walking `Expr::eval` against the bytecode VM on the same expression.
+// Run with `cargo bench`. There's no external benchmarking crate here, just `Instant`
+// and `black_box` so the optimizer can't throw the work away.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use enums::expr::Expr;
+use enums::vm::{self, Vm};
+use enums::Operations;
+
+const ITERATIONS: u32 = 1_000_000;
+
+// (x * 3 + y) - (if x - y then x / 2 else y * y)
+fn sample() -> Expr {
+    let x = || Expr::var("x");
+    let y = || Expr::var("y");
+    Expr::binary(
+        Operations::Subtract,
+        Expr::binary(Operations::Add, Expr::binary(Operations::Multiply, x(), Expr::Const(3)), y()),
+        Expr::if_else(
+            Expr::binary(Operations::Subtract, x(), y()),
+            Expr::binary(Operations::Divide, x(), Expr::Const(2)),
+            Expr::binary(Operations::Multiply, y(), y()),
+        ),
+    )
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let per_second = ITERATIONS as f64 / elapsed.as_secs_f64();
+    println!("{:<6} {:>10.2?} total, {:>12.0} evals/s", name, elapsed, per_second);
+}
+
+fn main() {
+    let expr = sample();
+    let chunk = vm::compile(&expr).unwrap();
+    let mut vm = Vm::new();
+
+    // Both sides update their variables in place, so neither pays for allocation in the loop.
+    let mut env = HashMap::from([("x".to_owned(), 0), ("y".to_owned(), 7)]);
+    let start = Instant::now();
+    for i in 0..ITERATIONS as i32 {
+        *env.get_mut("x").unwrap() = i % 100;
+        black_box(black_box(&expr).eval(&env).unwrap());
+    }
+    let tree = start.elapsed();
+
+    let start = Instant::now();
+    for i in 0..ITERATIONS as i32 {
+        let slots = [i % 100, 7];
+        black_box(vm.run(black_box(&chunk), &slots).unwrap());
+    }
+    let bytecode = start.elapsed();
+
+    report("tree", tree);
+    report("vm", bytecode);
+    println!("speedup: {:.2}x", tree.as_secs_f64() / bytecode.as_secs_f64());
+}
@@ -0,0 +1,64 @@
+// Summarising event logs and drawing click heatmaps.
+// Run with `cargo run --example analytics`.
+
+use std::time::Duration;
+
+use enums::analytics::{Heatmap, SessionStats};
+use enums::session::{Recorded, SessionReader};
+use enums::WebEvent;
+
+fn at(millis: u64, event: WebEvent) -> Recorded {
+    Recorded { at: Duration::from_millis(millis), event }
+}
+
+fn main() {
+    let session = vec![
+        at(0, WebEvent::PageLoad),
+        // Five keys 200ms apart, a long pause, then two more 100ms apart. The pause
+        // doesn't count, so that's 5 gaps in 0.9s: 5 keys / 0.9s = 333 per minute.
+        at(1_000, WebEvent::KeyPress('h')),
+        at(1_200, WebEvent::KeyPress('e')),
+        at(1_400, WebEvent::KeyPress('l')),
+        at(1_600, WebEvent::KeyPress('l')),
+        at(1_800, WebEvent::KeyPress('o')),
+        at(9_000, WebEvent::KeyPress('!')),
+        at(9_100, WebEvent::KeyPress('!')),
+        at(9_200, WebEvent::Paste("abc".to_owned())),
+        at(9_300, WebEvent::Paste("héllo 🦀".to_owned())),
+        at(10_000, WebEvent::Click { x: 0, y: 0 }),
+        at(10_100, WebEvent::Click { x: 99, y: 49 }),
+        at(10_200, WebEvent::Click { x: 99, y: 49 }),
+        at(10_300, WebEvent::Click { x: 50, y: 0 }),
+        at(12_000, WebEvent::PageUnload),
+        // A second visit, and a third that's still open when the log ends.
+        at(20_000, WebEvent::PageLoad),
+        at(23_000, WebEvent::PageUnload),
+        at(30_000, WebEvent::PageLoad),
+    ];
+    let stats = SessionStats::from_session(session.clone());
+    println!("{}", stats);
+    print!("{}", stats.heatmap(20, 6).unwrap());
+
+    // The same events without timestamps: counts and clicks, but nothing about time.
+    let events: Vec<WebEvent> = session.iter().map(|r| r.event.clone()).collect();
+    println!("{}", SessionStats::from_events(&events));
+
+    // A heatmap with fixed bounds counts the clicks outside them separately.
+    let mut heatmap = Heatmap::new((0, 9), (0, 9), 2, 2);
+    for (x, y) in [(0, 0), (4, 4), (5, 5), (9, 9), (10, 0), (-1, 5)] {
+        heatmap.add(x, y);
+    }
+    print!("{}", heatmap);
+    println!("{} click(s) outside", heatmap.outside());
+
+    // Straight from a recorded log.
+    let log = concat!(
+        r#"{"t_us":0,"type":"page_load"}"#,
+        "\n",
+        r#"{"t_us":500000,"type":"click","x":3,"y":4}"#,
+        "\n",
+        r#"{"t_us":2500000,"type":"page_unload"}"#,
+        "\n",
+    );
+    println!("{}", SessionStats::from_session(SessionReader::new(log.as_bytes()).map(Result::unwrap)));
+}
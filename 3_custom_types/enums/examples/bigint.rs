@@ -0,0 +1,21 @@
+// Factorials and powers that are far too big for any primitive integer.
+// Run with `cargo run --example bigint`.
+
+use enums::{BigInt, Operations};
+
+fn factorial(n: u32) -> BigInt {
+    (1..=n).fold(BigInt::one(), |acc, i| Operations::Multiply.run(acc, BigInt::from(i)))
+}
+
+fn main() {
+    println!("30! = {}", factorial(30));
+    println!("100! = {}", factorial(100));
+    println!("2^200 = {}", BigInt::from(2u32).pow(200));
+
+    // The same number in all three bases.
+    let n: BigInt = "-0xDeadBeefDeadBeefDeadBeefDeadBeef1".parse().unwrap();
+    println!("{} = {:#x} = {:#b}", n, n, n);
+
+    let (q, r) = factorial(50).div_rem(&factorial(48));
+    println!("50! / 48! = {} remainder {}", q, r);
+}
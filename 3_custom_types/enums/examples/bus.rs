@@ -0,0 +1,33 @@
+// Publishing from several threads to several subscribers, overflow policies,
+// shutdown and metrics. Run with `cargo run --example bus`.
+
+use std::thread;
+
+use enums::bus::{EventBus, Overflow};
+use enums::WebEvent;
+
+fn main() {
+    // Two producers, one consumer that sees everything and one that only keeps the
+    // newest few events when it falls behind.
+    let bus = EventBus::new();
+    let everything = bus.subscribe(8, Overflow::Block);
+    let latest = bus.subscribe(3, Overflow::DropOldest);
+    let consumer = thread::spawn(move || everything.iter().count());
+    let producers: Vec<_> = (0..2)
+        .map(|p| {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    bus.publish(WebEvent::Click { x: p, y: i }).unwrap();
+                }
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    bus.shutdown();
+    println!("the blocking subscriber got {} events", consumer.join().unwrap());
+    println!("the dropping one kept {:?}", latest.iter().collect::<Vec<_>>());
+    println!("{:?}", bus.metrics());
+}
@@ -0,0 +1,30 @@
+// Complex numbers and the infix calculator. Run with `cargo run --example complex`.
+
+use std::f64::consts::PI;
+
+use enums::calc;
+use enums::{Complex, Rational};
+
+fn main() {
+    let c = Complex::new;
+
+    println!("(1+2i)(3-i) = {}", c(1.0, 2.0) * c(3.0, -1.0));
+    println!("(1+2i)/(3-i) = {:.3}", c(1.0, 2.0) / c(3.0, -1.0));
+    println!("(1+i)^8 = {}, sqrt(2i) = {}", c(1.0, 1.0).powi(8), c(0.0, 2.0).sqrt());
+    println!("|3+4i| = {}, polar form of -1: {:?}", c(3.0, 4.0).modulus(), c(-1.0, 0.0).to_polar());
+
+    // Euler: e^(i*pi) = -1, and i^i = e^(-pi/2) is real.
+    println!("e^(i*pi) = {:.3}", c(0.0, PI).exp());
+    println!("i^i = {}", Complex::<f64>::i().pow(&Complex::i()));
+
+    // The calculator, with complex literals.
+    for input in ["(1+2i)*(3-i)", "i * i", "-(2 - 3i) + 4", "(1+2i)/(3-i)"] {
+        let value: Complex = calc::eval(input).unwrap();
+        println!("{} = {}", input, value);
+    }
+
+    // The same parser over other value types.
+    println!("8 - 4 - 2 * 3 = {:?}", calc::eval::<i64>("8 - 4 - 2 * 3"));
+    println!("1/3 + 1/6 = {:?}", calc::eval::<Rational>("1/3 + 1/6").map(|r| r.to_string()));
+    println!("1 + * 2 = {:?}", calc::eval::<f64>("1 + * 2"));
+}
@@ -0,0 +1,39 @@
+// Fixed-point decimals for money and token amounts. Run with `cargo run --example decimal`.
+
+use enums::{Decimal, Operations, RoundingMode};
+
+type Money = Decimal<2>;
+type Token = Decimal<18>;
+
+fn main() {
+    let d = |s: &str| s.parse::<Money>().unwrap();
+
+    // 0.1 + 0.2 is exactly 0.3, unlike with floats.
+    println!("0.1 + 0.2 = {}", d("0.1") + d("0.2"));
+
+    // The rectangle from the structs example, worked out exactly.
+    let width = Operations::Subtract.run(d("10.5"), d("9.5")).abs();
+    let height = Operations::Subtract.run(d("10.9"), d("9.7")).abs();
+    println!("area = {}", Operations::Multiply.run(width, height));
+
+    // Every rounding mode on the same tie and near-tie values.
+    let modes = [RoundingMode::HalfEven, RoundingMode::HalfUp, RoundingMode::Floor, RoundingMode::Ceil, RoundingMode::Truncate];
+    for mode in modes {
+        let got: Vec<String> = ["2.345", "-2.345", "2.355", "2.3451"]
+            .iter()
+            .map(|s| Money::parse_rounded(s, mode).unwrap().to_string())
+            .collect();
+        println!("{:<9} {:?}", format!("{:?}", mode), got);
+    }
+
+    // Splitting $100 three ways: each share rounds down, and the remainder is explicit.
+    let total = d("100");
+    let share = total.checked_div(&Money::from_int(3), RoundingMode::Floor).unwrap();
+    println!("100.00 / 3 = {} each, {} left over", share, total - share - share - share);
+
+    // 18-decimal token math.
+    let amount: Token = "1.5".parse().unwrap();
+    let price: Token = "2000.123456789012345678".parse().unwrap();
+    println!("{} * {} = {}", amount, price, amount * price);
+    println!("price in cents: {}", price.rescale::<2>(RoundingMode::HalfEven).unwrap());
+}
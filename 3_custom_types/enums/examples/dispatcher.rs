@@ -0,0 +1,38 @@
+// Per-variant handlers, priorities, stopping and unregistering.
+// Run with `cargo run --example dispatcher`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use enums::dispatcher::{EventDispatcher, Propagation};
+use enums::{EventKind, WebEvent};
+
+fn main() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    // A handler that records its name and then does whatever `result` says.
+    let record = |name: &'static str, result: Propagation| {
+        let seen = Rc::clone(&seen);
+        move |_: &WebEvent| {
+            seen.borrow_mut().push(name);
+            result
+        }
+    };
+
+    let mut dispatcher = EventDispatcher::new();
+    dispatcher.on_any(record("any", Propagation::Continue));
+    let keys = dispatcher.on(EventKind::KeyPress, record("keys", Propagation::Continue));
+    dispatcher.register(Some(EventKind::KeyPress), 10, record("urgent keys", Propagation::Continue));
+    dispatcher.register(Some(EventKind::Paste), 5, record("paste guard", Propagation::Stop));
+
+    // Higher priority first, then registration order. The guard stops pastes before
+    // the catch-all sees them, and after `off` the "keys" handler is gone.
+    let events = [WebEvent::KeyPress('a'), WebEvent::Paste("secret".to_owned()), WebEvent::Click { x: 1, y: 2 }];
+    for event in &events {
+        let result = dispatcher.dispatch(event);
+        println!("{:?}: {:?} ran, {:?}", event, seen.borrow(), result);
+        seen.borrow_mut().clear();
+    }
+    dispatcher.off(keys);
+    dispatcher.dispatch(&events[0]);
+    println!("after off: {:?}", seen.borrow());
+}
@@ -0,0 +1,57 @@
+// Driving the text editor core with events, including from a recorded session.
+// Run with `cargo run --example editor`.
+
+use enums::editor::TextBuffer;
+use enums::session::SessionReader;
+use enums::terminal::decode_all;
+use enums::web_event::keys;
+use enums::WebEvent;
+
+fn typed(s: &str) -> Vec<WebEvent> {
+    s.chars().map(WebEvent::KeyPress).collect()
+}
+
+fn run(buffer: &mut TextBuffer, events: &[WebEvent]) {
+    for event in events {
+        buffer.apply(event);
+    }
+}
+
+fn main() {
+    // Typing, then fixing a typo with Backspace and undoing it again.
+    let mut buffer = TextBuffer::new();
+    run(&mut buffer, &typed("hello wrold"));
+    println!("typed:    {:?}", buffer.text());
+    run(&mut buffer, &vec![WebEvent::KeyPress(keys::BACKSPACE); 4]);
+    run(&mut buffer, &typed("orld"));
+    println!("fixed:    {:?}", buffer.text());
+    buffer.apply(&WebEvent::KeyPress(keys::ctrl('z').unwrap()));
+    println!("undone:   {:?}", buffer.text());
+
+    // Lines, and where the cursor ends up after a click.
+    run(&mut buffer, &typed("\nsecond line"));
+    buffer.apply(&WebEvent::Click { x: 3, y: 0 });
+    println!("{:?} with the cursor at {:?}", buffer.text(), buffer.line_and_column());
+
+    // Replaying a recorded session into an editor.
+    let session = concat!(
+        r#"{"t_us":0,"type":"page_load"}"#,
+        "\n",
+        r#"{"t_us":100000,"type":"key_press","key":"H"}"#,
+        "\n",
+        r#"{"t_us":200000,"type":"key_press","key":"i"}"#,
+        "\n",
+        r#"{"t_us":300000,"type":"paste","text":", there"}"#,
+        "\n",
+    );
+    let mut buffer = TextBuffer::new();
+    for recorded in SessionReader::new(session.as_bytes()) {
+        buffer.apply(&recorded.unwrap().event);
+    }
+    println!("replayed: {:?}", buffer.text());
+
+    // And one typed into a terminal: "abc", Left, Backspace, then a bracketed paste.
+    let mut buffer = TextBuffer::new();
+    run(&mut buffer, &decode_all(b"abc\x1b[D\x7f\x1b[200~XY\x1b[201~"));
+    println!("terminal: {:?}, cursor at {}", buffer.text(), buffer.cursor());
+}
@@ -0,0 +1,27 @@
+// Parsing filters, matching events against them, and the errors for bad ones.
+// Run with `cargo run --example filter`.
+
+use enums::filter::Filter;
+use enums::web_event::keys;
+use enums::WebEvent;
+
+fn main() {
+    let events = [
+        WebEvent::PageLoad,
+        WebEvent::Click { x: 150, y: 20 },
+        WebEvent::Click { x: 50, y: 90 },
+        WebEvent::KeyPress('"'),
+        WebEvent::KeyPress(keys::UP),
+        WebEvent::Paste("see https://example.com".to_owned()),
+        WebEvent::PageUnload,
+    ];
+    for text in ["type == click && x > 100", "paste contains http || key == \"\\\"\"", "!(x < 1000)", "z > 1", "x > 1 &&"] {
+        match Filter::parse(text) {
+            Ok(filter) => {
+                let matched: Vec<&WebEvent> = events.iter().filter(|e| filter.matches(e)).collect();
+                println!("{}\n  printed as {}\n  matches {:?}", text, filter, matched);
+            },
+            Err(e) => println!("{}\n  {}^ {}", text, " ".repeat(e.at), e.kind),
+        }
+    }
+}
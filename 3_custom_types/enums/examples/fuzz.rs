@@ -0,0 +1,55 @@
+// Fuzzing handlers with generated events, and shrinking the streams that break them.
+// Run with `cargo run --release --example fuzz`, as it runs a lot of events.
+
+use enums::editor::TextBuffer;
+use enums::fuzz::{self, FuzzConfig, Generator};
+use enums::jsonl;
+use enums::WebEvent;
+
+fn main() {
+    // The same seed always gives the same events.
+    for event in Generator::new(42).take(5) {
+        println!("{}", jsonl::to_json(&event));
+    }
+
+    // The library's text editor, which shouldn't panic on anything.
+    let config = FuzzConfig { streams: 200, stream_len: 500, ..FuzzConfig::default() };
+    let total = fuzz::fuzz(&config, || {
+        let mut buffer = TextBuffer::new();
+        move |event: &WebEvent| {
+            buffer.apply(event);
+            assert!(buffer.cursor() <= buffer.len());
+        }
+    });
+    println!("editor: {:?} events handled", total);
+
+    // A handler with a bug: it shows the first ten bytes of a paste, which panics
+    // when byte ten falls inside a character. It shrinks to a single paste.
+    let failure = fuzz::fuzz(&config, || {
+        |event: &WebEvent| {
+            if let WebEvent::Paste(text) = event {
+                if text.len() > 10 {
+                    let _preview = &text[..10];
+                }
+            }
+        }
+    })
+    .unwrap_err();
+    print!("{}", failure);
+
+    // A bug that needs history: unloading twice in a row. Out of hundreds of events,
+    // only the two that matter are left.
+    let failure = fuzz::fuzz(&config, || {
+        let mut loaded = true;
+        move |event: &WebEvent| match event {
+            WebEvent::PageLoad => loaded = true,
+            WebEvent::PageUnload => {
+                assert!(loaded, "unloaded a page that wasn't loaded");
+                loaded = false;
+            },
+            _ => {},
+        }
+    })
+    .unwrap_err();
+    print!("{}", failure);
+}
@@ -0,0 +1,32 @@
+// Turning raw clicks, pointer moves and keys into gestures.
+// Run with `cargo run --example gesture`.
+
+use std::time::Duration;
+
+use enums::gesture::{GestureConfig, GestureRecognizer, Input, PointerAction};
+use enums::web_event::keys;
+use enums::WebEvent;
+
+fn main() {
+    let mut recognizer = GestureRecognizer::new(GestureConfig::default());
+    recognizer.chord("Ctrl+K Ctrl+C", "comment").unwrap();
+    recognizer.chord("g g", "top").unwrap();
+
+    let pointer = |action, x, y| Input::Pointer { action, x, y };
+    let inputs: Vec<(u64, Input)> = vec![
+        (0, WebEvent::Click { x: 5, y: 5 }.into()),
+        (300, WebEvent::Click { x: 6, y: 4 }.into()),
+        (1_000, pointer(PointerAction::Down, 10, 10)),
+        (1_020, pointer(PointerAction::Move, 30, 15)),
+        (1_040, pointer(PointerAction::Up, 31, 15)),
+        (2_000, WebEvent::KeyPress(keys::ctrl('k').unwrap()).into()),
+        (2_400, WebEvent::KeyPress(keys::ctrl('c').unwrap()).into()),
+        (3_000, WebEvent::KeyPress('g').into()),
+        (3_200, WebEvent::KeyPress('g').into()),
+    ];
+    for (ms, input) in inputs {
+        for gesture in recognizer.feed(Duration::from_millis(ms), input) {
+            println!("{:>5} ms  {:?}", ms, gesture);
+        }
+    }
+}
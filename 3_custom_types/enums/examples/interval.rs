@@ -0,0 +1,34 @@
+// Guaranteed error bounds with `Interval`. Run with `cargo run --example interval`.
+
+use enums::{Interval, Operations};
+
+fn main() {
+    let d = |s: &str| Interval::from_decimal_str(s).unwrap();
+
+    // The rectangle from the structs example, with the coordinates as the decimals they
+    // were written as. The exact answer, 1.2, is guaranteed to be inside.
+    let width = Operations::Subtract.run(d("9.5"), d("10.5")).abs();
+    let height = Operations::Subtract.run(d("10.9"), d("9.7")).abs();
+    let area = Operations::Multiply.run(width, height);
+    println!("area in {} (width {:e})", area, area.width());
+
+    // 0.1 added ten times: plain `f64` misses 1.0, the interval still contains it.
+    let mut sum = 0.0;
+    let mut bounds = Interval::point(0.0);
+    for _ in 0..10 {
+        sum += 0.1;
+        bounds = Operations::Add.run(bounds, d("0.1"));
+    }
+    println!("f64 sum = {}, interval = {}", sum, bounds);
+
+    let a = Interval::new(-2.0, 3.0);
+    let b = Interval::new(4.0, 5.0);
+    println!("{} * {} = {}", a, b, a * b);
+    println!("{} / {} = {}", a, b, a / b);
+    println!("{} / {} = {:?}", b, a, Operations::Divide.checked_run(b, a));
+
+    // A diagonal from f32 points: sqrt(dx^2 + dy^2) for (0.1, 0.2) to (0.4, 0.6).
+    let (x1, y1, x2, y2) = (Interval::point(0.1f32 as f64), Interval::point(0.2f32 as f64), Interval::point(0.4f32 as f64), Interval::point(0.6f32 as f64));
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    println!("diagonal in {:.12}", (dx * dx + dy * dy).sqrt());
+}
@@ -0,0 +1,34 @@
+// Writing `WebEvent`s as JSON Lines and reading them back.
+// Run with `cargo run --example jsonl`.
+
+use enums::jsonl::{from_json, write_event, EventReader};
+use enums::WebEvent;
+
+fn main() {
+    let events = vec![
+        WebEvent::PageLoad,
+        WebEvent::KeyPress('\u{1f980}'),
+        WebEvent::Paste("quotes \" and \\backslashes\\ and\r\nnewlines".to_owned()),
+        WebEvent::Click { x: i64::MIN, y: i64::MAX },
+        WebEvent::PageUnload,
+    ];
+
+    let mut log = Vec::new();
+    for event in &events {
+        write_event(&mut log, event).unwrap();
+    }
+    print!("{}", String::from_utf8_lossy(&log));
+
+    // Reading accepts other field orders, whitespace and `\u` escapes.
+    println!("{:?}", from_json(r#" { "y" : -3, "x": 4, "type": "click", "button": "left" } "#));
+    println!("{:?}", from_json(r#"{"type":"key_press","key":"🦀"}"#));
+
+    // Errors carry the line they were on, and reading carries on past them.
+    let input: &[u8] = b"{\"type\":\"page_load\"}\n\n{\"type\":\"scroll\"}\n{\"type\":\"click\",\"x\":1}\n[1,2]\n{\"type\":\"page_unload\"}\n";
+    for result in EventReader::new(input) {
+        match result {
+            Ok(event) => println!("ok: {:?}", event),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
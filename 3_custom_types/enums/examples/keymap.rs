@@ -0,0 +1,69 @@
+// Key bindings with modes, multi-key sequences and a config file.
+// Run with `cargo run --example keymap`.
+
+use std::time::Duration;
+
+use enums::keymap::{parse_keys, Action, Keymap};
+use enums::web_event::keys;
+use enums::WebEvent;
+
+const CONFIG: &str = "
+# Half a second to finish a sequence.
+timeout = 500
+
+[normal]
+h = move-left
+l = move-right
+g = go-to-line
+g g = go-to-top
+Ctrl+W v = split-vertical
+Ctrl+W s = split-horizontal
+Alt+x = command-palette
+Shift+z Shift+z = save-and-quit
+= = equalize
+i = mode insert
+
+[insert]
+Esc = mode normal
+Ctrl+W = delete-word
+";
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+// Feeds keys 10ms apart, starting at `start` milliseconds.
+fn type_keys(keymap: &mut Keymap, start: u64, keys: &[char]) -> Vec<Action> {
+    let mut out = Vec::new();
+    for (i, &c) in keys.iter().enumerate() {
+        out.extend(keymap.feed(ms(start + 10 * i as u64), &WebEvent::KeyPress(c)));
+    }
+    out
+}
+
+fn main() {
+    let mut keymap = Keymap::from_config(CONFIG).unwrap();
+    println!("Ctrl+W v in normal mode: {:?}", keymap.lookup("normal", "Ctrl+W v"));
+
+    // Single keys fire straight away, `g g` waits for the second `g`, and Ctrl+W, Alt+x
+    // and Shift+z arrive as the characters a terminal would send.
+    let ctrl_w = keys::ctrl('w').unwrap();
+    println!("{:?}", type_keys(&mut keymap, 0, &['h', 'l', 'g', 'g', ctrl_w, 's', keys::ESCAPE, 'x', 'Z', 'Z']));
+
+    // A lone `g` comes out once the timeout has passed.
+    type_keys(&mut keymap, 1_000, &['g']);
+    println!("waiting until {:?}", keymap.deadline());
+    println!("{:?}", keymap.tick(ms(1_501)));
+
+    // Insert mode passes letters through as keys to type.
+    println!("{:?}", type_keys(&mut keymap, 2_000, &['i', 'h', 'i', ctrl_w, keys::ESCAPE, 'h']));
+
+    // Keymaps can be built in code, too.
+    let mut keymap = Keymap::new("normal");
+    keymap.bind("normal", "Ctrl+K Ctrl+C", "comment").unwrap();
+    let (k, c) = (keys::ctrl('k').unwrap(), keys::ctrl('c').unwrap());
+    println!("{:?}", type_keys(&mut keymap, 0, &[k, c]));
+
+    println!("Alt+Enter F5 is {:?}", parse_keys("Alt+Enter F5").unwrap());
+    println!("{}", Keymap::from_config("[normal]\nh = a\nh = b").unwrap_err());
+}
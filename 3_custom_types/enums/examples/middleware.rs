@@ -0,0 +1,106 @@
+// Debouncing, throttling, coalescing and batching events, on a clock that only moves
+// when told to. Run with `cargo run --example middleware`.
+
+use std::time::Duration;
+
+use enums::middleware::{Pipeline, Stage};
+use enums::session::{Clock, ManualClock};
+use enums::{EventKind, WebEvent};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn key(c: char) -> WebEvent {
+    WebEvent::KeyPress(c)
+}
+
+fn click(x: i64) -> WebEvent {
+    WebEvent::Click { x, y: 0 }
+}
+
+fn paste(text: &str) -> WebEvent {
+    WebEvent::Paste(text.to_owned())
+}
+
+// Feeds `(millis, event)` pairs through a pipeline, ticking whenever it has something
+// due, and collects what comes out along with when it did.
+fn drive<S: Stage<In = WebEvent>>(pipeline: &mut Pipeline<S>, events: &[(u64, WebEvent)], end: u64) -> Vec<(u64, S::Out)> {
+    let mut out = Vec::new();
+    let tick_until = |pipeline: &mut Pipeline<S>, out: &mut Vec<(u64, S::Out)>, until: u64| {
+        while let Some(deadline) = pipeline.deadline().filter(|d| *d <= ms(until)) {
+            let at = deadline.as_millis() as u64;
+            out.extend(pipeline.tick(deadline).into_iter().map(|o| (at, o)));
+        }
+    };
+    for (at, event) in events {
+        tick_until(pipeline, &mut out, *at);
+        out.extend(pipeline.push(ms(*at), event.clone()).into_iter().map(|o| (*at, o)));
+    }
+    tick_until(pipeline, &mut out, end);
+    out
+}
+
+// A stage written outside the library: numbers the events it sees.
+struct Numbered(usize);
+
+impl Stage for Numbered {
+    type In = WebEvent;
+    type Out = (usize, WebEvent);
+
+    fn push(&mut self, _at: Duration, event: WebEvent, out: &mut Vec<(usize, WebEvent)>) {
+        self.0 += 1;
+        out.push((self.0, event));
+    }
+}
+
+fn main() {
+    // Debouncing: a burst of keys comes out as its last key, once things are quiet.
+    let mut pipeline = Pipeline::new().debounce(EventKind::KeyPress, ms(100));
+    let events = [(0, key('h')), (50, key('e')), (120, key('y')), (400, key('!')), (499, key('?'))];
+    println!("debounced: {:?}", drive(&mut pipeline, &events, 1_000));
+
+    // Throttling: one click per 250ms, counting from the last one let through.
+    let mut pipeline = Pipeline::new().throttle(EventKind::Click, ms(250));
+    let events = [(0, click(0)), (100, click(1)), (120, key('k')), (249, click(2)), (250, click(3)), (600, click(4))];
+    println!("throttled: {:?}", drive(&mut pipeline, &events, 1_000));
+
+    // Coalescing: pastes less than 50ms apart become one.
+    let mut pipeline = Pipeline::new().coalesce_pastes(ms(50));
+    let events = [(0, paste("a")), (30, paste("b")), (79, paste("c")), (200, paste("d")), (300, paste("x")), (310, key('k'))];
+    println!("coalesced: {:?}", drive(&mut pipeline, &events, 1_000));
+
+    // Batching: by count, or when the first in the batch has waited long enough.
+    let mut pipeline = Pipeline::new().batch(3, ms(100));
+    let events = [(0, click(0)), (10, click(1)), (20, click(2)), (30, click(3)), (40, click(4))];
+    println!("batched: {:?}", drive(&mut pipeline, &events, 1_000));
+
+    // Stages compose, and the types follow along: after `batch` the pipeline carries
+    // `Vec<WebEvent>`s, after `map` whatever the function returns. Custom stages plug
+    // in with `then`.
+    let mut pipeline = Pipeline::new()
+        .filter(|event| *event != WebEvent::PageUnload)
+        .throttle(EventKind::Click, ms(100))
+        .then(Numbered(0))
+        .batch(10, ms(500))
+        .map(|batch| batch.len());
+    let events = [(0, click(1)), (10, key('a')), (20, click(2)), (30, WebEvent::PageUnload), (40, paste("p"))];
+    println!("batch sizes: {:?}", drive(&mut pipeline, &events, 1_000));
+
+    // A `Runner` takes the time from a clock and hands results to a handler, the way a
+    // real event loop would use it.
+    let clock = ManualClock::new();
+    let mut runner = Pipeline::new()
+        .debounce(EventKind::KeyPress, ms(300))
+        .throttle(EventKind::Click, ms(1_000))
+        .run(&clock, |event| println!("{:?} at {:?}", event, clock.now()));
+    for c in "search".chars() {
+        runner.send(key(c));
+        clock.advance(ms(80));
+    }
+    runner.send(click(1));
+    runner.send(click(2));
+    clock.advance(ms(400));
+    runner.poll();
+    let (_pipeline, _handler) = runner.finish();
+}
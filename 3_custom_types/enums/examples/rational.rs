@@ -0,0 +1,31 @@
+// Exact fractions with `Rational`. Run with `cargo run --example rational`.
+
+use enums::{BigInt, Operations, Rational};
+
+// The same calculation as `rect_area` in the structs example, but exact, so there's
+// no rounding to tidy up afterwards.
+fn rect_area(top_left: (Rational, Rational), bottom_right: (Rational, Rational)) -> Rational {
+    let width = Operations::Subtract.run(bottom_right.0, top_left.0).abs();
+    let height = Operations::Subtract.run(top_left.1, bottom_right.1).abs();
+    Operations::Multiply.run(width, height)
+}
+
+fn main() {
+    let r = |s: &str| s.parse::<Rational>().unwrap();
+
+    let area = rect_area((r("10.5"), r("10.9")), (r("9.5"), r("9.7")));
+    println!("area = {} = {}", area, area.to_decimal(2));
+    println!("f32 area = {}", (9.5f32 - 10.5).abs() * (10.9f32 - 9.7).abs());
+
+    println!("6/-4 = {}, 2/3 = {}", Rational::<i64>::new(6, -4), r("2/3").to_decimal(4));
+    println!("0.1 as f64 is exactly {}", Rational::<i64>::from_f64(0.1).unwrap());
+
+    // `i64` reports overflow, `BigInt` doesn't have any.
+    let huge = Rational::<i64>::from_integer(i64::MAX);
+    println!("i64::MAX + i64::MAX = {:?}", Operations::Add.checked_run(huge.clone(), huge));
+
+    let harmonic = (1..=100).fold(Rational::<BigInt>::zero(), |acc, n| {
+        Operations::Add.run(acc, Rational::new(BigInt::one(), BigInt::from(n)))
+    });
+    println!("H(100) = {} ~ {}", harmonic, harmonic.to_decimal(10));
+}
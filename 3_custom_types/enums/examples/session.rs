@@ -0,0 +1,39 @@
+// Recording a session and replaying it, fast and in (virtual) real time.
+// Run with `cargo run --example session`.
+
+use std::time::Duration;
+
+use enums::session::{Clock, ManualClock, Pace, Player, Recorded, Recorder, SessionReader};
+use enums::web_event::describe;
+use enums::WebEvent;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn main() {
+    // Record with a clock we control, so the timestamps are known exactly.
+    let clock = ManualClock::new();
+    let mut recorder = Recorder::new(Vec::new(), &clock);
+    let script = [
+        (0, WebEvent::PageLoad),
+        (250, WebEvent::KeyPress('h')),
+        (400, WebEvent::KeyPress('é')),
+        (1_000, WebEvent::Paste("\"quoted\"\n".to_owned())),
+        (0, WebEvent::Click { x: 20, y: 80 }),
+        (2_350, WebEvent::PageUnload),
+    ];
+    for (gap, event) in &script {
+        clock.advance(ms(*gap));
+        recorder.record(event).unwrap();
+    }
+    let log = recorder.finish().unwrap();
+    print!("{}", String::from_utf8_lossy(&log));
+
+    // Replay at double speed, printing when each event arrives.
+    let session: Vec<Recorded> = SessionReader::new(&log[..]).map(Result::unwrap).collect();
+    let replay_clock = ManualClock::new();
+    Player::new(&replay_clock, Pace::Timed { speed: 2.0 }).play(session, |e| {
+        println!("{:>5} ms  {}", replay_clock.now().as_millis(), describe(e));
+    });
+}
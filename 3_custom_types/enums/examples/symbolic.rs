@@ -0,0 +1,26 @@
+// A tiny computer-algebra system on top of `Operations`.
+// Run with `cargo run --example symbolic`.
+
+use enums::symbolic::Sym;
+
+fn main() {
+    let x = || Sym::var("x");
+    let y = || Sym::var("y");
+    let n = Sym::int;
+
+    // Like terms, in any order.
+    let f = x() * n(2) + y() + (n(3) * x() - y());
+    println!("{}  =>  {}", f, f.simplify());
+
+    let f = x() * x() + n(3) * x() - n(7);
+    let df = f.derivative("x");
+    println!("d/dx {}  =  {}", f, df);
+    println!("at x = 2: {:?} and {:?}", f.eval_at("x", 2.0), df.eval_at("x", 2.0));
+
+    let g = n(1) / x();
+    println!("d/dx {}  =  {}", g, g.derivative("x"));
+
+    // Partial derivatives treat other variables as constants.
+    let h = x() * y() * (x() - y());
+    println!("d/dy {}  =  {}", h, h.derivative("y"));
+}
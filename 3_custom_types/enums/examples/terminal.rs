@@ -0,0 +1,22 @@
+// Decoding canned terminal input, the way it arrives in reads.
+// Run with `cargo run --example terminal`.
+
+use enums::terminal::{decode_all, TerminalDecoder};
+
+fn main() {
+    let samples: [&[u8]; 5] = [
+        "héllo 🦀\r".as_bytes(),
+        b"\x1b[A\x1b[1;5A\x1b[3~\x1bOP",
+        b"\x1b[<0;21;81M\x1b[<0;21;81m",
+        b"\x1b[200~line one\r\nline \x1b[A two\x1b[201~!",
+        b"a\xffb",
+    ];
+    for bytes in samples {
+        println!("{:?} => {:?}", String::from_utf8_lossy(bytes), decode_all(bytes));
+    }
+
+    // A sequence cut short between reads waits for the rest.
+    let mut decoder = TerminalDecoder::new();
+    println!("first read: {:?}", decoder.feed(b"a\x1b[1;"));
+    println!("second read: {:?}", decoder.feed(b"5C"));
+}
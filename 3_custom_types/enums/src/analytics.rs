@@ -0,0 +1,301 @@
+// Summaries of an event log: how many of each kind of event there were, how fast the
+// user typed, how long their pastes were, how long they stayed on each page, and where
+// they clicked, binned into a heatmap that prints as a grid of characters.
+//
+// Events are added one at a time with the time they happened, if it's known. Logs
+// without timestamps still give counts, paste lengths and clicks, but no typing speed
+// or time on page.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::session::Recorded;
+use crate::web_event::{EventKind, WebEvent};
+
+// A longer gap between two key presses is a pause, not slow typing, and doesn't count
+// towards the typing speed.
+pub const TYPING_PAUSE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    // Indexed in `EventKind::all()` order.
+    counts: [u64; 5],
+    last_key: Option<Duration>,
+    typing_keys: u64,
+    typing_time: Duration,
+    paste_chars: u64,
+    page_loaded: Option<Duration>,
+    visits: Vec<Duration>,
+    clicks: Vec<(i64, i64)>,
+}
+
+fn index(kind: EventKind) -> usize {
+    EventKind::all().position(|k| k == kind).expect("every kind is in `all`")
+}
+
+impl SessionStats {
+    pub fn new() -> SessionStats {
+        SessionStats::default()
+    }
+
+    pub fn from_session<I: IntoIterator<Item = Recorded>>(session: I) -> SessionStats {
+        let mut stats = SessionStats::new();
+        for recorded in session {
+            stats.add(&recorded.event, Some(recorded.at));
+        }
+        stats
+    }
+
+    pub fn from_events<'a, I: IntoIterator<Item = &'a WebEvent>>(events: I) -> SessionStats {
+        let mut stats = SessionStats::new();
+        for event in events {
+            stats.add(event, None);
+        }
+        stats
+    }
+
+    // Events with times should come in order. One that claims to be earlier than the
+    // event before it is treated as happening at the same time.
+    pub fn add(&mut self, event: &WebEvent, at: Option<Duration>) {
+        self.counts[index(event.kind())] += 1;
+        match event {
+            WebEvent::KeyPress(_) => {
+                if let (Some(at), Some(last)) = (at, self.last_key) {
+                    let gap = at.saturating_sub(last);
+                    if gap <= TYPING_PAUSE {
+                        self.typing_keys += 1;
+                        self.typing_time += gap;
+                    }
+                }
+                // A key without a time breaks the run, since there's no telling how
+                // long it took.
+                self.last_key = at;
+            },
+            WebEvent::Paste(text) => self.paste_chars += text.chars().count() as u64,
+            // A second load without an unload in between starts the visit over.
+            WebEvent::PageLoad => self.page_loaded = at,
+            WebEvent::PageUnload => {
+                if let (Some(at), Some(loaded)) = (at, self.page_loaded.take()) {
+                    self.visits.push(at.saturating_sub(loaded));
+                }
+            },
+            WebEvent::Click { x, y } => self.clicks.push((*x, *y)),
+        }
+    }
+
+    pub fn count(&self, kind: EventKind) -> u64 {
+        self.counts[index(kind)]
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // Key presses per minute while actually typing, or `None` without at least two
+    // timed key presses close enough together.
+    pub fn keys_per_minute(&self) -> Option<f64> {
+        if self.typing_time.is_zero() {
+            return None;
+        }
+        Some(self.typing_keys as f64 * 60.0 / self.typing_time.as_secs_f64())
+    }
+
+    // In characters.
+    pub fn average_paste_length(&self) -> Option<f64> {
+        match self.count(EventKind::Paste) {
+            0 => None,
+            pastes => Some(self.paste_chars as f64 / pastes as f64),
+        }
+    }
+
+    // How long each page stayed loaded, from a `PageLoad` to the next `PageUnload`.
+    // A page still open at the end of the log isn't included.
+    pub fn page_visits(&self) -> &[Duration] {
+        &self.visits
+    }
+
+    pub fn total_dwell_time(&self) -> Duration {
+        self.visits.iter().sum()
+    }
+
+    pub fn clicks(&self) -> &[(i64, i64)] {
+        &self.clicks
+    }
+
+    // A heatmap just big enough to hold every click, or `None` if there weren't any
+    // or the size isn't one `Heatmap::checked_new` accepts.
+    pub fn heatmap(&self, columns: usize, rows: usize) -> Option<Heatmap> {
+        Heatmap::fit(&self.clicks, columns, rows)
+    }
+}
+
+// The most cells a heatmap can have: far more than fit on any screen, and small enough
+// (8 MB of counts) that asking for one can't exhaust memory.
+pub const MAX_CELLS: usize = 1 << 20;
+
+// Click counts binned into a grid of `columns` by `rows` cells. As on screen, `y`
+// grows downwards, so the first row holds the smallest `y`s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    x: (i64, i64),
+    y: (i64, i64),
+    columns: usize,
+    rows: usize,
+    cells: Vec<u64>,
+    // Clicks outside the bounds, which aren't in any cell.
+    outside: u64,
+}
+
+// From empty to busiest.
+const ASCII_RAMP: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+// Dark grey to white in the 256-colour palette.
+const ANSI_RAMP: &[u8] = &[236, 238, 240, 242, 244, 246, 248, 250, 252, 255];
+
+// Which of `cells` cells along one axis `value` falls in, or `None` if it's outside
+// `min..=max`. Done in `i128` so the full range of `i64` works.
+fn bin(value: i64, (min, max): (i64, i64), cells: usize) -> Option<usize> {
+    if value < min || value > max {
+        return None;
+    }
+    let span = max as i128 - min as i128 + 1;
+    Some(((value as i128 - min as i128) * cells as i128 / span) as usize)
+}
+
+// The smallest and largest `x`, then the same for `y`.
+fn bounds(clicks: &[(i64, i64)]) -> Option<((i64, i64), (i64, i64))> {
+    let x = (clicks.iter().map(|c| c.0).min()?, clicks.iter().map(|c| c.0).max()?);
+    let y = (clicks.iter().map(|c| c.1).min()?, clicks.iter().map(|c| c.1).max()?);
+    Some((x, y))
+}
+
+impl Heatmap {
+    // Covers `x` and `y` inclusive. `None` if there are no cells, more than
+    // `MAX_CELLS` of them, or the bounds are backwards.
+    pub fn checked_new(x: (i64, i64), y: (i64, i64), columns: usize, rows: usize) -> Option<Heatmap> {
+        let cells = columns.checked_mul(rows).filter(|&cells| cells > 0 && cells <= MAX_CELLS)?;
+        if x.0 > x.1 || y.0 > y.1 {
+            return None;
+        }
+        Some(Heatmap { x, y, columns, rows, cells: vec![0; cells], outside: 0 })
+    }
+
+    pub fn new(x: (i64, i64), y: (i64, i64), columns: usize, rows: usize) -> Heatmap {
+        Heatmap::checked_new(x, y, columns, rows).expect("heatmap with no cells, too many cells or backwards bounds")
+    }
+
+    // `None` if there are no clicks, or for a size `checked_new` doesn't accept.
+    pub fn fit(clicks: &[(i64, i64)], columns: usize, rows: usize) -> Option<Heatmap> {
+        let (x, y) = bounds(clicks)?;
+        let mut heatmap = Heatmap::checked_new(x, y, columns, rows)?;
+        for &(x, y) in clicks {
+            heatmap.add(x, y);
+        }
+        Some(heatmap)
+    }
+
+    pub fn add(&mut self, x: i64, y: i64) {
+        match (bin(x, self.x, self.columns), bin(y, self.y, self.rows)) {
+            (Some(column), Some(row)) => self.cells[row * self.columns + column] += 1,
+            _ => self.outside += 1,
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> u64 {
+        assert!(column < self.columns && row < self.rows, "cell ({}, {}) is outside the heatmap", column, row);
+        self.cells[row * self.columns + column]
+    }
+
+    pub fn max(&self) -> u64 {
+        self.cells.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn outside(&self) -> u64 {
+        self.outside
+    }
+
+    // Where a count falls on a ramp of `steps` steps. Only empty cells get the first
+    // step, so a single click never disappears next to a busy cell.
+    fn level(count: u64, max: u64, steps: usize) -> usize {
+        match (count, max) {
+            (0, _) => 0,
+            (_, 1) => steps - 1,
+            (count, max) => 1 + ((count - 1) as u128 * (steps - 2) as u128 / (max - 1) as u128) as usize,
+        }
+    }
+
+    // One character per cell inside a frame, with `@` for the busiest cells.
+    pub fn render_ascii(&self) -> String {
+        let max = self.max();
+        let border = format!("+{}+\n", "-".repeat(self.columns));
+        let mut out = border.clone();
+        for row in self.cells.chunks(self.columns) {
+            out.push('|');
+            out.extend(row.iter().map(|&count| ASCII_RAMP[Heatmap::level(count, max, ASCII_RAMP.len())]));
+            out.push_str("|\n");
+        }
+        out.push_str(&border);
+        out
+    }
+
+    // Two coloured spaces per cell (so cells come out roughly square), for terminals.
+    pub fn render_ansi(&self) -> String {
+        let max = self.max();
+        let mut out = String::new();
+        for row in self.cells.chunks(self.columns) {
+            for &count in row {
+                let colour = ANSI_RAMP[Heatmap::level(count, max, ANSI_RAMP.len())];
+                out.push_str(&format!("\x1b[48;5;{}m  ", colour));
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+}
+
+impl fmt::Display for Heatmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render_ascii())
+    }
+}
+
+// A plain-text report, one fact per line. The heatmap isn't included, since how big
+// to make it is up to whoever prints it.
+impl fmt::Display for SessionStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "events: {}", self.total())?;
+        for kind in EventKind::all() {
+            writeln!(f, "  {:<12} {}", kind.tag(), self.count(kind))?;
+        }
+        match self.keys_per_minute() {
+            Some(speed) => writeln!(f, "typing: {:.0} keys per minute", speed)?,
+            None => writeln!(f, "typing: not enough timed key presses")?,
+        }
+        match self.average_paste_length() {
+            Some(length) => writeln!(f, "pastes: {:.1} characters on average", length)?,
+            None => writeln!(f, "pastes: none")?,
+        }
+        match self.visits.len() {
+            0 => writeln!(f, "pages: no complete visits")?,
+            visits => writeln!(
+                f,
+                "pages: {} visit(s), {:.1?} in total, {:.1?} on average",
+                visits,
+                self.total_dwell_time(),
+                self.total_dwell_time() / visits as u32
+            )?,
+        }
+        write!(f, "clicks: {}", self.clicks.len())?;
+        if let Some((x, y)) = bounds(&self.clicks) {
+            write!(f, " between ({}, {}) and ({}, {})", x.0, y.0, x.1, y.1)?;
+        }
+        writeln!(f)
+    }
+}
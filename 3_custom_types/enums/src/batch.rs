@@ -0,0 +1,74 @@
+// Evaluating one line of a batch file, like `add 3 4` or `divide 10 0`.
+// The `calc-batch` binary handles the files and output formats, this module only
+// knows how to turn a line into a result or a typed error.
+
+use std::fmt;
+
+use crate::operations::{Operations, ParseOperationError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineError {
+    UnknownOperation(ParseOperationError),
+    // The operand as written, e.g. `"3.5"` or `"ten"`.
+    InvalidOperand(String),
+    // How many operands the line had, when it wasn't two.
+    WrongArity(usize),
+    DivisionByZero,
+    Overflow(Operations),
+    // The line's bytes aren't UTF-8, so there's nothing to evaluate.
+    InvalidUtf8,
+}
+
+impl LineError {
+    // A stable snake_case name for reports, so scripts don't have to match on messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LineError::UnknownOperation(_) => "unknown_operation",
+            LineError::InvalidOperand(_) => "invalid_operand",
+            LineError::WrongArity(_) => "wrong_arity",
+            LineError::DivisionByZero => "division_by_zero",
+            LineError::Overflow(_) => "overflow",
+            LineError::InvalidUtf8 => "invalid_utf8",
+        }
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineError::UnknownOperation(e) => write!(f, "{}", e),
+            LineError::InvalidOperand(s) => write!(f, "`{}` is not an integer", s),
+            LineError::WrongArity(n) => write!(f, "expected 2 operands, found {}", n),
+            LineError::DivisionByZero => write!(f, "division by zero"),
+            LineError::Overflow(op) => write!(f, "{} overflowed", op.name()),
+            LineError::InvalidUtf8 => write!(f, "line is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for LineError {}
+
+// Returns `None` for lines with nothing to evaluate: blank ones and `#` comments.
+pub fn eval_line(line: &str) -> Option<Result<i64, LineError>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    Some(eval_words(line))
+}
+
+fn eval_words(line: &str) -> Result<i64, LineError> {
+    let mut words = line.split_whitespace();
+    // `eval_line` already skipped empty lines, so there's always a first word.
+    let op: Operations = words.next().unwrap_or_default().parse().map_err(LineError::UnknownOperation)?;
+    let operands: Vec<&str> = words.collect();
+    let [x, y] = operands[..] else {
+        return Err(LineError::WrongArity(operands.len()));
+    };
+    let parse = |s: &str| s.parse::<i64>().map_err(|_| LineError::InvalidOperand(s.to_owned()));
+    let (x, y) = (parse(x)?, parse(y)?);
+    if op == Operations::Divide && y == 0 {
+        return Err(LineError::DivisionByZero);
+    }
+    op.checked_run(x, y).ok_or(LineError::Overflow(op))
+}
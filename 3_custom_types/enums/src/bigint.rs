@@ -0,0 +1,710 @@
+// An arbitrary-precision integer, using only the standard library.
+//
+// The number is stored as a sign plus a magnitude. The magnitude is a `Vec<u32>` of
+// "limbs" in little-endian order, so `mag[0]` is the lowest 32 bits. Every limb is a
+// digit in base 2^32, the same way every element of `[1, 2, 3]` would be a digit of 321
+// in base 10 if you read it backwards.
+//
+// Two rules keep the representation unique, so `#[derive(PartialEq)]` is correct:
+// there are never zero limbs at the end of `mag`, and zero is never negative.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+use crate::operations::{Operand, Operations};
+
+// Below this many limbs (in the shorter operand) schoolbook multiplication is faster
+// than Karatsuba's extra additions and allocations.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    mag: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    Empty,
+    InvalidDigit(char),
+    InvalidRadix(u32),
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigIntError::InvalidDigit(c) => write!(f, "invalid digit '{}'", c),
+            ParseBigIntError::InvalidRadix(radix) => write!(f, "radix {} is not in 2..=36", radix),
+        }
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::from(1u32)
+    }
+
+    // Builds a `BigInt` and restores the two representation rules from the top of the file.
+    fn from_parts(negative: bool, mut mag: Vec<u32>) -> BigInt {
+        while mag.last() == Some(&0) {
+            mag.pop();
+        }
+        let negative = negative && !mag.is_empty();
+        BigInt { negative, mag }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt { negative: false, mag: self.mag.clone() }
+    }
+
+    // -1, 0 or 1, like `i32::signum`.
+    pub fn signum(&self) -> i32 {
+        match (self.is_zero(), self.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        }
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.mag.last() {
+            Some(top) => self.mag.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    // The value as `m * 2^e`, with `m` holding just the top 64 bits of the magnitude,
+    // so it stays finite however big the number is. Good for dividing two huge numbers
+    // without both ends going to infinity first.
+    pub fn to_f64_exp(&self) -> (f64, u64) {
+        let shift = self.bits().saturating_sub(64);
+        let top = shr_bits(&self.mag[(shift / 32) as usize..], (shift % 32) as u32);
+        let m = top.iter().rev().fold(0u128, |acc, &limb| (acc << 32) | limb as u128) as f64;
+        (if self.negative { -m } else { m }, shift)
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.mag.first().is_none_or(|low| low & 1 == 0)
+    }
+
+    // Truncating division, the same as `/` and `%` on primitive integers: the quotient
+    // rounds toward zero and the remainder takes the sign of `self`.
+    // Returns `None` when `divisor` is zero.
+    pub fn checked_div_rem(&self, divisor: &BigInt) -> Option<(BigInt, BigInt)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (q, r) = div_rem_mag(&self.mag, &divisor.mag);
+        Some((BigInt::from_parts(self.negative != divisor.negative, q), BigInt::from_parts(self.negative, r)))
+    }
+
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        self.checked_div_rem(divisor).expect("attempt to divide by zero")
+    }
+
+    // Exponentiation by squaring: `x^13 = x^8 * x^4 * x^1`, one squaring per bit of `exp`.
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative || self.mag.len() > 4 {
+            return None;
+        }
+        Some(self.mag.iter().rev().fold(0u128, |acc, &limb| (acc << 32) | limb as u128))
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let mag = BigInt { negative: false, mag: self.mag.clone() }.to_u128()?;
+        if self.negative {
+            // `i128::MIN` has no positive counterpart, so it's checked separately.
+            if mag == i128::MIN.unsigned_abs() { Some(i128::MIN) } else { i128::try_from(mag).ok().map(|m| -m) }
+        } else {
+            i128::try_from(mag).ok()
+        }
+    }
+
+    // Accepts an optional leading `+` or `-` followed by digits in `radix`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        if !(2..=36).contains(&radix) {
+            return Err(ParseBigIntError::InvalidRadix(radix));
+        }
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        // Rather than multiplying the whole number by `radix` once per digit, digits are
+        // gathered into chunks that fit a `u32` and folded in one chunk at a time.
+        let (chunk_len, _) = chunk_size(radix);
+        let chars: Vec<char> = digits.chars().collect();
+        let mut mag = Vec::new();
+        // The first chunk takes the leftover digits, so every later chunk is full size.
+        let first = match chars.len() % chunk_len {
+            0 => chunk_len,
+            n => n,
+        };
+        let mut start = 0;
+        let mut end = first;
+        while start < chars.len() {
+            let mut chunk = 0u32;
+            for &c in &chars[start..end] {
+                let digit = c.to_digit(radix).ok_or(ParseBigIntError::InvalidDigit(c))?;
+                chunk = chunk * radix + digit;
+            }
+            mul_small_add(&mut mag, radix.pow((end - start) as u32), chunk);
+            start = end;
+            end += chunk_len;
+        }
+        Ok(BigInt::from_parts(negative, mag))
+    }
+
+    // Lowercase digits, with a leading `-` for negative numbers and no radix prefix.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix {} is not in 2..=36", radix);
+        let mut out = String::new();
+        if self.negative {
+            out.push('-');
+        }
+        out.push_str(&self.mag_to_str_radix(radix));
+        out
+    }
+
+    fn mag_to_str_radix(&self, radix: u32) -> String {
+        if self.is_zero() {
+            return "0".to_owned();
+        }
+        // Peel off chunks of digits from the bottom by repeatedly dividing by `radix^chunk_len`.
+        let (chunk_len, chunk_divisor) = chunk_size(radix);
+        let mut mag = self.mag.clone();
+        let mut chunks = Vec::new();
+        while !mag.is_empty() {
+            chunks.push(div_small(&mut mag, chunk_divisor));
+        }
+        let mut out = String::new();
+        for (i, chunk) in chunks.iter().rev().enumerate() {
+            let digits = u32_to_str_radix(*chunk, radix);
+            // Every chunk but the most significant one needs its leading zeros.
+            if i > 0 {
+                out.extend(std::iter::repeat_n('0', chunk_len - digits.len()));
+            }
+            out.push_str(&digits);
+        }
+        out
+    }
+}
+
+// The largest `n` such that `radix^n` fits in a `u32`, along with `radix^n` itself.
+fn chunk_size(radix: u32) -> (usize, u32) {
+    let mut len = 1;
+    let mut power = radix;
+    while let Some(next) = power.checked_mul(radix) {
+        power = next;
+        len += 1;
+    }
+    (len, power)
+}
+
+fn u32_to_str_radix(mut n: u32, radix: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(std::char::from_digit(n % radix, radix).unwrap());
+        n /= radix;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+// ---------------------------------------------------------------- Magnitude arithmetic -----------------------------------------------
+// These work on the bare limb slices and ignore signs. Inputs may have zero limbs at the
+// end (Karatsuba's halves often do), outputs may too; `BigInt::from_parts` trims them.
+
+fn trimmed(a: &[u32]) -> &[u32] {
+    let len = a.iter().rposition(|&limb| limb != 0).map_or(0, |i| i + 1);
+    &a[..len]
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    let (a, b) = (trimmed(a), trimmed(b));
+    // More limbs means bigger, otherwise compare from the most significant limb down.
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &limb) in long.iter().enumerate() {
+        let sum = limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        out.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+// `a - b`, which must not be negative.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let b = trimmed(b);
+    debug_assert!(cmp_mag(a, b) != Ordering::Less, "sub_mag would go negative");
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        out.push(diff as u32);
+    }
+    out
+}
+
+// Adds `src << (32 * shift)` into `out` in place. `out` must be long enough for the result.
+fn add_into(out: &mut [u32], src: &[u32], shift: usize) {
+    let mut carry = 0u64;
+    let mut i = shift;
+    for &limb in src {
+        let sum = out[i] as u64 + limb as u64 + carry;
+        out[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+    while carry > 0 {
+        let sum = out[i] as u64 + carry;
+        out[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = (trimmed(a), trimmed(b));
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return schoolbook_mul(a, b);
+    }
+    karatsuba_mul(a, b)
+}
+
+fn schoolbook_mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            // At most (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1, so this never overflows.
+            let t = x as u64 * y as u64 + out[i + j] as u64 + carry;
+            out[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    out
+}
+
+// Split both numbers at `m` limbs, `a = a1 * B^m + a0` and `b = b1 * B^m + b0`. Then
+//   a * b = z2 * B^2m + z1 * B^m + z0
+// where z0 = a0 * b0, z2 = a1 * b1, and z1 = (a0 + a1)(b0 + b1) - z0 - z2.
+// That's three half-size multiplications instead of the four the schoolbook way needs.
+fn karatsuba_mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let m = a.len().max(b.len()) / 2;
+    let split = |x: &'_ [u32]| -> (Vec<u32>, Vec<u32>) {
+        if x.len() <= m { (x.to_vec(), Vec::new()) } else { (x[..m].to_vec(), x[m..].to_vec()) }
+    };
+    let (a0, a1) = split(a);
+    let (b0, b1) = split(b);
+    let z0 = mul_mag(&a0, &b0);
+    let z2 = mul_mag(&a1, &b1);
+    let z1 = mul_mag(&add_mag(&a0, &a1), &add_mag(&b0, &b1));
+    let z1 = sub_mag(&sub_mag(&z1, &z0), &z2);
+
+    let mut out = vec![0u32; a.len() + b.len() + 1];
+    add_into(&mut out, trimmed(&z0), 0);
+    add_into(&mut out, trimmed(&z1), m);
+    add_into(&mut out, trimmed(&z2), 2 * m);
+    out
+}
+
+// `mag = mag * factor + addend`, in place.
+fn mul_small_add(mag: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in mag.iter_mut() {
+        let t = *limb as u64 * factor as u64 + carry;
+        *limb = t as u32;
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        mag.push(carry as u32);
+    }
+}
+
+// `mag /= divisor` in place, returning the remainder and trimming the quotient.
+fn div_small(mag: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in mag.iter_mut().rev() {
+        let cur = (rem << 32) | *limb as u64;
+        *limb = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    rem as u32
+}
+
+// Long division of magnitudes, Knuth's algorithm D (TAOCP vol. 2, 4.3.1).
+// `v` must not be zero.
+fn div_rem_mag(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let (u, v) = (trimmed(u), trimmed(v));
+    if cmp_mag(u, v) == Ordering::Less {
+        return (Vec::new(), u.to_vec());
+    }
+    if v.len() == 1 {
+        let mut q = u.to_vec();
+        let r = div_small(&mut q, v[0]);
+        return (q, vec![r]);
+    }
+
+    // Shift both so the divisor's top limb has its high bit set. That keeps the
+    // per-limb quotient guesses below off by at most two.
+    let shift = v[v.len() - 1].leading_zeros();
+    let vn = shl_bits(v, shift);
+    // The dividend gets one extra limb so the top of the running remainder always exists.
+    let mut un = shl_bits(u, shift);
+    un.resize(u.len() + 1, 0);
+
+    let n = vn.len();
+    let m = u.len() - n;
+    let base = 1u64 << 32;
+    let mut q = vec![0u32; m + 1];
+
+    for j in (0..=m).rev() {
+        // Guess the next quotient limb from the top two limbs of the running remainder...
+        let num = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+        let mut qhat = num / vn[n - 1] as u64;
+        let mut rhat = num % vn[n - 1] as u64;
+        // ...and correct the guess using the third limb.
+        while qhat >= base || qhat * vn[n - 2] as u64 > ((rhat << 32) | un[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u64;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // Subtract `qhat * vn` from the running remainder.
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let p = qhat * vn[i] as u64;
+            let t = un[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+            un[i + j] = t as u32;
+            borrow = (p >> 32) as i64 - (t >> 32);
+        }
+        let t = un[j + n] as i64 - borrow;
+        un[j + n] = t as u32;
+
+        // The guess can still be one too big, in which case the subtraction went
+        // negative and the divisor gets added back once.
+        if t < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = un[i + j] as u64 + vn[i] as u64 + carry;
+                un[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+        q[j] = qhat as u32;
+    }
+
+    let r = shr_bits(&un[..n], shift);
+    (q, r)
+}
+
+fn shl_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u32;
+    for &limb in a {
+        out.push((limb << shift) | carry);
+        carry = limb >> (32 - shift);
+    }
+    if carry > 0 {
+        out.push(carry);
+    }
+    out
+}
+
+fn shr_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+    let mut out = vec![0u32; a.len()];
+    for i in 0..a.len() {
+        let high = a.get(i + 1).map_or(0, |&next| next << (32 - shift));
+        out[i] = (a[i] >> shift) | high;
+    }
+    out
+}
+
+// ---------------------------------------------------------------- Conversions --------------------------------------------------------
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(n: $t) -> BigInt {
+                    let mut n = n as u128;
+                    let mut mag = Vec::new();
+                    while n > 0 {
+                        mag.push(n as u32);
+                        n >>= 32;
+                    }
+                    BigInt::from_parts(false, mag)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(n: $t) -> BigInt {
+                    // `unsigned_abs` handles `MIN`, where `abs` would overflow.
+                    let mut big = BigInt::from(n.unsigned_abs() as u128);
+                    big.negative = n < 0;
+                    big
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+// Decimal by default. A `0x` or `0b` prefix (after the sign) switches to hex or binary.
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'-') | Some(b'+') => s.split_at(1),
+            _ => ("", s),
+        };
+        let (radix, digits) = if let Some(hex) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+            (16, hex)
+        } else if let Some(bin) = rest.strip_prefix("0b").or_else(|| rest.strip_prefix("0B")) {
+            (2, bin)
+        } else {
+            (10, rest)
+        };
+        // A second sign after the prefix (`0x-ff`) isn't a number.
+        if let Some(c) = digits.chars().next().filter(|c| *c == '-' || *c == '+') {
+            return Err(ParseBigIntError::InvalidDigit(c));
+        }
+        let big = BigInt::from_str_radix(digits, radix)?;
+        Ok(if sign == "-" { -big } else { big })
+    }
+}
+
+// `pad_integral` takes care of `{:+}`, `{:#x}`, widths and zero padding for us,
+// so these behave just like they do for the primitive integers.
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.mag_to_str_radix(10))
+    }
+}
+
+impl fmt::LowerHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0x", &self.mag_to_str_radix(16))
+    }
+}
+
+impl fmt::UpperHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0x", &self.mag_to_str_radix(16).to_uppercase())
+    }
+}
+
+impl fmt::Binary for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0b", &self.mag_to_str_radix(2))
+    }
+}
+
+// ---------------------------------------------------------------- Comparison ---------------------------------------------------------
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.mag, &other.mag),
+            // Both negative: the bigger magnitude is the smaller number.
+            (true, true) => cmp_mag(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// ---------------------------------------------------------------- Operators ----------------------------------------------------------
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.mag)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.mag, &other.mag));
+        }
+        // Different signs: subtract the smaller magnitude from the bigger one,
+        // and the result takes the sign of the bigger one.
+        match cmp_mag(&self.mag, &other.mag) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_mag(&other.mag, &self.mag)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.mag, &other.mag)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        // a - b = a + (-b). Flipping the sign is free, it only copies the limbs.
+        self + &(-other)
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mul_mag(&self.mag, &other.mag))
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+// The owned versions just borrow and forward, so `a + b` works as well as `&a + &b`.
+macro_rules! forward_owned_binop {
+    ($($trait:ident $method:ident),*) => {
+        $(
+            impl $trait for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, other: BigInt) -> BigInt {
+                    (&self).$method(&other)
+                }
+            }
+
+            impl $trait<&BigInt> for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, other: &BigInt) -> BigInt {
+                    (&self).$method(other)
+                }
+            }
+
+            impl $trait<BigInt> for &BigInt {
+                type Output = BigInt;
+
+                fn $method(self, other: BigInt) -> BigInt {
+                    self.$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned_binop!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Operand for BigInt {
+    fn apply(op: Operations, x: BigInt, y: BigInt) -> Option<BigInt> {
+        match op {
+            Operations::Add => Some(x + y),
+            Operations::Subtract => Some(x - y),
+            Operations::Multiply => Some(x * y),
+            // The only thing that can go wrong with a `BigInt` is dividing by zero.
+            Operations::Divide => x.checked_div_rem(&y).map(|(q, _)| q),
+        }
+    }
+}
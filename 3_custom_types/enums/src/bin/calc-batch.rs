@@ -0,0 +1,153 @@
+// Evaluates a file of operations, one per line:
+//
+//   add 3 4
+//   divide 10 0
+//   # comments and blank lines are skipped
+//
+// Usage: calc-batch [--format csv|json] [--output FILE] INPUT
+//
+// Results go to stdout (or FILE) as CSV or JSON lines. Failed lines don't stop the run,
+// they're reported in the output and again in a summary on stderr. The exit code is 0
+// if every line worked (or for `--help`), 1 if any line failed and 2 if the arguments
+// were wrong or the file couldn't be read.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+use enums::batch::{eval_line, LineError};
+use enums::json::quote;
+
+#[derive(Clone, Copy)]
+enum Format {
+    Csv,
+    Json,
+}
+
+struct Args {
+    format: Format,
+    output: Option<String>,
+    input: String,
+}
+
+const USAGE: &str = "usage: calc-batch [--format csv|json] [--output FILE] INPUT (use - for stdin)";
+
+// `Ok(None)` when asked for `--help`.
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut format = Format::Csv;
+    let mut output = None;
+    let mut input = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("csv") => Format::Csv,
+                    Some("json") => Format::Json,
+                    other => return Err(format!("unknown format {:?}, expected csv or json", other.unwrap_or(""))),
+                }
+            },
+            "--output" => output = Some(args.next().ok_or("--output needs a file name")?),
+            "-h" | "--help" => return Ok(None),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("unexpected argument \"{}\"", arg)),
+        }
+    }
+    Ok(Some(Args { format, output, input: input.ok_or(USAGE)? }))
+}
+
+// Quotes a CSV field only when it has to, doubling any quotes inside it.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_owned() }
+}
+
+fn write_record(out: &mut dyn Write, format: Format, line_no: usize, input: &str, result: &Result<i64, LineError>) -> io::Result<()> {
+    match (format, result) {
+        (Format::Csv, Ok(value)) => writeln!(out, "{},{},{},", line_no, csv_field(input), value),
+        (Format::Csv, Err(e)) => writeln!(out, "{},{},,{}", line_no, csv_field(input), e.kind()),
+        (Format::Json, Ok(value)) => {
+            writeln!(out, "{{\"line\":{},\"input\":{},\"result\":{}}}", line_no, quote(input), value)
+        },
+        (Format::Json, Err(e)) => writeln!(
+            out,
+            "{{\"line\":{},\"input\":{},\"error\":{},\"message\":{}}}",
+            line_no,
+            quote(input),
+            quote(e.kind()),
+            quote(&e.to_string())
+        ),
+    }
+}
+
+fn run(args: &Args) -> io::Result<Vec<(usize, LineError)>> {
+    let mut input: Box<dyn BufRead> = match args.input.as_str() {
+        "-" => Box::new(io::stdin().lock()),
+        path => {
+            let file = File::open(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+            Box::new(BufReader::new(file))
+        },
+    };
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    if let Format::Csv = args.format {
+        writeln!(out, "line,input,result,error")?;
+    }
+    let mut ok = 0;
+    let mut failures = Vec::new();
+    // `read_until` rather than `lines`, so a line that isn't UTF-8 is reported as a
+    // failed line instead of ending the run.
+    let mut buf = Vec::new();
+    // Line numbers start at 1, like in an editor.
+    let mut line_no = 0;
+    loop {
+        buf.clear();
+        if input.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        line_no += 1;
+        let result = match std::str::from_utf8(&buf) {
+            Ok(line) => eval_line(line),
+            Err(_) => Some(Err(LineError::InvalidUtf8)),
+        };
+        let Some(result) = result else { continue };
+        write_record(&mut out, args.format, line_no, String::from_utf8_lossy(&buf).trim(), &result)?;
+        match result {
+            Ok(_) => ok += 1,
+            Err(e) => failures.push((line_no, e)),
+        }
+    }
+    out.flush()?;
+
+    eprintln!("{} succeeded, {} failed", ok, failures.len());
+    Ok(failures)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        },
+    };
+    match run(&args) {
+        Ok(failures) if failures.is_empty() => ExitCode::SUCCESS,
+        Ok(failures) => {
+            for (line_no, e) in &failures {
+                eprintln!("  line {}: {} ({})", line_no, e, e.kind());
+            }
+            ExitCode::from(1)
+        },
+        Err(e) => {
+            eprintln!("calc-batch: {}", e);
+            ExitCode::from(2)
+        },
+    }
+}
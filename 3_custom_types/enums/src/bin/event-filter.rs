@@ -0,0 +1,115 @@
+// Prints the events in a JSON Lines log (plain or a recorded session) that match a
+// filter, in the same format they came in.
+//
+// Usage: event-filter [--count] FILTER [FILE]
+//
+//   event-filter 'type == click && x > 100' session.jsonl
+//   event-filter --count 'paste contains "http"' < events.jsonl
+//
+// Lines that can't be read are reported on stderr and skipped. The exit code is 0 if
+// every line was read (or for `--help`), 1 if some weren't and 2 for a bad filter or
+// an unreadable file.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use enums::filter::Filter;
+use enums::json::Value;
+use enums::jsonl::{self, JsonLines, ParseErrorKind};
+use enums::session::{self, Recorded};
+use enums::WebEvent;
+
+const USAGE: &str = "usage: event-filter [--count] FILTER [FILE] (reads stdin without FILE or with -)";
+
+// Session lines have a timestamp, plain event lines don't.
+fn decode(value: &Value) -> Result<(Option<Duration>, WebEvent), ParseErrorKind> {
+    if value.get("t_us").is_some() {
+        let recorded = session::from_value(value)?;
+        Ok((Some(recorded.at), recorded.event))
+    } else {
+        Ok((None, jsonl::from_value(value)?))
+    }
+}
+
+fn encode(at: Option<Duration>, event: WebEvent) -> String {
+    match at {
+        Some(at) => session::to_value(&Recorded { at, event }).to_string(),
+        None => jsonl::to_json(&event),
+    }
+}
+
+// Shows the filter with a caret under the problem.
+fn report_filter_error(filter: &str, at: usize, message: &str) {
+    let column = filter[..at].chars().count();
+    eprintln!("event-filter: invalid filter: {}", message);
+    eprintln!("  {}", filter);
+    eprintln!("  {}^", " ".repeat(column));
+}
+
+fn main() -> ExitCode {
+    let mut count_only = false;
+    let mut positional = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "-c" | "--count" => count_only = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            },
+            _ => positional.push(arg),
+        }
+    }
+    let (filter_text, path) = match &positional[..] {
+        [filter] => (filter, "-"),
+        [filter, path] => (filter, path.as_str()),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        },
+    };
+    let filter = match Filter::parse(filter_text) {
+        Ok(filter) => filter,
+        Err(e) => {
+            report_filter_error(filter_text, e.at, &e.kind.to_string());
+            return ExitCode::from(2);
+        },
+    };
+
+    let input: Box<dyn BufRead> = match path {
+        "-" => Box::new(io::stdin().lock()),
+        path => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("event-filter: {}: {}", path, e);
+                return ExitCode::from(2);
+            },
+        },
+    };
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut matched = 0;
+    let mut bad_lines = 0;
+    for entry in JsonLines::with_decoder(input, decode) {
+        match entry {
+            Ok((at, event)) if filter.matches_at(&event, at) => {
+                matched += 1;
+                if !count_only && writeln!(out, "{}", encode(at, event)).is_err() {
+                    // Most likely a closed pipe, like `| head`.
+                    return ExitCode::SUCCESS;
+                }
+            },
+            Ok(_) => {},
+            Err(e) => {
+                bad_lines += 1;
+                eprintln!("event-filter: {}", e);
+            },
+        }
+    }
+    if count_only {
+        writeln!(out, "{}", matched).ok();
+    }
+    out.flush().ok();
+    if bad_lines > 0 { ExitCode::from(1) } else { ExitCode::SUCCESS }
+}
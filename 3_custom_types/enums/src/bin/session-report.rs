@@ -0,0 +1,110 @@
+// Prints a summary of a JSON Lines event log (plain or a recorded session): event
+// counts, typing speed, paste lengths, time on page and a heatmap of the clicks.
+//
+// Usage: session-report [--ansi] [--size COLUMNSxROWS] [FILE]
+//
+//   session-report session.jsonl
+//   session-report --ansi --size 60x20 < session.jsonl
+//
+// Lines that can't be read are reported on stderr and skipped. The exit code is 0 if
+// every line was read (or for `--help`), 1 if some weren't and 2 for bad arguments or
+// an unreadable file.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use enums::analytics::{SessionStats, MAX_CELLS};
+use enums::json::Value;
+use enums::jsonl::{self, JsonLines, ParseErrorKind};
+use enums::session;
+use enums::WebEvent;
+
+const USAGE: &str = "usage: session-report [--ansi] [--size COLUMNSxROWS] [FILE] (reads stdin without FILE or with -)";
+
+// Session lines have a timestamp, plain event lines don't.
+fn decode(value: &Value) -> Result<(Option<Duration>, WebEvent), ParseErrorKind> {
+    if value.get("t_us").is_some() {
+        let recorded = session::from_value(value)?;
+        Ok((Some(recorded.at), recorded.event))
+    } else {
+        Ok((None, jsonl::from_value(value)?))
+    }
+}
+
+// `Err` says what's wrong with it.
+fn parse_size(size: Option<&str>) -> Result<(usize, usize), String> {
+    let parse = |size: &str| -> Option<(usize, usize)> {
+        let (columns, rows) = size.split_once('x')?;
+        Some((columns.parse().ok()?, rows.parse().ok()?))
+    };
+    match size.and_then(parse) {
+        None | Some((0, _) | (_, 0)) => Err("--size needs COLUMNSxROWS, like 40x12".to_owned()),
+        Some((columns, rows)) if columns.checked_mul(rows).is_none_or(|cells| cells > MAX_CELLS) => {
+            Err(format!("--size {}x{} is too big, the most is {} cells", columns, rows, MAX_CELLS))
+        },
+        Some(size) => Ok(size),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut ansi = false;
+    let mut size = (40, 12);
+    let mut path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ansi" => ansi = true,
+            "--size" => match parse_size(args.next().as_deref()) {
+                Ok(parsed) => size = parsed,
+                Err(message) => {
+                    eprintln!("session-report: {}", message);
+                    return ExitCode::from(2);
+                },
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            },
+        }
+    }
+
+    let input: Box<dyn BufRead> = match path.as_deref().unwrap_or("-") {
+        "-" => Box::new(io::stdin().lock()),
+        path => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("session-report: {}: {}", path, e);
+                return ExitCode::from(2);
+            },
+        },
+    };
+
+    let mut stats = SessionStats::new();
+    let mut bad_lines = 0;
+    for entry in JsonLines::with_decoder(input, decode) {
+        match entry {
+            Ok((at, event)) => stats.add(&event, at),
+            Err(e) => {
+                bad_lines += 1;
+                eprintln!("session-report: {}", e);
+            },
+        }
+    }
+
+    print!("{}", stats);
+    if let Some(heatmap) = stats.heatmap(size.0, size.1) {
+        if ansi {
+            print!("{}", heatmap.render_ansi());
+        } else {
+            print!("{}", heatmap);
+        }
+    }
+    if bad_lines > 0 { ExitCode::from(1) } else { ExitCode::SUCCESS }
+}
@@ -0,0 +1,96 @@
+// Prints the `WebEvent`s your terminal produces as you type, paste and click.
+// Press q (or Ctrl+C) to quit.
+//
+// Usage: term-events [--json]
+//
+// Raw mode is switched on with `stty`, so this works on Linux and macOS terminals
+// without pulling in a terminal library. With input that isn't a terminal (a pipe or a
+// file), it just decodes the bytes it's given.
+
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use enums::jsonl::to_json;
+use enums::terminal::{TerminalDecoder, DISABLE_REPORTING, ENABLE_REPORTING};
+use enums::web_event::{describe, keys};
+use enums::WebEvent;
+
+// How long a lone ESC waits for the rest of a sequence before it counts as Escape.
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(25);
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty").args(args).stdin(Stdio::inherit()).stderr(Stdio::null()).status().is_ok_and(|s| s.success())
+}
+
+// Puts the terminal back the way it was, even if something panics.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> Option<RawMode> {
+        let output = Command::new("stty").arg("-g").stdin(Stdio::inherit()).stderr(Stdio::null()).output().ok()?;
+        let saved = String::from_utf8(output.stdout).ok()?.trim().to_owned();
+        if !output.status.success() || !stty(&["raw", "-echo"]) {
+            return None;
+        }
+        print!("{}", ENABLE_REPORTING);
+        io::stdout().flush().ok();
+        Some(RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("{}", DISABLE_REPORTING);
+        io::stdout().flush().ok();
+        stty(&[&self.saved]);
+    }
+}
+
+fn main() {
+    let json = std::env::args().skip(1).any(|arg| arg == "--json");
+    let raw = RawMode::enable();
+    // Raw mode turns off the terminal's own newline handling, so lines need a `\r`.
+    let newline = if raw.is_some() { "\r\n" } else { "\n" };
+    if raw.is_some() {
+        print!("type, paste or click; q quits{}", newline);
+    }
+
+    // Reading happens on its own thread so the main one can notice when input stops
+    // arriving, which is what settles a lone ESC.
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut buf = [0; 1024];
+        while let Ok(n @ 1..) = stdin.read(&mut buf) {
+            if tx.send(buf[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut decoder = TerminalDecoder::new();
+    let mut out = io::stdout().lock();
+    loop {
+        let (events, done) = match rx.recv_timeout(ESCAPE_TIMEOUT) {
+            Ok(bytes) => (decoder.feed(&bytes), false),
+            Err(RecvTimeoutError::Timeout) => (decoder.flush(), false),
+            Err(RecvTimeoutError::Disconnected) => (decoder.flush(), true),
+        };
+        for event in events {
+            let line = if json { to_json(&event) } else { describe(&event) };
+            write!(out, "{}{}", line, newline).ok();
+            if raw.is_some() && matches!(event, WebEvent::KeyPress(c) if c == 'q' || Some(c) == keys::ctrl('c')) {
+                return;
+            }
+        }
+        out.flush().ok();
+        if done {
+            return;
+        }
+    }
+}
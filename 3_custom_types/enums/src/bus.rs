@@ -0,0 +1,312 @@
+// A publish/subscribe bus for `WebEvent`s that works across threads.
+//
+// Any thread holding an `EventBus` (it's cheap to clone) can publish, and every
+// `Subscriber` gets its own copy of each event in its own bounded queue, so a slow
+// consumer only ever holds up itself, or publishers if its policy is `Block`. What
+// happens when a queue is full is up to each subscriber:
+//
+//   - `Block` makes the publisher wait for room, so nothing is lost
+//   - `DropOldest` throws away the oldest queued event to make room, for consumers
+//     that only care about what's recent
+//   - `DropNewest` throws away the event being published, keeping the queue as it is
+//
+// `shutdown` stops new events from being published, wakes up anyone waiting, and lets
+// subscribers finish what's already queued: `recv` keeps returning events until the
+// queue is empty and only then returns `None`.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::web_event::WebEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Block,
+    DropOldest,
+    DropNewest,
+}
+
+// Counts since the bus (or subscriber) was created. For a subscriber, `published` is
+// how many events were offered to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub published: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    published: AtomicU64,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+}
+
+fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+impl Counters {
+    fn snapshot(&self) -> Metrics {
+        Metrics {
+            published: self.published.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusClosed;
+
+impl fmt::Display for BusClosed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the event bus has been shut down")
+    }
+}
+
+impl std::error::Error for BusClosed {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    // Nothing queued right now (or before the timeout ran out).
+    Empty,
+    // Shut down and everything queued has been received.
+    Closed,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecvError::Empty => write!(f, "no event available"),
+            RecvError::Closed => write!(f, "the event bus has been shut down"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+#[derive(Debug)]
+struct QueueState {
+    events: VecDeque<WebEvent>,
+    closed: bool,
+    // The `Subscriber` was dropped, so nobody will ever make room again.
+    abandoned: bool,
+}
+
+#[derive(Debug)]
+struct Queue {
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    overflow: Overflow,
+    counters: Counters,
+}
+
+impl Queue {
+    // Every change made under the lock is a single `VecDeque` call or flag, so even a
+    // poisoned lock guards a consistent queue and it's fine to carry on.
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Returns whether the event was queued.
+    fn push(&self, event: &WebEvent, bus: &Counters) -> bool {
+        let mut state = self.lock();
+        if state.closed || state.abandoned {
+            return false;
+        }
+        bump(&self.counters.published);
+        while state.events.len() >= self.capacity {
+            match self.overflow {
+                Overflow::Block => {
+                    state = self.not_full.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+                    if state.closed || state.abandoned {
+                        return false;
+                    }
+                },
+                Overflow::DropOldest => {
+                    state.events.pop_front();
+                    bump(&self.counters.dropped);
+                    bump(&bus.dropped);
+                },
+                Overflow::DropNewest => {
+                    bump(&self.counters.dropped);
+                    bump(&bus.dropped);
+                    return false;
+                },
+            }
+        }
+        state.events.push_back(event.clone());
+        drop(state);
+        self.not_empty.notify_one();
+        true
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    subscribers: Mutex<Vec<Arc<Queue>>>,
+    closed: AtomicBool,
+    counters: Counters,
+}
+
+impl Shared {
+    fn subscribers(&self) -> MutexGuard<'_, Vec<Arc<Queue>>> {
+        self.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EventBus {
+    shared: Arc<Shared>,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus::default()
+    }
+
+    // A new subscriber only sees events published after it subscribed.
+    pub fn subscribe(&self, capacity: usize, overflow: Overflow) -> Subscriber {
+        assert!(capacity > 0, "a subscriber's queue needs room for at least one event");
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState { events: VecDeque::with_capacity(capacity), closed: false, abandoned: false }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            overflow,
+            counters: Counters::default(),
+        });
+        let mut subscribers = self.shared.subscribers();
+        // Checked while holding the list, so `shutdown` can't slip in between and miss it.
+        if self.shared.closed.load(Ordering::SeqCst) {
+            queue.close();
+        } else {
+            subscribers.push(Arc::clone(&queue));
+        }
+        Subscriber { queue, bus: Arc::clone(&self.shared) }
+    }
+
+    // Hands the event to every subscriber and returns how many queued it, which can be
+    // fewer than there are subscribers if some dropped it. With a `Block` subscriber
+    // whose queue is full, this waits until there's room (or the bus shuts down).
+    pub fn publish(&self, event: WebEvent) -> Result<usize, BusClosed> {
+        if self.shared.closed.load(Ordering::SeqCst) {
+            return Err(BusClosed);
+        }
+        bump(&self.shared.counters.published);
+        // Copied out so that a blocked publisher doesn't keep others from subscribing.
+        let subscribers: Vec<Arc<Queue>> = self.shared.subscribers().clone();
+        Ok(subscribers.iter().filter(|queue| queue.push(&event, &self.shared.counters)).count())
+    }
+
+    pub fn shutdown(&self) {
+        let subscribers = self.shared.subscribers();
+        self.shared.closed.store(true, Ordering::SeqCst);
+        for queue in subscribers.iter() {
+            queue.close();
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::SeqCst)
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.subscribers().len()
+    }
+
+    // Totals across all subscribers, past and present: `delivered` and `dropped` count
+    // once per subscriber, so with three subscribers one event can be delivered three times.
+    pub fn metrics(&self) -> Metrics {
+        self.shared.counters.snapshot()
+    }
+}
+
+pub struct Subscriber {
+    queue: Arc<Queue>,
+    bus: Arc<Shared>,
+}
+
+impl Subscriber {
+    // Waits for the next event. `None` means the bus was shut down and there's nothing
+    // left in the queue.
+    pub fn recv(&self) -> Option<WebEvent> {
+        self.recv_until(None).ok()
+    }
+
+    pub fn try_recv(&self) -> Result<WebEvent, RecvError> {
+        self.recv_until(Some(Instant::now()))
+    }
+
+    // A timeout too long to be a point in time, like `Duration::MAX`, waits as long
+    // as `recv` does.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<WebEvent, RecvError> {
+        self.recv_until(Instant::now().checked_add(timeout))
+    }
+
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<WebEvent, RecvError> {
+        let mut state = self.queue.lock();
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                drop(state);
+                self.queue.not_full.notify_one();
+                bump(&self.queue.counters.delivered);
+                bump(&self.bus.counters.delivered);
+                return Ok(event);
+            }
+            if state.closed {
+                return Err(RecvError::Closed);
+            }
+            state = match deadline {
+                None => self.queue.not_empty.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner()),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(RecvError::Empty);
+                    }
+                    let wait = self.queue.not_empty.wait_timeout(state, deadline - now);
+                    wait.unwrap_or_else(|poisoned| poisoned.into_inner()).0
+                },
+            };
+        }
+    }
+
+    // Receives until the bus shuts down and the queue runs dry.
+    pub fn iter(&self) -> impl Iterator<Item = WebEvent> + '_ {
+        std::iter::from_fn(|| self.recv())
+    }
+
+    // How many events are waiting right now.
+    pub fn len(&self) -> usize {
+        self.queue.lock().events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        self.queue.counters.snapshot()
+    }
+}
+
+// Unsubscribes, and lets go of any publisher stuck waiting for this queue to have room.
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.bus.subscribers().retain(|queue| !Arc::ptr_eq(queue, &self.queue));
+        self.queue.lock().abandoned = true;
+        self.queue.not_full.notify_all();
+    }
+}
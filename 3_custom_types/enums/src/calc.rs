@@ -0,0 +1,229 @@
+// An infix calculator: `(1+2i)*(3-i)`, `1/3 + 1/6`, `-(2 + 3) * 4`.
+//
+// The expression is evaluated while it's parsed, using precedence climbing: parse one
+// operand, then keep folding in operators as long as they bind at least as tightly as
+// the caller allows. Precedence and associativity come straight from `Operations`, so
+// this parser and the printers in `symbolic` can't disagree about how `a - b * c` groups.
+//
+// The value type is picked by the caller: `calc::eval::<i64>`, `calc::eval::<Rational>`,
+// `calc::eval::<Complex>` and so on. Any `Operand` that can read a number literal works.
+
+use std::fmt;
+
+use crate::bigint::BigInt;
+use crate::complex::{Complex, Real};
+use crate::decimal::Decimal;
+use crate::operations::{Associativity, Operand, Operations};
+use crate::rational::{Integer, Rational};
+
+// A value the calculator can work with: it has to know how to read a literal. `imaginary`
+// is set for literals with an `i` on the end (`2i`, or just `i`), which only complex
+// types accept.
+pub trait CalcValue: Operand + Clone {
+    fn from_literal(text: &str, imaginary: bool) -> Option<Self>;
+}
+
+macro_rules! impl_calc_value_via_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl CalcValue for $t {
+                fn from_literal(text: &str, imaginary: bool) -> Option<Self> {
+                    if imaginary { None } else { text.parse().ok() }
+                }
+            }
+        )*
+    };
+}
+
+impl_calc_value_via_from_str!(i32, i64, i128, f32, f64, BigInt);
+
+impl<T: Integer> CalcValue for Rational<T> {
+    fn from_literal(text: &str, imaginary: bool) -> Option<Self> {
+        if imaginary { None } else { text.parse().ok() }
+    }
+}
+
+impl<const SCALE: u32> CalcValue for Decimal<SCALE> {
+    fn from_literal(text: &str, imaginary: bool) -> Option<Self> {
+        if imaginary { None } else { text.parse().ok() }
+    }
+}
+
+impl<T: Real + std::str::FromStr> CalcValue for Complex<T> {
+    fn from_literal(text: &str, imaginary: bool) -> Option<Self> {
+        let value: T = text.parse().ok()?;
+        Some(if imaginary { Complex::new(T::zero(), value) } else { Complex::from_real(value) })
+    }
+}
+
+// How deep parentheses and leading signs can be nested. The parser recurses once per
+// level, so without a limit `((((…1` or `----…1` would overflow the stack.
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalcError {
+    // Byte offset into the input and the character found there.
+    UnexpectedChar(usize, char),
+    // Byte offset of a token that doesn't fit, like the `*` in `2 + * 3`.
+    UnexpectedToken(usize),
+    UnexpectedEnd,
+    // The literal parsed, but the value type can't hold it (`2i` as an `i64`, say).
+    InvalidLiteral(String),
+    // Overflow or division by zero in the operation at this byte offset.
+    Arithmetic(usize, Operations),
+    // Byte offset of the `(` or sign that was more than `MAX_DEPTH` levels deep.
+    TooDeep(usize),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalcError::UnexpectedChar(at, c) => write!(f, "unexpected '{}' at {}", c, at),
+            CalcError::UnexpectedToken(at) => write!(f, "unexpected token at {}", at),
+            CalcError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            CalcError::InvalidLiteral(text) => write!(f, "invalid number \"{}\"", text),
+            CalcError::Arithmetic(at, Operations::Divide) => write!(f, "division by zero or overflow at {}", at),
+            CalcError::Arithmetic(at, op) => write!(f, "{} overflowed at {}", op.name(), at),
+            CalcError::TooDeep(at) => write!(f, "nested more than {} levels deep at {}", MAX_DEPTH, at),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // The digits (and decimal point) as written, so each value type can parse them exactly.
+    Number { text: String, imaginary: bool },
+    Op(Operations),
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut text = String::new();
+            while let Some(&(_, d)) = chars.peek() {
+                if !(d.is_ascii_digit() || d == '.') {
+                    break;
+                }
+                text.push(d);
+                chars.next();
+            }
+            // An `i` right after the digits makes it imaginary: `2i`, `0.5i`.
+            let imaginary = chars.next_if(|&(_, d)| d == 'i').is_some();
+            tokens.push((start, Token::Number { text, imaginary }));
+        } else if c == 'i' {
+            chars.next();
+            tokens.push((start, Token::Number { text: "1".to_owned(), imaginary: true }));
+        } else if c == '(' || c == ')' {
+            chars.next();
+            tokens.push((start, if c == '(' { Token::LParen } else { Token::RParen }));
+        } else if let Ok(op) = c.to_string().parse::<Operations>() {
+            chars.next();
+            tokens.push((start, Token::Op(op)));
+        } else {
+            return Err(CalcError::UnexpectedChar(start, c));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<T> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    // How many parentheses and leading signs the parser is inside.
+    depth: usize,
+    _value: std::marker::PhantomData<T>,
+}
+
+impl<T: CalcValue> Parser<T> {
+    fn peek(&self) -> Option<&(usize, Token)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<(usize, Token), CalcError> {
+        let token = self.tokens.get(self.pos).cloned().ok_or(CalcError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    // Runs `parse` one level deeper for the `(` or sign at `at`, if there's room.
+    fn nested(&mut self, at: usize, parse: impl FnOnce(&mut Self) -> Result<T, CalcError>) -> Result<T, CalcError> {
+        if self.depth == MAX_DEPTH {
+            return Err(CalcError::TooDeep(at));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn apply(at: usize, op: Operations, x: T, y: T) -> Result<T, CalcError> {
+        op.checked_run(x, y).ok_or(CalcError::Arithmetic(at, op))
+    }
+
+    // Parses operators that bind at least as tightly as `min_precedence`.
+    fn expression(&mut self, min_precedence: u8) -> Result<T, CalcError> {
+        let mut lhs = self.unary()?;
+        while let Some(&(at, Token::Op(op))) = self.peek() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            // For a left-associative operator the right side may only contain tighter
+            // operators, so `8 - 4 - 2` stops after the `4` and groups as `(8 - 4) - 2`.
+            let next_min = match op.associativity() {
+                Associativity::Left => op.precedence() + 1,
+            };
+            let rhs = self.expression(next_min)?;
+            lhs = Parser::apply(at, op, lhs, rhs)?;
+        }
+        Ok(lhs)
+    }
+
+    // A leading `-` is `0 - x`, so it works for any value type without a `Neg` bound.
+    fn unary(&mut self) -> Result<T, CalcError> {
+        if let Some(&(at, Token::Op(op @ (Operations::Subtract | Operations::Add)))) = self.peek() {
+            self.pos += 1;
+            let operand = self.nested(at, Parser::unary)?;
+            if op == Operations::Add {
+                return Ok(operand);
+            }
+            let zero = T::from_literal("0", false).ok_or_else(|| CalcError::InvalidLiteral("0".to_owned()))?;
+            return Parser::apply(at, Operations::Subtract, zero, operand);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<T, CalcError> {
+        match self.next()? {
+            (_, Token::Number { text, imaginary }) => {
+                T::from_literal(&text, imaginary).ok_or_else(|| CalcError::InvalidLiteral(if imaginary { text + "i" } else { text }))
+            },
+            (at, Token::LParen) => {
+                let value = self.nested(at, |parser| parser.expression(0))?;
+                match self.next() {
+                    Ok((_, Token::RParen)) => Ok(value),
+                    Ok((at, _)) => Err(CalcError::UnexpectedToken(at)),
+                    Err(e) => Err(e),
+                }
+            },
+            (at, _) => Err(CalcError::UnexpectedToken(at)),
+        }
+    }
+}
+
+pub fn eval<T: CalcValue>(input: &str) -> Result<T, CalcError> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0, depth: 0, _value: std::marker::PhantomData };
+    let value = parser.expression(0)?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(&(at, _)) => Err(CalcError::UnexpectedToken(at)),
+    }
+}
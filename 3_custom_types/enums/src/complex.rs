@@ -0,0 +1,273 @@
+// Complex numbers `re + im*i`, where `i * i = -1`.
+//
+// Multiplying two of them multiplies their lengths and adds their angles, which is why
+// the polar form (`modulus`, `arg`) shows up so much below: `exp`, `ln` and `pow` are all
+// easiest to write in terms of it.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::operations::{Operand, Operations};
+
+// The floating-point operations `Complex` needs, so it works with both `f32` and `f64`.
+pub trait Real:
+    Copy + PartialOrd + fmt::Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, x: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn is_finite(self) -> bool;
+    fn abs(self) -> Self;
+    // True for `-0.0` too, unlike `< 0.0`.
+    fn is_sign_negative(self) -> bool;
+}
+
+macro_rules! impl_real {
+    ($($t:ty),*) => {
+        $(
+            impl Real for $t {
+                fn zero() -> $t { 0.0 }
+                fn one() -> $t { 1.0 }
+                fn sqrt(self) -> $t { <$t>::sqrt(self) }
+                fn exp(self) -> $t { <$t>::exp(self) }
+                fn ln(self) -> $t { <$t>::ln(self) }
+                fn sin(self) -> $t { <$t>::sin(self) }
+                fn cos(self) -> $t { <$t>::cos(self) }
+                fn atan2(self, x: $t) -> $t { <$t>::atan2(self, x) }
+                fn hypot(self, other: $t) -> $t { <$t>::hypot(self, other) }
+                fn is_finite(self) -> bool { <$t>::is_finite(self) }
+                fn abs(self) -> $t { <$t>::abs(self) }
+                fn is_sign_negative(self) -> bool { <$t>::is_sign_negative(self) }
+            }
+        )*
+    };
+}
+
+impl_real!(f32, f64);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex<T = f64> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T: Real> Complex<T> {
+    pub fn new(re: T, im: T) -> Complex<T> {
+        Complex { re, im }
+    }
+
+    // The imaginary unit `i`.
+    pub fn i() -> Complex<T> {
+        Complex::new(T::zero(), T::one())
+    }
+
+    pub fn from_real(re: T) -> Complex<T> {
+        Complex::new(re, T::zero())
+    }
+
+    // `r * (cos(theta) + i * sin(theta))`
+    pub fn from_polar(r: T, theta: T) -> Complex<T> {
+        Complex::new(r * theta.cos(), r * theta.sin())
+    }
+
+    // `(modulus, arg)`, the inverse of `from_polar`.
+    pub fn to_polar(&self) -> (T, T) {
+        (self.modulus(), self.arg())
+    }
+
+    // Mirrors across the real axis: `a + bi` becomes `a - bi`.
+    pub fn conj(&self) -> Complex<T> {
+        Complex::new(self.re, -self.im)
+    }
+
+    // `|z|^2 = a^2 + b^2`, which is also `z * conj(z)`.
+    pub fn norm_sqr(&self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    // The distance from 0. `hypot` avoids overflowing in the squares for big parts.
+    pub fn modulus(&self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    // The angle from the positive real axis, in (-pi, pi].
+    pub fn arg(&self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.re == T::zero() && self.im == T::zero()
+    }
+
+    // `None` for zero.
+    pub fn checked_recip(&self) -> Option<Complex<T>> {
+        Complex::from_real(T::one()).checked_div(self)
+    }
+
+    // `None` when dividing by zero. The textbook `z * conj(w) / |w|^2` squares the parts
+    // of `w`, which overflows for parts around 1e155 and up (and underflows for tiny
+    // ones), so this uses Smith's algorithm instead: divide through by the bigger of
+    // `w`'s two parts first, so nothing gets squared.
+    pub fn checked_div(&self, other: &Complex<T>) -> Option<Complex<T>> {
+        if other.is_zero() {
+            return None;
+        }
+        let (a, b, c, d) = (self.re, self.im, other.re, other.im);
+        Some(if c.abs() >= d.abs() {
+            // (a + bi) / (c + di) = ((a + b*r) + (b - a*r)i) / (c + d*r), with r = d / c
+            let r = d / c;
+            let den = c + d * r;
+            Complex::new((a + b * r) / den, (b - a * r) / den)
+        } else {
+            // The same with the roles of `c` and `d` swapped, r = c / d.
+            let r = c / d;
+            let den = c * r + d;
+            Complex::new((a * r + b) / den, (b * r - a) / den)
+        })
+    }
+
+    // e^(a + bi) = e^a * (cos b + i sin b)
+    pub fn exp(&self) -> Complex<T> {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    // The principal logarithm, ln|z| + i*arg(z). `ln(0)` has a real part of -infinity.
+    pub fn ln(&self) -> Complex<T> {
+        Complex::new(self.modulus().ln(), self.arg())
+    }
+
+    // The principal square root, the one with a non-negative real part. Halving the
+    // angle in polar form would work too, but going through `cos` and `sin` loses
+    // precision, so `sqrt(2i)` wouldn't come out as exactly `1+1i`. This way only
+    // rounds in the `sqrt` and the divisions.
+    pub fn sqrt(&self) -> Complex<T> {
+        if self.is_zero() {
+            return *self;
+        }
+        let two = T::one() + T::one();
+        let t = ((self.re.abs() + self.modulus()) / two).sqrt();
+        if self.re >= T::zero() {
+            Complex::new(t, self.im / (two * t))
+        } else {
+            Complex::new(self.im.abs() / (two * t), if self.im < T::zero() { -t } else { t })
+        }
+    }
+
+    // z^w = e^(w * ln z), using the principal logarithm. For a zero base, like `powf`
+    // does for reals: 0^w is 0 when w has a positive real part, infinite when it's
+    // negative, and 1 for w = 0. Otherwise (w = bi) there's no answer, so it's NaN.
+    pub fn pow(&self, w: &Complex<T>) -> Complex<T> {
+        if self.is_zero() {
+            let value = if w.is_zero() {
+                T::one()
+            } else if w.re > T::zero() {
+                T::zero()
+            } else if w.re < T::zero() {
+                T::one() / T::zero()
+            } else {
+                T::zero() / T::zero()
+            };
+            return Complex::from_real(value);
+        }
+        (*w * self.ln()).exp()
+    }
+
+    // Integer powers by repeated squaring. Exact for Gaussian integers like `(1+i)^8`,
+    // which `pow` would only get approximately right. A negative power of zero is
+    // infinite, like it is for `f64::powi`.
+    pub fn powi(&self, n: i32) -> Complex<T> {
+        let mut base = match (n < 0, self.checked_recip()) {
+            (false, _) => *self,
+            (true, Some(recip)) => recip,
+            (true, None) => return Complex::from_real(T::one() / T::zero()),
+        };
+        let mut exp = n.unsigned_abs();
+        let mut result = Complex::from_real(T::one());
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl<T: Real> From<T> for Complex<T> {
+    fn from(re: T) -> Complex<T> {
+        Complex::from_real(re)
+    }
+}
+
+// `5+5i`, `3-1i`, `0+1i`. Precision applies to both parts: `{:.2}` gives `0.50-1.25i`.
+// A negative zero imaginary part prints as `3-0i`, never `3+-0i`.
+impl<T: Real> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (sign, im) = if self.im.is_sign_negative() { ('-', self.im.abs()) } else { ('+', self.im) };
+        match f.precision() {
+            Some(p) => write!(f, "{:.*}{}{:.*}i", p, self.re, sign, p, im),
+            None => write!(f, "{}{}{}i", self.re, sign, im),
+        }
+    }
+}
+
+impl<T: Real> Neg for Complex<T> {
+    type Output = Complex<T>;
+
+    fn neg(self) -> Complex<T> {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl<T: Real> Add for Complex<T> {
+    type Output = Complex<T>;
+
+    fn add(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<T: Real> Sub for Complex<T> {
+    type Output = Complex<T>;
+
+    fn sub(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+// (a + bi)(c + di) = ac + adi + bci + bd*i^2 = (ac - bd) + (ad + bc)i
+impl<T: Real> Mul for Complex<T> {
+    type Output = Complex<T>;
+
+    fn mul(self, other: Complex<T>) -> Complex<T> {
+        Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+}
+
+impl<T: Real> Div for Complex<T> {
+    type Output = Complex<T>;
+
+    fn div(self, other: Complex<T>) -> Complex<T> {
+        self.checked_div(&other).expect("complex division by zero")
+    }
+}
+
+impl<T: Real> Operand for Complex<T> {
+    fn apply(op: Operations, x: Complex<T>, y: Complex<T>) -> Option<Complex<T>> {
+        let result = match op {
+            Operations::Add => x + y,
+            Operations::Subtract => x - y,
+            Operations::Multiply => x * y,
+            Operations::Divide => x.checked_div(&y)?,
+        };
+        // Same rule as for `f32` and `f64`: a result that isn't finite is an overflow.
+        if result.re.is_finite() && result.im.is_finite() { Some(result) } else { None }
+    }
+}
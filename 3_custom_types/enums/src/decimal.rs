@@ -0,0 +1,327 @@
+// A fixed-point decimal number with `SCALE` digits after the point, stored as an `i128`
+// count of `10^-SCALE` units. `Decimal<2>` is good for money (12.34 is stored as 1234),
+// `Decimal<18>` for token amounts.
+//
+// Addition and subtraction are always exact. Multiplication and division usually
+// produce more digits than `SCALE` can hold, so they take a `RoundingMode` saying what
+// to do with the extra ones. This replaces the `(x * 100.0).round() / 100.0` trick
+// with something that can't be thrown off by binary floating point.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::bigint::BigInt;
+use crate::operations::{Operand, Operations};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    // Ties go to the even neighbour: 2.5 -> 2, 3.5 -> 4. Also called banker's rounding,
+    // it doesn't drift upward over many roundings, so it's the default.
+    HalfEven,
+    // Ties go away from zero: 2.5 -> 3, -2.5 -> -3. What most people learn in school.
+    HalfUp,
+    // Toward negative infinity: 2.7 -> 2, -2.1 -> -3.
+    Floor,
+    // Toward positive infinity: 2.1 -> 3, -2.7 -> -2.
+    Ceil,
+    // Toward zero, just dropping the extra digits: 2.7 -> 2, -2.7 -> -2.
+    Truncate,
+}
+
+// Whether a truncated quotient should move one step away from zero.
+// `half` is how the dropped fraction compares to one half.
+fn rounds_away(mode: RoundingMode, negative: bool, quotient_is_odd: bool, half: Ordering) -> bool {
+    match mode {
+        RoundingMode::Truncate => false,
+        RoundingMode::Floor => negative,
+        RoundingMode::Ceil => !negative,
+        RoundingMode::HalfUp => half != Ordering::Less,
+        RoundingMode::HalfEven => half == Ordering::Greater || (half == Ordering::Equal && quotient_is_odd),
+    }
+}
+
+// `n / d` rounded to an integer with `mode`. `None` if `d` is zero or the result doesn't fit.
+fn round_div(n: i128, d: i128, mode: RoundingMode) -> Option<i128> {
+    let (q, r) = (n.checked_div(d)?, n.checked_rem(d)?);
+    if r == 0 {
+        return Some(q);
+    }
+    let (r, d_abs) = (r.unsigned_abs(), d.unsigned_abs());
+    // Comparing `r` with `d - r` instead of `2 * r` with `d` can't overflow.
+    let half = r.cmp(&(d_abs - r));
+    let negative = (n < 0) != (d < 0);
+    if rounds_away(mode, negative, q % 2 != 0, half) { q.checked_add(if negative { -1 } else { 1 }) } else { Some(q) }
+}
+
+fn round_div_big(n: &BigInt, d: &BigInt, mode: RoundingMode) -> Option<i128> {
+    let (q, r) = n.checked_div_rem(d)?;
+    // Rounding only ever moves away from zero, so if the truncated quotient doesn't
+    // fit in an `i128`, the rounded one won't either.
+    let q = q.to_i128()?;
+    if r.is_zero() {
+        return Some(q);
+    }
+    let half = r.abs().cmp(&(d.abs() - r.abs()));
+    let negative = n.is_negative() != d.is_negative();
+    if rounds_away(mode, negative, q % 2 != 0, half) { q.checked_add(if negative { -1 } else { 1 }) } else { Some(q) }
+}
+
+// `a * b / d`, rounded. Falls back to `BigInt` when the product doesn't fit in an `i128`.
+fn mul_div(a: i128, b: i128, d: i128, mode: RoundingMode) -> Option<i128> {
+    match a.checked_mul(b) {
+        Some(product) => round_div(product, d, mode),
+        None => round_div_big(&(BigInt::from(a) * BigInt::from(b)), &BigInt::from(d), mode),
+    }
+}
+
+const fn pow10(exp: u32) -> i128 {
+    let mut result = 1i128;
+    let mut i = 0;
+    while i < exp {
+        result *= 10;
+        i += 1;
+    }
+    result
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    Invalid(String),
+    // The string has non-zero digits past `SCALE`. Use `parse_rounded` to accept it anyway.
+    TooPrecise,
+    Overflow,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDecimalError::Invalid(s) => write!(f, "invalid decimal number \"{}\"", s),
+            ParseDecimalError::TooPrecise => write!(f, "more decimal places than the type can hold"),
+            ParseDecimalError::Overflow => write!(f, "decimal number out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+// Ordering by `raw` is correct because both sides always have the same `SCALE`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal<const SCALE: u32> {
+    raw: i128,
+}
+
+impl<const SCALE: u32> Decimal<SCALE> {
+    // How many raw units make 1. Naming this for a `SCALE` above 38 is a compile error,
+    // because `10^39` doesn't fit in an `i128`.
+    pub const UNIT: i128 = pow10(SCALE);
+    pub const ZERO: Decimal<SCALE> = Decimal { raw: 0 };
+    pub const ONE: Decimal<SCALE> = Decimal { raw: Self::UNIT };
+    pub const MAX: Decimal<SCALE> = Decimal { raw: i128::MAX };
+    pub const MIN: Decimal<SCALE> = Decimal { raw: i128::MIN };
+
+    // `Decimal::<2>::from_raw(1234)` is 12.34.
+    pub const fn from_raw(raw: i128) -> Decimal<SCALE> {
+        Decimal { raw }
+    }
+
+    pub const fn raw(&self) -> i128 {
+        self.raw
+    }
+
+    pub fn checked_from_int(n: i128) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: n.checked_mul(Self::UNIT)? })
+    }
+
+    pub fn from_int(n: i64) -> Decimal<SCALE> {
+        Self::checked_from_int(n as i128).expect("integer out of range for Decimal")
+    }
+
+    pub fn checked_add(&self, other: &Decimal<SCALE>) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: self.raw.checked_add(other.raw)? })
+    }
+
+    pub fn checked_sub(&self, other: &Decimal<SCALE>) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: self.raw.checked_sub(other.raw)? })
+    }
+
+    // (a / 10^S) * (b / 10^S) = (a * b / 10^S) / 10^S
+    pub fn checked_mul(&self, other: &Decimal<SCALE>, mode: RoundingMode) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: mul_div(self.raw, other.raw, Self::UNIT, mode)? })
+    }
+
+    // (a / 10^S) / (b / 10^S) = (a * 10^S / b) / 10^S. `None` when dividing by zero.
+    pub fn checked_div(&self, other: &Decimal<SCALE>, mode: RoundingMode) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: mul_div(self.raw, Self::UNIT, other.raw, mode)? })
+    }
+
+    pub fn checked_neg(&self) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: self.raw.checked_neg()? })
+    }
+
+    // `None` for `MIN`, which has no positive counterpart.
+    pub fn checked_abs(&self) -> Option<Decimal<SCALE>> {
+        Some(Decimal { raw: self.raw.checked_abs()? })
+    }
+
+    pub fn abs(&self) -> Decimal<SCALE> {
+        self.checked_abs().expect("attempt to take the absolute value of Decimal::MIN")
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.raw < 0
+    }
+
+    // Rounds to `places` digits after the point, keeping the same type.
+    // `Decimal::<4>` 2.3450 rounded to 2 places with `HalfEven` is 2.3400.
+    // `None` when rounding away from zero goes past `MAX` or `MIN`.
+    pub fn checked_round_dp(&self, places: u32, mode: RoundingMode) -> Option<Decimal<SCALE>> {
+        if places >= SCALE {
+            return Some(*self);
+        }
+        let Some(step) = 10i128.checked_pow(SCALE - places) else {
+            // With a step too big for an `i128`, the only multiple of it that fits is zero.
+            let units = round_div_big(&BigInt::from(self.raw), &BigInt::from(10u32).pow(SCALE - places), mode)?;
+            return if units == 0 { Some(Self::ZERO) } else { None };
+        };
+        Some(Decimal { raw: round_div(self.raw, step, mode)?.checked_mul(step)? })
+    }
+
+    pub fn round_dp(&self, places: u32, mode: RoundingMode) -> Decimal<SCALE> {
+        self.checked_round_dp(places, mode).expect("Decimal::round_dp out of range")
+    }
+
+    // Converts to another scale, rounding if digits are dropped. `None` on overflow.
+    pub fn rescale<const NEW: u32>(&self, mode: RoundingMode) -> Option<Decimal<NEW>> {
+        let raw = if NEW >= SCALE {
+            self.raw.checked_mul(10i128.checked_pow(NEW - SCALE)?)?
+        } else {
+            match 10i128.checked_pow(SCALE - NEW) {
+                Some(step) => round_div(self.raw, step, mode)?,
+                None => round_div_big(&BigInt::from(self.raw), &BigInt::from(10u32).pow(SCALE - NEW), mode)?,
+            }
+        };
+        Some(Decimal { raw })
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        // Not exact for big values, but it's the best an `f64` can do.
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    // Like `from_str`, but rounds digits past `SCALE` with `mode` instead of failing.
+    pub fn parse_rounded(s: &str, mode: RoundingMode) -> Result<Decimal<SCALE>, ParseDecimalError> {
+        Self::parse(s, Some(mode))
+    }
+
+    fn parse(s: &str, mode: Option<RoundingMode>) -> Result<Decimal<SCALE>, ParseDecimalError> {
+        let invalid = || ParseDecimalError::Invalid(s.to_owned());
+        let trimmed = s.trim();
+        let (negative, body) = match trimmed.as_bytes().first() {
+            Some(b'-') => (true, &trimmed[1..]),
+            Some(b'+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+        let (int_part, frac_part) = body.split_once('.').unwrap_or((body, ""));
+        let all_digits = |p: &str| p.bytes().all(|b| b.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty()) || !all_digits(int_part) || !all_digits(frac_part) {
+            return Err(invalid());
+        }
+
+        // Read everything as one integer `n` with `frac_part.len()` digits after the point.
+        let sign = if negative { "-" } else { "" };
+        let n = BigInt::from_str_radix(&format!("{}{}{}", sign, int_part, frac_part), 10).map_err(|_| invalid())?;
+        let digits = frac_part.len() as u32;
+        let raw = if digits <= SCALE {
+            (n * BigInt::from(10u32).pow(SCALE - digits)).to_i128().ok_or(ParseDecimalError::Overflow)?
+        } else {
+            let dropped = &frac_part[SCALE as usize..];
+            let mode = match mode {
+                Some(mode) => mode,
+                // Without a rounding mode, extra digits are only fine if they're all zeros.
+                None if dropped.bytes().all(|b| b == b'0') => RoundingMode::Truncate,
+                None => return Err(ParseDecimalError::TooPrecise),
+            };
+            let divisor = BigInt::from(10u32).pow(digits - SCALE);
+            round_div_big(&n, &divisor, mode).ok_or(ParseDecimalError::Overflow)?
+        };
+        Ok(Decimal { raw })
+    }
+}
+
+// Exact: fails with `TooPrecise` rather than rounding.
+impl<const SCALE: u32> FromStr for Decimal<SCALE> {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Decimal<SCALE>, ParseDecimalError> {
+        Decimal::parse(s, None)
+    }
+}
+
+// Always prints all `SCALE` digits, so money shows as `12.50`, not `12.5`.
+impl<const SCALE: u32> fmt::Display for Decimal<SCALE> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let magnitude = self.raw.unsigned_abs();
+        let unit = Self::UNIT as u128;
+        let digits = if SCALE == 0 {
+            magnitude.to_string()
+        } else {
+            format!("{}.{:0width$}", magnitude / unit, magnitude % unit, width = SCALE as usize)
+        };
+        f.pad_integral(self.raw >= 0, "", &digits)
+    }
+}
+
+impl<const SCALE: u32> Neg for Decimal<SCALE> {
+    type Output = Decimal<SCALE>;
+
+    fn neg(self) -> Decimal<SCALE> {
+        self.checked_neg().expect("attempt to negate Decimal::MIN")
+    }
+}
+
+impl<const SCALE: u32> Add for Decimal<SCALE> {
+    type Output = Decimal<SCALE>;
+
+    fn add(self, other: Decimal<SCALE>) -> Decimal<SCALE> {
+        self.checked_add(&other).expect("Decimal addition overflowed")
+    }
+}
+
+impl<const SCALE: u32> Sub for Decimal<SCALE> {
+    type Output = Decimal<SCALE>;
+
+    fn sub(self, other: Decimal<SCALE>) -> Decimal<SCALE> {
+        self.checked_sub(&other).expect("Decimal subtraction overflowed")
+    }
+}
+
+// The `*` and `/` operators round with `HalfEven`. Call `checked_mul`/`checked_div`
+// directly to pick a different mode.
+impl<const SCALE: u32> Mul for Decimal<SCALE> {
+    type Output = Decimal<SCALE>;
+
+    fn mul(self, other: Decimal<SCALE>) -> Decimal<SCALE> {
+        self.checked_mul(&other, RoundingMode::HalfEven).expect("Decimal multiplication overflowed")
+    }
+}
+
+impl<const SCALE: u32> Div for Decimal<SCALE> {
+    type Output = Decimal<SCALE>;
+
+    fn div(self, other: Decimal<SCALE>) -> Decimal<SCALE> {
+        self.checked_div(&other, RoundingMode::HalfEven).expect("Decimal division overflowed or divided by zero")
+    }
+}
+
+impl<const SCALE: u32> Operand for Decimal<SCALE> {
+    fn apply(op: Operations, x: Decimal<SCALE>, y: Decimal<SCALE>) -> Option<Decimal<SCALE>> {
+        match op {
+            Operations::Add => x.checked_add(&y),
+            Operations::Subtract => x.checked_sub(&y),
+            Operations::Multiply => x.checked_mul(&y, RoundingMode::HalfEven),
+            Operations::Divide => x.checked_div(&y, RoundingMode::HalfEven),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Routes `WebEvent`s to handlers registered for a specific variant or for every event.
+//
+// Handlers run from highest to lowest priority, and in registration order when the
+// priorities are equal. Any handler can return `Propagation::Stop` to keep the event
+// from reaching the handlers after it, like `stopPropagation()` in a browser.
+
+use crate::web_event::{EventKind, WebEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagation {
+    Continue,
+    Stop,
+}
+
+// Returned when registering, and needed to unregister. Ids are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandlerId(u64);
+
+// What happened to one dispatched event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dispatched {
+    // How many handlers ran, including the one that stopped propagation.
+    pub handled: usize,
+    pub stopped: bool,
+}
+
+type Handler = Box<dyn FnMut(&WebEvent) -> Propagation>;
+
+struct Registration {
+    id: HandlerId,
+    // `None` means the handler wants every event.
+    kind: Option<EventKind>,
+    priority: i32,
+    handler: Handler,
+}
+
+#[derive(Default)]
+pub struct EventDispatcher {
+    // Kept sorted by priority, so dispatching is a single pass.
+    registrations: Vec<Registration>,
+    next_id: u64,
+}
+
+impl EventDispatcher {
+    pub fn new() -> EventDispatcher {
+        EventDispatcher::default()
+    }
+
+    // Handles one variant, at priority 0.
+    pub fn on<F>(&mut self, kind: EventKind, handler: F) -> HandlerId
+    where
+        F: FnMut(&WebEvent) -> Propagation + 'static,
+    {
+        self.register(Some(kind), 0, handler)
+    }
+
+    // Handles every event, at priority 0.
+    pub fn on_any<F>(&mut self, handler: F) -> HandlerId
+    where
+        F: FnMut(&WebEvent) -> Propagation + 'static,
+    {
+        self.register(None, 0, handler)
+    }
+
+    // The general form: `kind` of `None` for every event, and a higher `priority` runs earlier.
+    pub fn register<F>(&mut self, kind: Option<EventKind>, priority: i32, handler: F) -> HandlerId
+    where
+        F: FnMut(&WebEvent) -> Propagation + 'static,
+    {
+        let id = HandlerId(self.next_id);
+        self.next_id += 1;
+        // Insert after every handler with the same or higher priority, which keeps
+        // registration order among equals.
+        let at = self.registrations.partition_point(|r| r.priority >= priority);
+        self.registrations.insert(at, Registration { id, kind, priority, handler: Box::new(handler) });
+        id
+    }
+
+    // Returns `false` if the handler was already removed.
+    pub fn off(&mut self, id: HandlerId) -> bool {
+        let before = self.registrations.len();
+        self.registrations.retain(|r| r.id != id);
+        self.registrations.len() != before
+    }
+
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    pub fn dispatch(&mut self, event: &WebEvent) -> Dispatched {
+        let kind = event.kind();
+        let mut handled = 0;
+        for registration in &mut self.registrations {
+            if registration.kind.is_some_and(|k| k != kind) {
+                continue;
+            }
+            handled += 1;
+            if (registration.handler)(event) == Propagation::Stop {
+                return Dispatched { handled, stopped: true };
+            }
+        }
+        Dispatched { handled, stopped: false }
+    }
+}
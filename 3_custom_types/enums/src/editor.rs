@@ -0,0 +1,309 @@
+// A small text editing core driven by `WebEvent`s: typing and pasting insert text,
+// Backspace and Delete remove it, the arrows, Home and End move the cursor, and a
+// `Click { x, y }` puts the cursor at column `x` of line `y`.
+//
+// A few Ctrl keys do what they do everywhere: Ctrl+A selects everything, Ctrl+Z
+// undoes and Ctrl+Y redoes. Typing or pasting over a selection replaces it.
+//
+// Undo works in groups, so one Ctrl+Z takes back a whole run of typing rather than a
+// single letter. A run of typed characters is one group, and so is a run of
+// Backspaces or Deletes. Anything else (moving the cursor, pasting, Enter, switching
+// from typing to deleting) closes the group and starts a new one.
+//
+// Positions are counted in `char`s, not bytes, so they line up with what the user sees
+// for most text.
+
+use std::ops::Range;
+
+use crate::web_event::{keys, WebEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupKind {
+    Typing,
+    Backspace,
+    Delete,
+    // Never merged with anything.
+    Single,
+}
+
+// One change: `removed` was replaced with `inserted` at position `at`.
+#[derive(Debug, Clone)]
+struct Edit {
+    at: usize,
+    removed: Vec<char>,
+    inserted: Vec<char>,
+}
+
+// Where the cursor and selection anchor were, so undo and redo can put them back.
+type CursorState = (usize, Option<usize>);
+
+#[derive(Debug, Clone)]
+struct Group {
+    edits: Vec<Edit>,
+    before: CursorState,
+    after: CursorState,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextBuffer {
+    text: Vec<char>,
+    cursor: usize,
+    // The other end of the selection, if there is one. The cursor is always the end
+    // that moves.
+    anchor: Option<usize>,
+    // The column Up and Down try to stay in, even when passing through shorter lines.
+    goal_column: Option<usize>,
+    undo: Vec<Group>,
+    redo: Vec<Group>,
+    // The group new edits may join, until something closes it.
+    open: Option<GroupKind>,
+}
+
+impl TextBuffer {
+    pub fn new() -> TextBuffer {
+        TextBuffer::default()
+    }
+
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    // The selected range, or `None` if nothing (or an empty range) is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor.filter(|&anchor| anchor != self.cursor)?;
+        Some(anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    pub fn selected_text(&self) -> String {
+        self.selection().map(|range| self.text[range].iter().collect()).unwrap_or_default()
+    }
+
+    // Both counted from 0.
+    pub fn line_and_column(&self) -> (usize, usize) {
+        self.position_to_line_column(self.cursor)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Returns whether the event meant anything to the editor. Page loads, function
+    // keys and the like are ignored.
+    pub fn apply(&mut self, event: &WebEvent) -> bool {
+        match event {
+            WebEvent::KeyPress(c) => self.key(*c),
+            WebEvent::Paste(text) => {
+                self.insert(text, GroupKind::Single);
+                true
+            },
+            WebEvent::Click { x, y } => {
+                let line = usize::try_from(*y).unwrap_or(0);
+                let column = usize::try_from(*x).unwrap_or(0);
+                self.move_to(self.line_column_to_position(line, column), false);
+                true
+            },
+            WebEvent::PageLoad | WebEvent::PageUnload => false,
+        }
+    }
+
+    fn key(&mut self, c: char) -> bool {
+        match c {
+            keys::BACKSPACE => self.backspace(),
+            keys::DELETE => self.delete(),
+            keys::LEFT => self.left(),
+            keys::RIGHT => self.right(),
+            keys::UP => self.vertical(-1),
+            keys::DOWN => self.vertical(1),
+            keys::HOME => self.move_to(self.line_start(self.cursor), false),
+            keys::END => self.move_to(self.line_end(self.cursor), false),
+            keys::ENTER => self.insert("\n", GroupKind::Single),
+            c if Some(c) == keys::ctrl('a') => self.select(0..self.text.len()),
+            c if Some(c) == keys::ctrl('z') => return self.undo(),
+            c if Some(c) == keys::ctrl('y') => return self.redo(),
+            // Other control characters and the private-use characters standing in for
+            // function keys don't type anything.
+            c if c.is_control() && c != keys::TAB => return false,
+            '\u{f700}'..='\u{f8ff}' => return false,
+            c => self.insert(&c.to_string(), GroupKind::Typing),
+        }
+        true
+    }
+
+    // Replaces the selection (if any) with `text`.
+    pub fn insert_str(&mut self, text: &str) {
+        self.insert(text, GroupKind::Single);
+    }
+
+    fn insert(&mut self, text: &str, kind: GroupKind) {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        self.edit(range, text.chars().collect(), kind);
+    }
+
+    // Deletes the selection, or the character before the cursor.
+    pub fn backspace(&mut self) {
+        match self.selection() {
+            Some(range) => self.edit(range, Vec::new(), GroupKind::Single),
+            None if self.cursor > 0 => self.edit(self.cursor - 1..self.cursor, Vec::new(), GroupKind::Backspace),
+            None => {},
+        }
+    }
+
+    // Deletes the selection, or the character after the cursor.
+    pub fn delete(&mut self) {
+        match self.selection() {
+            Some(range) => self.edit(range, Vec::new(), GroupKind::Single),
+            None if self.cursor < self.text.len() => self.edit(self.cursor..self.cursor + 1, Vec::new(), GroupKind::Delete),
+            None => {},
+        }
+    }
+
+    fn edit(&mut self, range: Range<usize>, inserted: Vec<char>, kind: GroupKind) {
+        // Like pasting nothing with nothing selected. Not worth an undo step.
+        if range.is_empty() && inserted.is_empty() {
+            return;
+        }
+        let before = (self.cursor, self.anchor);
+        let had_selection = self.selection().is_some();
+        let removed: Vec<char> = self.text.splice(range.clone(), inserted.iter().copied()).collect();
+        self.cursor = range.start + inserted.len();
+        self.anchor = None;
+        self.goal_column = None;
+        self.redo.clear();
+
+        let edit = Edit { at: range.start, removed, inserted };
+        let after = (self.cursor, None);
+        match self.undo.last_mut() {
+            Some(group) if self.open == Some(kind) && kind != GroupKind::Single && !had_selection => {
+                group.edits.push(edit);
+                group.after = after;
+            },
+            _ => self.undo.push(Group { edits: vec![edit], before, after }),
+        }
+        self.open = Some(kind);
+    }
+
+    // Moves the cursor, extending the selection if `extend` is set and dropping it
+    // otherwise.
+    pub fn move_to(&mut self, position: usize, extend: bool) {
+        let position = position.min(self.text.len());
+        if extend {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = position;
+        self.goal_column = None;
+        self.open = None;
+    }
+
+    pub fn select(&mut self, range: Range<usize>) {
+        let end = range.end.min(self.text.len());
+        self.move_to(range.start.min(end), false);
+        self.move_to(end, true);
+    }
+
+    // With a selection, Left goes to its start rather than one before the cursor.
+    fn left(&mut self) {
+        let target = match self.selection() {
+            Some(range) => range.start,
+            None => self.cursor.saturating_sub(1),
+        };
+        self.move_to(target, false);
+    }
+
+    fn right(&mut self) {
+        let target = match self.selection() {
+            Some(range) => range.end,
+            None => self.cursor + 1,
+        };
+        self.move_to(target, false);
+    }
+
+    fn vertical(&mut self, lines: isize) {
+        let (line, column) = self.line_and_column();
+        let goal = self.goal_column.unwrap_or(column);
+        let last_line = self.text.iter().filter(|&&c| c == '\n').count();
+        let target_line = line.saturating_add_signed(lines).min(last_line);
+        let target = if target_line == line {
+            // Up on the first line goes to its start, Down on the last to its end.
+            if lines < 0 { 0 } else { self.text.len() }
+        } else {
+            self.line_column_to_position(target_line, goal)
+        };
+        self.move_to(target, false);
+        self.goal_column = Some(goal);
+    }
+
+    fn line_start(&self, position: usize) -> usize {
+        self.text[..position].iter().rposition(|&c| c == '\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self, position: usize) -> usize {
+        self.text[position..].iter().position(|&c| c == '\n').map_or(self.text.len(), |i| position + i)
+    }
+
+    fn position_to_line_column(&self, position: usize) -> (usize, usize) {
+        let line = self.text[..position].iter().filter(|&&c| c == '\n').count();
+        (line, position - self.line_start(position))
+    }
+
+    // Past the end of a line is the end of that line, past the last line is the last line.
+    fn line_column_to_position(&self, line: usize, column: usize) -> usize {
+        let mut start = 0;
+        for _ in 0..line {
+            match self.text[start..].iter().position(|&c| c == '\n') {
+                Some(i) => start += i + 1,
+                None => break,
+            }
+        }
+        (start + column).min(self.line_end(start))
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(group) = self.undo.pop() else { return false };
+        for edit in group.edits.iter().rev() {
+            self.text.splice(edit.at..edit.at + edit.inserted.len(), edit.removed.iter().copied());
+        }
+        (self.cursor, self.anchor) = group.before;
+        self.redo.push(group);
+        self.goal_column = None;
+        self.open = None;
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(group) = self.redo.pop() else { return false };
+        for edit in &group.edits {
+            self.text.splice(edit.at..edit.at + edit.removed.len(), edit.inserted.iter().copied());
+        }
+        (self.cursor, self.anchor) = group.after;
+        self.undo.push(group);
+        self.goal_column = None;
+        self.open = None;
+        true
+    }
+}
+
+impl From<&str> for TextBuffer {
+    // Starts with the cursor at the end and nothing to undo.
+    fn from(text: &str) -> TextBuffer {
+        let text: Vec<char> = text.chars().collect();
+        TextBuffer { cursor: text.len(), text, ..TextBuffer::default() }
+    }
+}
@@ -0,0 +1,71 @@
+// An expression tree built out of `Operations`. Each node is one of a few variants,
+// and the tree is evaluated by walking it recursively (a "tree-walking" interpreter).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::operations::Operations;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(i32),
+    Var(String),
+    // `Box` is needed for the same reason as in the linked-list example: an `Expr`
+    // can't contain an `Expr` directly, or its size would be infinite.
+    Binary(Operations, Box<Expr>, Box<Expr>),
+    // Evaluates `then` if `cond` is non-zero, `otherwise` if it's zero.
+    If {
+        cond: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    UnboundVariable(String),
+    // The operation that failed, so callers can tell an overflow in `Add` from a division by zero.
+    Arithmetic(Operations),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::UnboundVariable(name) => write!(f, "unbound variable `{}`", name),
+            EvalError::Arithmetic(Operations::Divide) => write!(f, "division by zero or overflow"),
+            EvalError::Arithmetic(op) => write!(f, "overflow in {:?}", op),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl Expr {
+    pub fn var(name: &str) -> Expr {
+        Expr::Var(name.to_owned())
+    }
+
+    pub fn binary(op: Operations, lhs: Expr, rhs: Expr) -> Expr {
+        Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn if_else(cond: Expr, then: Expr, otherwise: Expr) -> Expr {
+        Expr::If { cond: Box::new(cond), then: Box::new(then), otherwise: Box::new(otherwise) }
+    }
+
+    pub fn eval(&self, env: &HashMap<String, i32>) -> Result<i32, EvalError> {
+        match self {
+            Expr::Const(n) => Ok(*n),
+            Expr::Var(name) => env.get(name).copied().ok_or_else(|| EvalError::UnboundVariable(name.clone())),
+            Expr::Binary(op, lhs, rhs) => {
+                let x = lhs.eval(env)?;
+                let y = rhs.eval(env)?;
+                op.checked_run(x, y).ok_or(EvalError::Arithmetic(*op))
+            },
+            // Only the branch that's taken gets evaluated, so `if b then a / b else 0` is safe.
+            Expr::If { cond, then, otherwise } => {
+                if cond.eval(env)? != 0 { then.eval(env) } else { otherwise.eval(env) }
+            },
+        }
+    }
+}
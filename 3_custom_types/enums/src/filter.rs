@@ -0,0 +1,520 @@
+// A little query language for picking events out of a log:
+//
+//   type == click && x > 100 && y < 50
+//   paste contains "http"
+//   !(type == key_press) || t >= 1500
+//
+// Fields are `type`, `x`, `y`, `key`, `paste` and `t` (milliseconds into a recorded
+// session). Numbers compare with `== != < <= > >=`, text with `== !=` and `contains`,
+// `starts_with` and `ends_with`. Strings go in double quotes, with the same escapes as
+// JSON (`\"`, `\\`, `\u00e9` and so on), but a plain word like `click` works too. `&&`
+// binds tighter than `||`, and `!` and parentheses work as usual.
+//
+// A comparison on a field the event doesn't have, like `x` on a key press or `t` on an
+// event from a plain log, is false.
+//
+// Mistakes are caught when the filter is parsed rather than per event, so a typo
+// fails straight away instead of quietly matching nothing.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+use std::time::Duration;
+
+use crate::web_event::{EventKind, WebEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Type,
+    X,
+    Y,
+    Key,
+    Paste,
+    Time,
+}
+
+impl Field {
+    pub fn all() -> impl Iterator<Item = Field> {
+        [Field::Type, Field::X, Field::Y, Field::Key, Field::Paste, Field::Time].into_iter()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Type => "type",
+            Field::X => "x",
+            Field::Y => "y",
+            Field::Key => "key",
+            Field::Paste => "paste",
+            Field::Time => "t",
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Field::X | Field::Y | Field::Time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl CompareOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Contains => "contains",
+            CompareOp::StartsWith => "starts_with",
+            CompareOp::EndsWith => "ends_with",
+        }
+    }
+
+    fn from_word(word: &str) -> Option<CompareOp> {
+        match word {
+            "contains" => Some(CompareOp::Contains),
+            "starts_with" => Some(CompareOp::StartsWith),
+            "ends_with" => Some(CompareOp::EndsWith),
+            _ => None,
+        }
+    }
+
+    fn is_ordering(&self) -> bool {
+        matches!(self, CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Literal {
+    Int(i64),
+    Str(String),
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Literal::Int(n) => write!(f, "{}", n),
+            Literal::Str(s) => write!(f, "{}", crate::json::quote(s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Compare(Field, CompareOp, Literal),
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Filter, FilterError> {
+        let tokens = lex(input)?;
+        let mut parser = Parser { tokens, pos: 0, end: input.len(), depth: 0 };
+        let filter = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(filter),
+            Some((at, _)) => Err(FilterError { at: *at, kind: FilterErrorKind::Expected("`&&`, `||` or the end of the filter") }),
+        }
+    }
+
+    pub fn matches(&self, event: &WebEvent) -> bool {
+        self.matches_at(event, None)
+    }
+
+    // For events from a recorded session, where `t` has a value.
+    pub fn matches_at(&self, event: &WebEvent, at: Option<Duration>) -> bool {
+        match self {
+            Filter::Compare(field, op, literal) => compare(event, at, *field, *op, literal),
+            Filter::Not(inner) => !inner.matches_at(event, at),
+            Filter::And(a, b) => a.matches_at(event, at) && b.matches_at(event, at),
+            Filter::Or(a, b) => a.matches_at(event, at) || b.matches_at(event, at),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Filter, FilterError> {
+        Filter::parse(s)
+    }
+}
+
+// Prints a filter that parses back to the same thing, with parentheses only where
+// they're needed.
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Filter::Compare(field, op, literal) => write!(f, "{} {} {}", field.name(), op.symbol(), literal),
+            Filter::Not(inner) if matches!(**inner, Filter::Compare(..)) => write!(f, "!({})", inner),
+            Filter::Not(inner) => write!(f, "!{}", Wrapped(inner, 3)),
+            // Both chain to the left, so a chain on the right came from parentheses.
+            Filter::And(a, b) => write!(f, "{} && {}", Wrapped(a, 2), Wrapped(b, 3)),
+            Filter::Or(a, b) => write!(f, "{} || {}", a, Wrapped(b, 2)),
+        }
+    }
+}
+
+// A filter inside an operator that binds at least as tightly as `level` (`||` is 1,
+// `&&` 2, `!` 3), parenthesized if it binds less tightly than that.
+struct Wrapped<'a>(&'a Filter, u8);
+
+impl fmt::Display for Wrapped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = match self.0 {
+            Filter::Or(..) => 1,
+            Filter::And(..) => 2,
+            Filter::Not(_) | Filter::Compare(..) => 3,
+        };
+        if level < self.1 { write!(f, "({})", self.0) } else { write!(f, "{}", self.0) }
+    }
+}
+
+fn compare(event: &WebEvent, at: Option<Duration>, field: Field, op: CompareOp, literal: &Literal) -> bool {
+    match literal {
+        Literal::Int(wanted) => {
+            let value = match (field, event) {
+                (Field::X, WebEvent::Click { x, .. }) => *x,
+                (Field::Y, WebEvent::Click { y, .. }) => *y,
+                (Field::Time, _) => match at {
+                    Some(at) => i64::try_from(at.as_millis()).unwrap_or(i64::MAX),
+                    None => return false,
+                },
+                _ => return false,
+            };
+            match op {
+                CompareOp::Eq => value == *wanted,
+                CompareOp::Ne => value != *wanted,
+                CompareOp::Lt => value < *wanted,
+                CompareOp::Le => value <= *wanted,
+                CompareOp::Gt => value > *wanted,
+                CompareOp::Ge => value >= *wanted,
+                // Ruled out by the parser.
+                CompareOp::Contains | CompareOp::StartsWith | CompareOp::EndsWith => false,
+            }
+        },
+        Literal::Str(wanted) => {
+            let key;
+            let value: &str = match (field, event) {
+                (Field::Type, _) => event.kind().tag(),
+                (Field::Key, WebEvent::KeyPress(c)) => {
+                    key = c.to_string();
+                    &key
+                },
+                (Field::Paste, WebEvent::Paste(text)) => text,
+                _ => return false,
+            };
+            match op {
+                CompareOp::Eq => value == wanted,
+                CompareOp::Ne => value != wanted,
+                CompareOp::Contains => value.contains(wanted.as_str()),
+                CompareOp::StartsWith => value.starts_with(wanted.as_str()),
+                CompareOp::EndsWith => value.ends_with(wanted.as_str()),
+                CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge => false,
+            }
+        },
+    }
+}
+
+// How deep `!`s and parentheses can be nested. The parser recurses once per level, so
+// without a limit a filter of 100,000 `!`s would overflow the stack.
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterErrorKind {
+    UnexpectedChar(char),
+    UnterminatedString,
+    // A `\u` escape that isn't four hex digits, or half a surrogate pair.
+    InvalidEscape,
+    InvalidNumber(String),
+    UnknownField(String),
+    UnknownType(String),
+    // The field holds numbers but was compared to text, or the other way around.
+    TypeMismatch { field: Field, expected: &'static str },
+    // Like `<` on text or `contains` on a number.
+    InvalidOperator { field: Field, op: CompareOp },
+    Expected(&'static str),
+    // More than `MAX_DEPTH` `!`s and parentheses inside each other.
+    TooDeep,
+}
+
+// `at` is the byte offset in the filter where the problem is, for pointing at it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    pub at: usize,
+    pub kind: FilterErrorKind,
+}
+
+impl fmt::Display for FilterErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilterErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            FilterErrorKind::UnterminatedString => write!(f, "string is missing its closing quote"),
+            FilterErrorKind::InvalidEscape => write!(f, "invalid \\u escape in string"),
+            FilterErrorKind::InvalidNumber(n) => write!(f, "\"{}\" is not a valid number", n),
+            FilterErrorKind::UnknownField(name) => {
+                let names: Vec<&str> = Field::all().map(|field| field.name()).collect();
+                write!(f, "unknown field \"{}\", expected one of: {}", name, names.join(", "))
+            },
+            FilterErrorKind::UnknownType(name) => {
+                let tags: Vec<&str> = EventKind::all().map(|kind| kind.tag()).collect();
+                write!(f, "unknown event type \"{}\", expected one of: {}", name, tags.join(", "))
+            },
+            FilterErrorKind::TypeMismatch { field, expected } => write!(f, "field \"{}\" is compared to {}", field.name(), expected),
+            FilterErrorKind::InvalidOperator { field, op } => {
+                let kind = if field.is_numeric() { "a number" } else { "text" };
+                write!(f, "`{}` can't be used on \"{}\", which is {}", op.symbol(), field.name(), kind)
+            },
+            FilterErrorKind::Expected(what) => write!(f, "expected {}", what),
+            FilterErrorKind::TooDeep => write!(f, "nested more than {} levels deep", MAX_DEPTH),
+        }
+    }
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at {}: {}", self.at, self.kind)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Int(String),
+    Str(String),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn lex(input: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((at, c)) = chars.next() {
+        let mut next_is = |expected: char| chars.next_if(|&(_, c)| c == expected).is_some();
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' if next_is('&') => Token::And,
+            '|' if next_is('|') => Token::Or,
+            '=' if next_is('=') => Token::Op(CompareOp::Eq),
+            '!' if next_is('=') => Token::Op(CompareOp::Ne),
+            '!' => Token::Not,
+            '<' if next_is('=') => Token::Op(CompareOp::Le),
+            '<' => Token::Op(CompareOp::Lt),
+            '>' if next_is('=') => Token::Op(CompareOp::Ge),
+            '>' => Token::Op(CompareOp::Gt),
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err(FilterError { at, kind: FilterErrorKind::UnterminatedString }),
+                        Some((_, '"')) => break,
+                        // The same escapes as JSON, since `Display` writes strings with
+                        // `json::quote`. Any other escaped character stands for itself.
+                        Some((escape_at, '\\')) => match chars.next() {
+                            Some((_, 'n')) => s.push('\n'),
+                            Some((_, 't')) => s.push('\t'),
+                            Some((_, 'r')) => s.push('\r'),
+                            Some((_, 'b')) => s.push('\u{8}'),
+                            Some((_, 'f')) => s.push('\u{c}'),
+                            Some((_, 'u')) => match unicode_escape(&mut chars) {
+                                Some(c) => s.push(c),
+                                None => return Err(FilterError { at: escape_at, kind: FilterErrorKind::InvalidEscape }),
+                            },
+                            Some((_, c)) => s.push(c),
+                            None => return Err(FilterError { at, kind: FilterErrorKind::UnterminatedString }),
+                        },
+                        Some((_, c)) => s.push(c),
+                    }
+                }
+                Token::Str(s)
+            },
+            c if c == '-' || c.is_ascii_digit() => {
+                let mut s = c.to_string();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_') {
+                    s.push(c);
+                }
+                Token::Int(s)
+            },
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut s = c.to_string();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_') {
+                    s.push(c);
+                }
+                match CompareOp::from_word(&s) {
+                    Some(op) => Token::Op(op),
+                    None => Token::Word(s),
+                }
+            },
+            c => return Err(FilterError { at, kind: FilterErrorKind::UnexpectedChar(c) }),
+        };
+        tokens.push((at, token));
+    }
+    Ok(tokens)
+}
+
+fn hex4(chars: &mut Peekable<CharIndices>) -> Option<u32> {
+    let mut n = 0;
+    for _ in 0..4 {
+        n = n * 16 + chars.next()?.1.to_digit(16)?;
+    }
+    Some(n)
+}
+
+// After `\u`. Like in JSON, a character outside the Basic Multilingual Plane is two
+// escapes, a surrogate pair, that have to be combined.
+fn unicode_escape(chars: &mut Peekable<CharIndices>) -> Option<char> {
+    let high = hex4(chars)?;
+    if !(0xd800..0xdc00).contains(&high) {
+        // `None` for a lone low surrogate.
+        return char::from_u32(high);
+    }
+    if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' {
+        return None;
+    }
+    let low = hex4(chars)?;
+    if !(0xdc00..0xe000).contains(&low) {
+        return None;
+    }
+    char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    // Where errors at the end of the input point.
+    end: usize,
+    // How many `!`s and parentheses the parser is inside.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn at(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(at, _)| *at)
+    }
+
+    fn error(&self, kind: FilterErrorKind) -> FilterError {
+        FilterError { at: self.at(), kind }
+    }
+
+    fn or(&mut self) -> Result<Filter, FilterError> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter, FilterError> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Filter::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    // Parses what's after a `!` or `(` one level deeper, if there's room for another
+    // level. The error points at the token that would have gone too deep.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Filter, FilterError>) -> Result<Filter, FilterError> {
+        if self.depth == MAX_DEPTH {
+            return Err(FilterError { at: self.tokens[self.pos - 1].0, kind: FilterErrorKind::TooDeep });
+        }
+        self.depth += 1;
+        let filter = parse(self);
+        self.depth -= 1;
+        filter
+    }
+
+    fn unary(&mut self) -> Result<Filter, FilterError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Filter::Not(Box::new(self.nested(Parser::unary)?)))
+            },
+            Some(Token::Open) => {
+                self.pos += 1;
+                let inner = self.nested(Parser::or)?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error(FilterErrorKind::Expected("`)`")));
+                }
+                self.pos += 1;
+                Ok(inner)
+            },
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Filter, FilterError> {
+        let field = match self.peek() {
+            Some(Token::Word(name)) => match Field::all().find(|field| field.name() == name) {
+                Some(field) => field,
+                None => return Err(self.error(FilterErrorKind::UnknownField(name.clone()))),
+            },
+            _ => return Err(self.error(FilterErrorKind::Expected("a field name or `(`"))),
+        };
+        self.pos += 1;
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            _ => return Err(self.error(FilterErrorKind::Expected("a comparison like `==` or `contains`"))),
+        };
+        self.pos += 1;
+        let value_at = self.at();
+        let literal = match self.peek() {
+            Some(Token::Int(n)) => match n.parse() {
+                Ok(n) => Literal::Int(n),
+                Err(_) => return Err(self.error(FilterErrorKind::InvalidNumber(n.clone()))),
+            },
+            Some(Token::Str(s) | Token::Word(s)) => Literal::Str(s.clone()),
+            _ => return Err(self.error(FilterErrorKind::Expected("a number or a string"))),
+        };
+        self.pos += 1;
+
+        // Check it all makes sense together, pointing at whichever part doesn't.
+        let mismatch = |expected| FilterError { at: value_at, kind: FilterErrorKind::TypeMismatch { field, expected } };
+        match (&literal, field.is_numeric()) {
+            (Literal::Str(_), true) => return Err(mismatch("text, but it holds numbers")),
+            (Literal::Int(_), false) => return Err(mismatch("a number, but it holds text")),
+            _ => {},
+        }
+        let valid_op = if field.is_numeric() {
+            !matches!(op, CompareOp::Contains | CompareOp::StartsWith | CompareOp::EndsWith)
+        } else {
+            !op.is_ordering() && (field != Field::Type || matches!(op, CompareOp::Eq | CompareOp::Ne))
+        };
+        if !valid_op {
+            return Err(FilterError { at: self.tokens[self.pos - 2].0, kind: FilterErrorKind::InvalidOperator { field, op } });
+        }
+        if let (Field::Type, Literal::Str(tag)) = (field, &literal) {
+            if EventKind::from_tag(tag).is_none() {
+                return Err(FilterError { at: value_at, kind: FilterErrorKind::UnknownType(tag.clone()) });
+            }
+        }
+        Ok(Filter::Compare(field, op, literal))
+    }
+}
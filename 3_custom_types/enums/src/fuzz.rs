@@ -0,0 +1,438 @@
+// Random `WebEvent` streams for shaking bugs out of handlers.
+//
+// `Generator` makes an endless stream of events from a seed, so the same seed always
+// gives the same events. It leans towards what real input looks like (mostly key
+// presses, screen-sized coordinates) but mixes in what breaks things: random Unicode,
+// control and private-use characters, empty and very long pastes, and coordinates
+// like `i64::MIN`.
+//
+// `fuzz` runs a handler over stream after stream, catching panics. When one panics,
+// the stream is shrunk to as few and as simple events as still make it panic, which
+// is usually small enough to see the bug at a glance.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::jsonl;
+use crate::web_event::{keys, EventKind, WebEvent};
+
+// SplitMix64: tiny, fast, and plenty random for generating test input. Not for
+// anything that needs to be unpredictable.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // A number in `0..n`. The bias from using `%` is far too small to matter here.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "can't pick from an empty range");
+        self.next_u64() % n
+    }
+
+    // An index into `weights`, picked in proportion to them.
+    pub fn weighted(&mut self, weights: &[u32]) -> usize {
+        let total: u64 = weights.iter().map(|&w| w as u64).sum();
+        let mut pick = self.below(total);
+        for (i, &weight) in weights.iter().enumerate() {
+            if pick < weight as u64 {
+                return i;
+            }
+            pick -= weight as u64;
+        }
+        unreachable!("`pick` is below the total of the weights")
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+// How often each kind of event comes up, relative to the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights {
+    pub page_load: u32,
+    pub page_unload: u32,
+    pub key_press: u32,
+    pub paste: u32,
+    pub click: u32,
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights { page_load: 3, page_unload: 3, key_press: 60, paste: 10, click: 24 }
+    }
+}
+
+impl Weights {
+    // Only ever `kind`.
+    pub fn only(kind: EventKind) -> Weights {
+        let mut weights = Weights { page_load: 0, page_unload: 0, key_press: 0, paste: 0, click: 0 };
+        *weights.get_mut(kind) = 1;
+        weights
+    }
+
+    fn check(&self) {
+        assert!(self.total() > 0, "at least one kind of event needs a weight");
+    }
+
+    fn total(&self) -> u64 {
+        [self.page_load, self.page_unload, self.key_press, self.paste, self.click].iter().map(|&w| w as u64).sum()
+    }
+
+    fn get_mut(&mut self, kind: EventKind) -> &mut u32 {
+        match kind {
+            EventKind::PageLoad => &mut self.page_load,
+            EventKind::PageUnload => &mut self.page_unload,
+            EventKind::KeyPress => &mut self.key_press,
+            EventKind::Paste => &mut self.paste,
+            EventKind::Click => &mut self.click,
+        }
+    }
+}
+
+const SPECIAL_KEYS: &[char] = &[
+    keys::BACKSPACE,
+    keys::TAB,
+    keys::ENTER,
+    keys::ESCAPE,
+    keys::UP,
+    keys::DOWN,
+    keys::LEFT,
+    keys::RIGHT,
+    keys::INSERT,
+    keys::DELETE,
+    keys::HOME,
+    keys::END,
+    keys::PAGE_UP,
+    keys::PAGE_DOWN,
+];
+
+// Characters that have broken text handling somewhere before: NUL, the byte order
+// mark, zero-width and direction-changing characters, a combining accent, the
+// replacement character and the very last code point.
+const AWKWARD_CHARS: &[char] = &['\0', '\u{feff}', '\u{200b}', '\u{202e}', '\u{301}', '\u{fffd}', '\u{10ffff}', '\u{7f}'];
+
+const EDGE_COORDINATES: &[i64] = &[0, -1, 1, i64::MIN, i64::MIN + 1, i64::MAX, i64::MAX - 1];
+
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Rng,
+    weights: Weights,
+    max_paste_len: usize,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator { rng: Rng::new(seed), weights: Weights::default(), max_paste_len: 10_000 }
+    }
+
+    pub fn weights(mut self, weights: Weights) -> Generator {
+        weights.check();
+        self.weights = weights;
+        self
+    }
+
+    // In characters. Most pastes are much shorter.
+    pub fn max_paste_len(mut self, len: usize) -> Generator {
+        self.max_paste_len = len;
+        self
+    }
+
+    pub fn event(&mut self) -> WebEvent {
+        let w = self.weights;
+        match self.rng.weighted(&[w.page_load, w.page_unload, w.key_press, w.paste, w.click]) {
+            0 => WebEvent::PageLoad,
+            1 => WebEvent::PageUnload,
+            2 => WebEvent::KeyPress(self.char()),
+            3 => WebEvent::Paste(self.text()),
+            _ => WebEvent::Click { x: self.coordinate(), y: self.coordinate() },
+        }
+    }
+
+    fn char(&mut self) -> char {
+        match self.rng.weighted(&[50, 10, 5, 5, 10, 10, 10]) {
+            0 => char::from(b' ' + self.rng.below(95) as u8),
+            1 => *self.rng.pick(SPECIAL_KEYS),
+            2 => keys::function(1 + self.rng.below(35) as u32).expect("F1 to F35 exist"),
+            3 => char::from(self.rng.below(0x20) as u8),
+            4 => *self.rng.pick(AWKWARD_CHARS),
+            // Accented Latin, Greek and Cyrillic, CJK, then emoji.
+            5 => {
+                let (start, len) = *self.rng.pick(&[(0xc0, 0x180), (0x370, 0x200), (0x4e00, 0x5200), (0x1f300, 0x300)]);
+                char::from_u32(start + self.rng.below(len) as u32).unwrap_or('?')
+            },
+            // Anything at all, skipping the surrogates that aren't valid `char`s.
+            _ => loop {
+                if let Some(c) = char::from_u32(self.rng.below(0x11_0000) as u32) {
+                    break c;
+                }
+            },
+        }
+    }
+
+    fn text(&mut self) -> String {
+        let len = match self.rng.weighted(&[70, 25, 5]) {
+            0 => self.rng.below(20),
+            1 => 20 + self.rng.below(480),
+            _ => self.rng.below(self.max_paste_len as u64 + 1),
+        };
+        let len = (len as usize).min(self.max_paste_len);
+        (0..len).map(|_| if self.rng.below(20) == 0 { '\n' } else { self.char() }).collect()
+    }
+
+    fn coordinate(&mut self) -> i64 {
+        match self.rng.weighted(&[80, 10, 10]) {
+            0 => self.rng.below(4_000) as i64,
+            1 => *self.rng.pick(EDGE_COORDINATES),
+            _ => self.rng.next_u64() as i64,
+        }
+    }
+}
+
+impl Iterator for Generator {
+    type Item = WebEvent;
+
+    // Never runs out.
+    fn next(&mut self) -> Option<WebEvent> {
+        Some(self.event())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzConfig {
+    pub seed: u64,
+    pub streams: usize,
+    // Each stream gets a new handler, so bugs that need some history have a chance to
+    // show up without streams getting too long to shrink.
+    pub stream_len: usize,
+    pub weights: Weights,
+    pub max_paste_len: usize,
+}
+
+// A million events.
+impl Default for FuzzConfig {
+    fn default() -> FuzzConfig {
+        FuzzConfig { seed: 0, streams: 1_000, stream_len: 1_000, weights: Weights::default(), max_paste_len: 10_000 }
+    }
+}
+
+impl FuzzConfig {
+    // Where stream `index` comes from, so a failing stream can be made again on its
+    // own with `Generator::new`.
+    pub fn stream_seed(&self, index: usize) -> u64 {
+        Rng::new(self.seed ^ (index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)).next_u64()
+    }
+
+    pub fn stream(&self, index: usize) -> Vec<WebEvent> {
+        let generator = Generator::new(self.stream_seed(index)).weights(self.weights).max_paste_len(self.max_paste_len);
+        generator.take(self.stream_len).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub stream_seed: u64,
+    // The events up to the panic, before shrinking.
+    pub original_len: usize,
+    // As few events as still make the handler panic.
+    pub events: Vec<WebEvent>,
+    // What the handler panicked with on `events`.
+    pub message: String,
+}
+
+// The events come out as JSON Lines, ready to save and replay.
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "handler panicked: {} (stream seed {}, shrunk from {} events to {})",
+            self.message,
+            self.stream_seed,
+            self.original_len,
+            self.events.len()
+        )?;
+        for event in &self.events {
+            writeln!(f, "{}", jsonl::to_json(event))?;
+        }
+        Ok(())
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "(not a string)".to_owned(),
+    }
+}
+
+// Runs a fresh handler over `events`. On a panic, returns its message and how many
+// events it took to get there.
+pub fn check<F, H>(events: &[WebEvent], make_handler: &mut F) -> Option<(usize, String)>
+where
+    F: FnMut() -> H,
+    H: FnMut(&WebEvent),
+{
+    let mut handled = 0;
+    // Making the handler (and dropping it) can panic too.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut handler = make_handler();
+        for event in events {
+            handled += 1;
+            handler(event);
+        }
+    }));
+    result.err().map(|payload| (handled, panic_message(&*payload)))
+}
+
+// Simpler versions of one event, simplest first.
+fn simplifications(event: &WebEvent) -> Vec<WebEvent> {
+    match event {
+        WebEvent::Paste(text) => {
+            let chars: Vec<char> = text.chars().collect();
+            let half = chars.len() / 2;
+            let mut simpler = vec![String::new(), chars[..half].iter().collect(), chars[half..].iter().collect()];
+            // Dropping one character at a time is slow, so only once it's short.
+            if chars.len() <= 32 {
+                for i in 0..chars.len() {
+                    simpler.push(chars.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, c)| c).collect());
+                }
+            }
+            simpler.into_iter().filter(|s| s.len() < text.len()).map(WebEvent::Paste).collect()
+        },
+        WebEvent::Click { x, y } => {
+            let mut simpler = Vec::new();
+            for (new_x, new_y) in [(0, *y), (*x, 0), (x / 2, *y), (*x, y / 2)] {
+                if (new_x, new_y) != (*x, *y) {
+                    simpler.push(WebEvent::Click { x: new_x, y: new_y });
+                }
+            }
+            simpler
+        },
+        WebEvent::KeyPress(c) if *c != 'a' => vec![WebEvent::KeyPress('a')],
+        _ => Vec::new(),
+    }
+}
+
+// Makes a failing stream as small as it'll go while the handler still panics:
+// first by dropping events, in big chunks and then smaller ones, and then by making
+// the events that are left simpler. The panic doesn't have to be the same one, only
+// a panic.
+pub fn shrink<F, H>(mut events: Vec<WebEvent>, make_handler: &mut F) -> Vec<WebEvent>
+where
+    F: FnMut() -> H,
+    H: FnMut(&WebEvent),
+{
+    let mut fails = |events: &[WebEvent]| check(events, make_handler).map(|(handled, _)| handled);
+    let Some(handled) = fails(&events) else { return events };
+    events.truncate(handled);
+    loop {
+        let mut progress = false;
+        let mut chunk = events.len() / 2;
+        while chunk > 0 {
+            let mut start = 0;
+            while start < events.len() {
+                let end = (start + chunk).min(events.len());
+                let candidate: Vec<WebEvent> = events[..start].iter().chain(&events[end..]).cloned().collect();
+                match fails(&candidate) {
+                    Some(handled) => {
+                        events = candidate;
+                        events.truncate(handled);
+                        progress = true;
+                    },
+                    None => start = end,
+                }
+            }
+            chunk /= 2;
+        }
+        for i in 0..events.len() {
+            for simpler in simplifications(&events[i]) {
+                let mut candidate = events.clone();
+                candidate[i] = simpler;
+                if fails(&candidate).is_some() {
+                    events = candidate;
+                    progress = true;
+                    break;
+                }
+            }
+        }
+        if !progress {
+            return events;
+        }
+    }
+}
+
+// Runs `config.streams` streams through handlers from `make_handler`, and returns how
+// many events were handled, or the first failure, shrunk.
+//
+// Panics are expected here, so the panic hook is switched off while it runs, which
+// also hides panics from any other threads for the duration. A bad config panics
+// before that, with the hook still in place.
+pub fn fuzz<F, H>(config: &FuzzConfig, mut make_handler: F) -> Result<u64, Failure>
+where
+    F: FnMut() -> H,
+    H: FnMut(&WebEvent),
+{
+    config.weights.check();
+    let quiet = QuietPanics::new();
+    // Anything that still gets out is caught here, because the hook can't be put back
+    // while a panic is unwinding. It carries on once the hook is back.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| run_streams(config, &mut make_handler)));
+    drop(quiet);
+    result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo) + Sync + Send>;
+
+// Switches the panic hook off until dropped, then puts the old one back.
+struct QuietPanics {
+    hook: Option<PanicHook>,
+}
+
+impl QuietPanics {
+    fn new() -> QuietPanics {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        QuietPanics { hook: Some(hook) }
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        if let Some(hook) = self.hook.take() {
+            panic::set_hook(hook);
+        }
+    }
+}
+
+fn run_streams<F, H>(config: &FuzzConfig, make_handler: &mut F) -> Result<u64, Failure>
+where
+    F: FnMut() -> H,
+    H: FnMut(&WebEvent),
+{
+    let mut total = 0;
+    for index in 0..config.streams {
+        let events = config.stream(index);
+        let Some((handled, _)) = check(&events, make_handler) else {
+            total += events.len() as u64;
+            continue;
+        };
+        let events = shrink(events[..handled].to_vec(), make_handler);
+        let message = check(&events, make_handler).map(|(_, message)| message).unwrap_or_default();
+        return Err(Failure { stream_seed: config.stream_seed(index), original_len: handled, events, message });
+    }
+    Ok(total)
+}
@@ -0,0 +1,242 @@
+// Turns low-level input into gestures: double and triple clicks, drags, and key
+// chords like `Ctrl+K Ctrl+C`.
+//
+// A `WebEvent::Click` is a press and release in one, which is all a click needs, but
+// drags need to see the pointer go down, move and come back up. So the recognizer
+// takes `Input`s, which are either a `WebEvent` or one of those pointer steps.
+//
+// Clicks are reported straight away, with a count like the browser's `event.detail`:
+// a double click is a `count: 1` click followed by a `count: 2` one. Waiting to see
+// whether a second click comes would make every single click feel slow.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::web_event::{keys, WebEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerAction {
+    Down,
+    Move,
+    Up,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Event(WebEvent),
+    Pointer { action: PointerAction, x: i64, y: i64 },
+}
+
+impl From<WebEvent> for Input {
+    fn from(event: WebEvent) -> Input {
+        Input::Event(event)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gesture {
+    // `count` is 1, 2 or 3. A fourth quick click starts over at 1.
+    Click { x: i64, y: i64, count: u32 },
+    // Where the pointer went down, reported once it has moved far enough away.
+    DragStart { x: i64, y: i64 },
+    DragMove { x: i64, y: i64 },
+    DragEnd { x: i64, y: i64 },
+    // The name a chord was registered under.
+    Chord(String),
+    // A key that isn't part of any chord, or that turned out not to finish one.
+    Key(char),
+    // Everything else (pastes, page loads) passes through untouched.
+    Other(WebEvent),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureConfig {
+    // The longest gap between clicks that still counts towards a double click.
+    pub multi_click_interval: Duration,
+    // How far apart, in either direction, the clicks of a double click can be.
+    pub multi_click_distance: u64,
+    // How far the pointer has to move while down before it's a drag and not a click.
+    pub drag_threshold: u64,
+    // How long the keys of a chord can be apart.
+    pub chord_timeout: Duration,
+}
+
+// Close to what desktop environments use.
+impl Default for GestureConfig {
+    fn default() -> GestureConfig {
+        GestureConfig {
+            multi_click_interval: Duration::from_millis(500),
+            multi_click_distance: 4,
+            drag_threshold: 4,
+            chord_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordError {
+    Empty,
+    UnknownKey(String),
+}
+
+impl fmt::Display for ChordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChordError::Empty => write!(f, "a chord needs at least one key"),
+            ChordError::UnknownKey(key) => write!(f, "unknown key \"{}\"", key),
+        }
+    }
+}
+
+impl std::error::Error for ChordError {}
+
+#[derive(Debug, Clone, Copy)]
+enum Pointer {
+    Up,
+    Pressed { x: i64, y: i64 },
+    // Where the pointer was last seen.
+    Dragging { x: i64, y: i64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastClick {
+    at: Duration,
+    x: i64,
+    y: i64,
+    count: u32,
+}
+
+#[derive(Debug)]
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    chords: Vec<(Vec<char>, String)>,
+    pointer: Pointer,
+    last_click: Option<LastClick>,
+    // Keys that could still turn into a chord, and when the last of them came.
+    held: Vec<char>,
+    last_key: Duration,
+}
+
+impl GestureRecognizer {
+    pub fn new(config: GestureConfig) -> GestureRecognizer {
+        GestureRecognizer { config, chords: Vec::new(), pointer: Pointer::Up, last_click: None, held: Vec::new(), last_key: Duration::ZERO }
+    }
+
+    // Registers a chord written as space-separated keys, in the notation `keys::parse`
+    // understands: `"Ctrl+K Ctrl+C"`, `"g g"`.
+    pub fn chord(&mut self, spec: &str, name: &str) -> Result<(), ChordError> {
+        let keys = spec
+            .split_whitespace()
+            .map(|key| keys::parse(key).ok_or_else(|| ChordError::UnknownKey(key.to_owned())))
+            .collect::<Result<Vec<char>, ChordError>>()?;
+        if keys.is_empty() {
+            return Err(ChordError::Empty);
+        }
+        self.chords.push((keys, name.to_owned()));
+        Ok(())
+    }
+
+    // `at` is when the input happened, on any clock, as long as it never goes backwards.
+    pub fn feed(&mut self, at: Duration, input: Input) -> Vec<Gesture> {
+        let mut out = self.tick(at);
+        match input {
+            Input::Event(WebEvent::KeyPress(c)) => {
+                self.held.push(c);
+                self.last_key = at;
+                self.resolve_keys(false, &mut out);
+            },
+            input => {
+                // Anything else in between means the keys weren't a chord after all.
+                self.resolve_keys(true, &mut out);
+                match input {
+                    Input::Event(WebEvent::Click { x, y }) => out.push(self.click(at, x, y)),
+                    Input::Event(event) => out.push(Gesture::Other(event)),
+                    Input::Pointer { action, x, y } => self.pointer(at, action, x, y, &mut out),
+                }
+            },
+        }
+        out
+    }
+
+    // Lets time pass without any input, so keys waiting on a chord that never got
+    // finished are let go once `chord_timeout` is up.
+    pub fn tick(&mut self, now: Duration) -> Vec<Gesture> {
+        let mut out = Vec::new();
+        if !self.held.is_empty() && now.saturating_sub(self.last_key) > self.config.chord_timeout {
+            self.resolve_keys(true, &mut out);
+        }
+        out
+    }
+
+    fn click(&mut self, at: Duration, x: i64, y: i64) -> Gesture {
+        let near = |last: &LastClick| {
+            at.saturating_sub(last.at) <= self.config.multi_click_interval
+                && x.abs_diff(last.x) <= self.config.multi_click_distance
+                && y.abs_diff(last.y) <= self.config.multi_click_distance
+        };
+        let count = match self.last_click {
+            Some(last) if last.count < 3 && near(&last) => last.count + 1,
+            _ => 1,
+        };
+        self.last_click = Some(LastClick { at, x, y, count });
+        Gesture::Click { x, y, count }
+    }
+
+    fn pointer(&mut self, at: Duration, action: PointerAction, x: i64, y: i64, out: &mut Vec<Gesture>) {
+        self.pointer = match (self.pointer, action) {
+            // A press in the middle of a drag means we missed the release, so the drag
+            // ends where the pointer was last seen before the new press starts.
+            (Pointer::Dragging { x: last_x, y: last_y }, PointerAction::Down) => {
+                out.push(Gesture::DragEnd { x: last_x, y: last_y });
+                Pointer::Pressed { x, y }
+            },
+            (_, PointerAction::Down) => Pointer::Pressed { x, y },
+            (Pointer::Pressed { x: from_x, y: from_y }, PointerAction::Move) => {
+                let far = x.abs_diff(from_x) > self.config.drag_threshold || y.abs_diff(from_y) > self.config.drag_threshold;
+                if !far {
+                    return;
+                }
+                out.push(Gesture::DragStart { x: from_x, y: from_y });
+                out.push(Gesture::DragMove { x, y });
+                Pointer::Dragging { x, y }
+            },
+            (Pointer::Dragging { .. }, PointerAction::Move) => {
+                out.push(Gesture::DragMove { x, y });
+                Pointer::Dragging { x, y }
+            },
+            (Pointer::Dragging { .. }, PointerAction::Up) => {
+                out.push(Gesture::DragEnd { x, y });
+                Pointer::Up
+            },
+            // Released without going far: a click where it went down, since a little
+            // wobble shouldn't move it.
+            (Pointer::Pressed { x: from_x, y: from_y }, PointerAction::Up) => {
+                out.push(self.click(at, from_x, from_y));
+                Pointer::Up
+            },
+            // Hovering, or a release we never saw the press for.
+            (Pointer::Up, _) => Pointer::Up,
+        };
+    }
+
+    // Emits whatever the held keys add up to. Unless `force` is set, it stops while
+    // they're still the start of some longer chord, to wait for the next key.
+    fn resolve_keys(&mut self, force: bool, out: &mut Vec<Gesture>) {
+        while !self.held.is_empty() {
+            let held = &self.held;
+            if !force && self.chords.iter().any(|(keys, _)| keys.len() > held.len() && keys.starts_with(held)) {
+                return;
+            }
+            // The longest chord the held keys start with wins. If none does, the
+            // first key was just a key, and the rest get another look on their own.
+            let matched = self.chords.iter().filter(|(keys, _)| held.starts_with(keys)).max_by_key(|(keys, _)| keys.len());
+            match matched {
+                Some((keys, name)) => {
+                    out.push(Gesture::Chord(name.clone()));
+                    self.held.drain(..keys.len());
+                },
+                None => out.push(Gesture::Key(self.held.remove(0))),
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// Interval arithmetic: instead of one `f64` that's probably a little off, carry a lower
+// and an upper bound that the true value is guaranteed to lie between.
+//
+// Every operation rounds its lower bound down and its upper bound up by one ULP (the
+// gap between neighbouring floats). The result of a single float operation is within
+// half an ULP of the exact answer, so after widening the exact answer is always inside,
+// no matter how the rounding went. The price is that intervals only ever get wider.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::operations::{Operand, Operations};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    // `None` if `lo > hi` or either bound is NaN. A NaN bound would make every
+    // comparison false, so `contains` would quietly say no to everything.
+    pub fn checked_new(lo: f64, hi: f64) -> Option<Interval> {
+        if !lo.is_nan() && !hi.is_nan() && lo <= hi { Some(Interval { lo, hi }) } else { None }
+    }
+
+    pub fn new(lo: f64, hi: f64) -> Interval {
+        Interval::checked_new(lo, hi).expect("interval bounds out of order or NaN")
+    }
+
+    // A value that's known exactly, like an `f32` coordinate (every `f32` is exactly
+    // representable as an `f64`, so `x as f64` is fine). `None` for NaN.
+    pub fn checked_point(x: f64) -> Option<Interval> {
+        Interval::checked_new(x, x)
+    }
+
+    pub fn point(x: f64) -> Interval {
+        Interval::checked_point(x).expect("interval point is NaN")
+    }
+
+    // A decimal number as written, like `"10.9"`. The nearest `f64` is usually not
+    // exactly 10.9, but parsing rounds correctly, so the exact value lies between the
+    // floats on either side of it.
+    pub fn from_decimal_str(s: &str) -> Option<Interval> {
+        let x: f64 = s.trim().parse().ok()?;
+        if !x.is_finite() {
+            return None;
+        }
+        Some(Interval { lo: x.next_down(), hi: x.next_up() })
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn midpoint(&self) -> f64 {
+        self.lo + (self.hi - self.lo) / 2.0
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    pub fn contains_zero(&self) -> bool {
+        self.contains(0.0)
+    }
+
+    // The smallest interval containing both.
+    pub fn hull(&self, other: &Interval) -> Interval {
+        Interval { lo: self.lo.min(other.lo), hi: self.hi.max(other.hi) }
+    }
+
+    // Widens a freshly computed pair of bounds by one ULP each way. `None` if a bound
+    // came out NaN, from adding infinities with opposite signs and the like.
+    fn outward(lo: f64, hi: f64) -> Option<Interval> {
+        Interval::checked_new(lo.next_down(), hi.next_up())
+    }
+
+    pub fn checked_add(&self, other: &Interval) -> Option<Interval> {
+        Interval::outward(self.lo + other.lo, self.hi + other.hi)
+    }
+
+    // The smallest result comes from the smallest `self` minus the biggest `other`.
+    pub fn checked_sub(&self, other: &Interval) -> Option<Interval> {
+        Interval::outward(self.lo - other.hi, self.hi - other.lo)
+    }
+
+    // With mixed signs any of the four corner products can be the smallest or biggest,
+    // so all four are checked. An infinite bound only means "unbounded", and every
+    // value in the interval is finite, so a corner that's `0 * inf` is 0 (exactly, so
+    // it isn't widened), and `[0, 1] * [1, inf]` is `[0, inf]`.
+    pub fn checked_mul(&self, other: &Interval) -> Option<Interval> {
+        let corner = |a: f64, b: f64| if a == 0.0 || b == 0.0 { (0.0, 0.0) } else { ((a * b).next_down(), (a * b).next_up()) };
+        let corners = [corner(self.lo, other.lo), corner(self.lo, other.hi), corner(self.hi, other.lo), corner(self.hi, other.hi)];
+        let lo = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+        let hi = corners.iter().map(|c| c.1).fold(f64::NEG_INFINITY, f64::max);
+        Interval::checked_new(lo, hi)
+    }
+
+    // `None` if `other` contains zero, since the quotient could then be anything.
+    pub fn checked_div(&self, other: &Interval) -> Option<Interval> {
+        if other.contains_zero() {
+            return None;
+        }
+        // Dividing by [a, b] is multiplying by [1/b, 1/a]. Both reciprocals are rounded,
+        // so they're widened too before the multiplication widens again.
+        let recip = Interval::outward(1.0 / other.hi, 1.0 / other.lo)?;
+        self.checked_mul(&recip)
+    }
+
+    // `None` if the whole interval is negative. A partly negative one is clipped at 0,
+    // because a value known to be a length can't really be below zero.
+    pub fn checked_sqrt(&self) -> Option<Interval> {
+        if self.hi < 0.0 {
+            return None;
+        }
+        let lo = self.lo.max(0.0).sqrt().next_down().max(0.0);
+        Some(Interval { lo, hi: self.hi.sqrt().next_up() })
+    }
+
+    pub fn sqrt(&self) -> Interval {
+        self.checked_sqrt().expect("square root of a negative interval")
+    }
+
+    // Exact, so no widening is needed.
+    pub fn abs(&self) -> Interval {
+        if self.lo >= 0.0 {
+            *self
+        } else if self.hi <= 0.0 {
+            Interval { lo: -self.hi, hi: -self.lo }
+        } else {
+            Interval { lo: 0.0, hi: self.hi.max(-self.lo) }
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Passes precision through, so `{:.3}` prints `[1.200, 1.201]`.
+        match f.precision() {
+            Some(p) => write!(f, "[{:.*}, {:.*}]", p, self.lo, p, self.hi),
+            None => write!(f, "[{}, {}]", self.lo, self.hi),
+        }
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Interval {
+        Interval { lo: -self.hi, hi: -self.lo }
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, other: Interval) -> Interval {
+        self.checked_add(&other).expect("interval addition of infinities with opposite signs")
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, other: Interval) -> Interval {
+        self.checked_sub(&other).expect("interval subtraction of infinities with the same sign")
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, other: Interval) -> Interval {
+        self.checked_mul(&other).expect("interval multiplication of zero by infinity")
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+
+    fn div(self, other: Interval) -> Interval {
+        self.checked_div(&other).expect("division by an interval containing zero")
+    }
+}
+
+impl Operand for Interval {
+    fn apply(op: Operations, x: Interval, y: Interval) -> Option<Interval> {
+        match op {
+            Operations::Add => x.checked_add(&y),
+            Operations::Subtract => x.checked_sub(&y),
+            Operations::Multiply => x.checked_mul(&y),
+            Operations::Divide => x.checked_div(&y),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+// `WebEvent` streams as JSON Lines: one JSON object per line, with a `type` field
+// naming the variant followed by the variant's data.
+//
+//   {"type":"page_load"}
+//   {"type":"key_press","key":"é"}
+//   {"type":"paste","text":"line one\nline two"}
+//   {"type":"click","x":20,"y":80}
+//
+// Writing always produces exactly this: the same field names in the same order with
+// no spaces, so logs can be diffed and grepped. Reading is more forgiving and accepts
+// any field order, extra whitespace and fields it doesn't know about.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::json::{self, JsonError, Value};
+use crate::web_event::{EventKind, WebEvent};
+
+impl EventKind {
+    // The `type` tag used in the JSON encoding.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            EventKind::PageLoad => "page_load",
+            EventKind::PageUnload => "page_unload",
+            EventKind::KeyPress => "key_press",
+            EventKind::Paste => "paste",
+            EventKind::Click => "click",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<EventKind> {
+        EventKind::all().find(|kind| kind.tag() == tag)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    Io(String),
+    InvalidUtf8,
+    Json(JsonError),
+    NotAnObject,
+    MissingField(&'static str),
+    WrongType { field: &'static str, expected: &'static str },
+    UnknownType(String),
+    // `key` has to be a string of exactly one character.
+    InvalidKey(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::Io(message) => write!(f, "read error: {}", message),
+            ParseErrorKind::InvalidUtf8 => write!(f, "line is not valid UTF-8"),
+            ParseErrorKind::Json(e) => write!(f, "invalid JSON: {}", e),
+            ParseErrorKind::NotAnObject => write!(f, "expected a JSON object"),
+            ParseErrorKind::MissingField(field) => write!(f, "missing field \"{}\"", field),
+            ParseErrorKind::WrongType { field, expected } => write!(f, "field \"{}\" should be {}", field, expected),
+            ParseErrorKind::UnknownType(tag) => write!(f, "unknown event type \"{}\"", tag),
+            ParseErrorKind::InvalidKey(key) => write!(f, "key {:?} is not a single character", key),
+        }
+    }
+}
+
+// Where in the stream a line failed, counting from 1 like an editor does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn to_value(event: &WebEvent) -> Value {
+    let mut fields = vec![("type".to_owned(), Value::String(event.kind().tag().to_owned()))];
+    match event {
+        WebEvent::PageLoad | WebEvent::PageUnload => {},
+        WebEvent::KeyPress(c) => fields.push(("key".to_owned(), Value::String(c.to_string()))),
+        WebEvent::Paste(s) => fields.push(("text".to_owned(), Value::String(s.clone()))),
+        WebEvent::Click { x, y } => {
+            fields.push(("x".to_owned(), Value::Number(x.to_string())));
+            fields.push(("y".to_owned(), Value::Number(y.to_string())));
+        },
+    }
+    Value::Object(fields)
+}
+
+// One line of JSON, without the trailing newline.
+pub fn to_json(event: &WebEvent) -> String {
+    to_value(event).to_string()
+}
+
+pub(crate) fn field<'a>(value: &'a Value, name: &'static str) -> Result<&'a Value, ParseErrorKind> {
+    value.get(name).ok_or(ParseErrorKind::MissingField(name))
+}
+
+pub(crate) fn str_field<'a>(value: &'a Value, name: &'static str) -> Result<&'a str, ParseErrorKind> {
+    field(value, name)?.as_str().ok_or(ParseErrorKind::WrongType { field: name, expected: "a string" })
+}
+
+pub(crate) fn int_field(value: &Value, name: &'static str) -> Result<i64, ParseErrorKind> {
+    field(value, name)?.as_i64().ok_or(ParseErrorKind::WrongType { field: name, expected: "an integer" })
+}
+
+pub(crate) fn uint_field(value: &Value, name: &'static str) -> Result<u64, ParseErrorKind> {
+    field(value, name)?.as_u64().ok_or(ParseErrorKind::WrongType { field: name, expected: "a non-negative integer" })
+}
+
+pub fn from_value(value: &Value) -> Result<WebEvent, ParseErrorKind> {
+    if !matches!(value, Value::Object(_)) {
+        return Err(ParseErrorKind::NotAnObject);
+    }
+    let tag = str_field(value, "type")?;
+    let kind = EventKind::from_tag(tag).ok_or_else(|| ParseErrorKind::UnknownType(tag.to_owned()))?;
+    Ok(match kind {
+        EventKind::PageLoad => WebEvent::PageLoad,
+        EventKind::PageUnload => WebEvent::PageUnload,
+        EventKind::KeyPress => {
+            let key = str_field(value, "key")?;
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => WebEvent::KeyPress(c),
+                _ => return Err(ParseErrorKind::InvalidKey(key.to_owned())),
+            }
+        },
+        EventKind::Paste => WebEvent::Paste(str_field(value, "text")?.to_owned()),
+        EventKind::Click => WebEvent::Click { x: int_field(value, "x")?, y: int_field(value, "y")? },
+    })
+}
+
+pub fn from_json(line: &str) -> Result<WebEvent, ParseErrorKind> {
+    from_value(&json::parse(line).map_err(ParseErrorKind::Json)?)
+}
+
+pub fn write_event<W: Write>(out: &mut W, event: &WebEvent) -> io::Result<()> {
+    writeln!(out, "{}", to_json(event))
+}
+
+// Reads one value per line, so a log doesn't have to fit in memory. Blank lines are
+// skipped. A bad line is reported and reading carries on with the next one, except
+// after a real read error, which ends the stream. `decode` turns each parsed line into
+// a `T`, which lets other line formats built on this one (like recorded sessions)
+// share the reading and the error reporting.
+pub struct JsonLines<R, T> {
+    input: R,
+    decode: fn(&Value) -> Result<T, ParseErrorKind>,
+    line: usize,
+    buf: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead, T> JsonLines<R, T> {
+    pub fn with_decoder(input: R, decode: fn(&Value) -> Result<T, ParseErrorKind>) -> JsonLines<R, T> {
+        JsonLines { input, decode, line: 0, buf: Vec::new(), done: false }
+    }
+
+    // The number of the last line read.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+pub type EventReader<R> = JsonLines<R, WebEvent>;
+
+impl<R: BufRead> EventReader<R> {
+    pub fn new(input: R) -> EventReader<R> {
+        JsonLines::with_decoder(input, from_value)
+    }
+}
+
+impl<R: BufRead, T> Iterator for JsonLines<R, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            // `read_until` rather than `read_line`, so a line that isn't UTF-8 is still
+            // consumed and reading can go on past it.
+            match self.input.read_until(b'\n', &mut self.buf) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line += 1;
+                    let line = self.line;
+                    let Ok(text) = std::str::from_utf8(&self.buf) else {
+                        return Some(Err(ParseError { line, kind: ParseErrorKind::InvalidUtf8 }));
+                    };
+                    let text = text.trim();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let decoded = json::parse(text).map_err(ParseErrorKind::Json).and_then(|value| (self.decode)(&value));
+                    return Some(decoded.map_err(|kind| ParseError { line, kind }));
+                },
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(ParseError { line: self.line + 1, kind: ParseErrorKind::Io(e.to_string()) }));
+                },
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,321 @@
+// Key bindings: sequences of key presses mapped to named commands, vi style, with
+// modes that each have their own bindings.
+//
+// Bindings are usually loaded from a config file:
+//
+//   # How long to wait for the rest of a sequence, in milliseconds.
+//   timeout = 1000
+//
+//   [normal]
+//   h = move-left
+//   g g = go-to-top
+//   Ctrl+W v = split-vertical
+//   Alt+x = command-palette
+//   i = mode insert
+//
+//   [insert]
+//   Esc = mode normal
+//
+// Keys are written the way `keys::parse` reads them, optionally with `Ctrl+`, `Alt+`
+// and `Shift+` in front. `KeyPress` only carries a `char`, so modifiers end up as what
+// a terminal sends: Ctrl+W is the control character for W, Shift+a is `A`, and Alt+x is
+// Escape followed by `x`. The first section is the mode the keymap starts in, and the
+// command `mode NAME` switches to another one instead of coming out as a command.
+//
+// When the keys so far could still become a longer sequence, the keymap waits for the
+// next key. With `g` and `g g` both bound, a `g` on its own only counts as `g` once the
+// timeout passes or some other key shows up. Keys that aren't bound to anything come
+// out as `Action::Key`, which in insert mode is just typing.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::web_event::{keys, WebEvent};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Command(String),
+    // Switched mode, because of a `mode NAME` binding.
+    Mode(String),
+    // A key that isn't bound, or turned out not to finish a sequence.
+    Key(char),
+    // Everything other than key presses passes through untouched.
+    Other(WebEvent),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySpecError {
+    Empty,
+    UnknownKey(String),
+    // Only letters have a Ctrl or Shift version that fits in a `char`.
+    InvalidModifier(String),
+}
+
+impl fmt::Display for KeySpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeySpecError::Empty => write!(f, "a binding needs at least one key"),
+            KeySpecError::UnknownKey(key) => write!(f, "unknown key \"{}\"", key),
+            KeySpecError::InvalidModifier(key) => write!(f, "\"{}\" can't be sent as a key press", key),
+        }
+    }
+}
+
+impl std::error::Error for KeySpecError {}
+
+// One key with its modifiers, like `Ctrl+Shift+x`, as the key presses it arrives as.
+fn parse_key(spec: &str) -> Result<Vec<char>, KeySpecError> {
+    let (mut ctrl, mut alt, mut shift) = (false, false, false);
+    let mut rest = spec;
+    // `+` on its own (or at the end, as in `Alt++`) is the key, not a separator.
+    while let Some((modifier, after)) = rest.split_once('+').filter(|(_, after)| !after.is_empty()) {
+        match modifier.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => ctrl = true,
+            "alt" | "meta" => alt = true,
+            "shift" => shift = true,
+            _ => break,
+        }
+        rest = after;
+    }
+    let mut key = keys::parse(rest).ok_or_else(|| KeySpecError::UnknownKey(spec.to_owned()))?;
+    if shift {
+        if !key.is_ascii_alphabetic() {
+            return Err(KeySpecError::InvalidModifier(spec.to_owned()));
+        }
+        key = key.to_ascii_uppercase();
+    }
+    if ctrl {
+        key = keys::ctrl(key).ok_or_else(|| KeySpecError::InvalidModifier(spec.to_owned()))?;
+    }
+    Ok(if alt { vec![keys::ESCAPE, key] } else { vec![key] })
+}
+
+// A space-separated sequence of keys, like `Ctrl+W v` or `g g`.
+pub fn parse_keys(spec: &str) -> Result<Vec<char>, KeySpecError> {
+    let mut sequence = Vec::new();
+    for key in spec.split_whitespace() {
+        sequence.extend(parse_key(key)?);
+    }
+    if sequence.is_empty() {
+        return Err(KeySpecError::Empty);
+    }
+    Ok(sequence)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigErrorKind {
+    Keys(KeySpecError),
+    // A line that isn't a `[mode]`, `keys = command` or comment.
+    Syntax,
+    BindingOutsideMode,
+    EmptyCommand,
+    InvalidTimeout(String),
+    Duplicate(String),
+    UnknownMode(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    // Counted from 1.
+    pub line: usize,
+    pub kind: ConfigErrorKind,
+}
+
+impl fmt::Display for ConfigErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigErrorKind::Keys(e) => write!(f, "{}", e),
+            ConfigErrorKind::Syntax => write!(f, "expected `[mode]` or `keys = command`"),
+            ConfigErrorKind::BindingOutsideMode => write!(f, "bindings go under a `[mode]` heading"),
+            ConfigErrorKind::EmptyCommand => write!(f, "missing the command after `=`"),
+            ConfigErrorKind::InvalidTimeout(value) => write!(f, "invalid timeout \"{}\", expected milliseconds", value),
+            ConfigErrorKind::Duplicate(keys) => write!(f, "\"{}\" is already bound in this mode", keys),
+            ConfigErrorKind::UnknownMode(mode) => write!(f, "there is no mode \"{}\"", mode),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// Vim's default `timeoutlen`.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    modes: HashMap<String, Vec<(Vec<char>, String)>>,
+    mode: String,
+    timeout: Duration,
+    // Keys that could still turn into a binding, and when the last of them came.
+    held: Vec<char>,
+    last_key: Duration,
+}
+
+impl Keymap {
+    // An empty keymap in mode `mode`.
+    pub fn new(mode: &str) -> Keymap {
+        Keymap { modes: HashMap::new(), mode: mode.to_owned(), timeout: DEFAULT_TIMEOUT, held: Vec::new(), last_key: Duration::ZERO }
+    }
+
+    pub fn from_config(text: &str) -> Result<Keymap, ConfigError> {
+        let mut keymap = Keymap::new("normal");
+        // The mode the bindings being read go in, `None` before the first heading.
+        let mut section: Option<String> = None;
+        // Where each `mode NAME` came from, to check the modes exist at the end.
+        let mut switches = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let error = |kind| ConfigError { line: i + 1, kind };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(mode) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                let mode = mode.trim();
+                if section.is_none() {
+                    keymap.mode = mode.to_owned();
+                }
+                keymap.modes.entry(mode.to_owned()).or_default();
+                section = Some(mode.to_owned());
+                continue;
+            }
+            // Skipping the first character lets `=` itself be bound, as in `= = equalize`.
+            let (split, _) = line.char_indices().skip(1).find(|&(_, c)| c == '=').ok_or(error(ConfigErrorKind::Syntax))?;
+            let (keys, command) = (line[..split].trim(), line[split + 1..].trim());
+            if command.is_empty() {
+                return Err(error(ConfigErrorKind::EmptyCommand));
+            }
+            let Some(mode) = &section else {
+                if keys != "timeout" {
+                    return Err(error(ConfigErrorKind::BindingOutsideMode));
+                }
+                let millis = command.parse().map_err(|_| error(ConfigErrorKind::InvalidTimeout(command.to_owned())))?;
+                keymap.timeout = Duration::from_millis(millis);
+                continue;
+            };
+            match keymap.bind(mode, keys, command) {
+                Ok(true) => {},
+                Ok(false) => return Err(error(ConfigErrorKind::Duplicate(keys.to_owned()))),
+                Err(e) => return Err(error(ConfigErrorKind::Keys(e))),
+            }
+            if let Some(target) = command.strip_prefix("mode ") {
+                switches.push((i + 1, target.trim().to_owned()));
+            }
+        }
+        for (line, target) in switches {
+            if !keymap.modes.contains_key(&target) {
+                return Err(ConfigError { line, kind: ConfigErrorKind::UnknownMode(target) });
+            }
+        }
+        Ok(keymap)
+    }
+
+    // Binds `spec` (like `"Ctrl+W v"`) to `command` in `mode`. Returns `Ok(false)` and
+    // leaves the old binding alone if those keys are already bound in that mode.
+    pub fn bind(&mut self, mode: &str, spec: &str, command: &str) -> Result<bool, KeySpecError> {
+        let sequence = parse_keys(spec)?;
+        let bindings = self.modes.entry(mode.to_owned()).or_default();
+        if bindings.iter().any(|(keys, _)| *keys == sequence) {
+            return Ok(false);
+        }
+        bindings.push((sequence, command.to_owned()));
+        Ok(true)
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    // Also drops any keys waiting on the rest of a sequence.
+    pub fn set_mode(&mut self, mode: &str) {
+        self.mode = mode.to_owned();
+        self.held.clear();
+    }
+
+    // What `spec` is bound to in `mode`, if anything.
+    pub fn lookup(&self, mode: &str, spec: &str) -> Option<&str> {
+        let sequence = parse_keys(spec).ok()?;
+        let bindings = self.modes.get(mode)?;
+        bindings.iter().find(|(keys, _)| *keys == sequence).map(|(_, command)| command.as_str())
+    }
+
+    // `at` is when the event happened, on any clock, as long as it never goes backwards.
+    pub fn feed(&mut self, at: Duration, event: &WebEvent) -> Vec<Action> {
+        let mut out = self.tick(at);
+        match event {
+            WebEvent::KeyPress(c) => {
+                self.held.push(*c);
+                self.last_key = at;
+                self.resolve(false, &mut out);
+            },
+            event => {
+                self.resolve(true, &mut out);
+                out.push(Action::Other(event.clone()));
+            },
+        }
+        out
+    }
+
+    // Lets time pass without any input, so a sequence that was never finished is
+    // settled once the timeout is up.
+    pub fn tick(&mut self, now: Duration) -> Vec<Action> {
+        let mut out = Vec::new();
+        if self.deadline().is_some_and(|deadline| now > deadline) {
+            self.resolve(true, &mut out);
+        }
+        out
+    }
+
+    // When the keys being held will be settled, if any are. A timeout too long to add
+    // to the time of the last key means there's no deadline: they wait for the next
+    // key or `flush`.
+    pub fn deadline(&self) -> Option<Duration> {
+        if self.held.is_empty() { None } else { self.last_key.checked_add(self.timeout) }
+    }
+
+    // The input has ended: settle anything still held.
+    pub fn flush(&mut self) -> Vec<Action> {
+        let mut out = Vec::new();
+        self.resolve(true, &mut out);
+        out
+    }
+
+    // Emits whatever the held keys add up to in the current mode. Unless `force` is
+    // set, it stops while they're still the start of some longer sequence.
+    fn resolve(&mut self, force: bool, out: &mut Vec<Action>) {
+        while !self.held.is_empty() {
+            let bindings = self.modes.get(&self.mode).map(Vec::as_slice).unwrap_or_default();
+            let held = &self.held;
+            if !force && bindings.iter().any(|(keys, _)| keys.len() > held.len() && keys.starts_with(held)) {
+                return;
+            }
+            // The longest binding the held keys start with wins. If none does, the
+            // first key was just a key, and the rest get another look on their own.
+            let matched = bindings.iter().filter(|(keys, _)| held.starts_with(keys)).max_by_key(|(keys, _)| keys.len());
+            match matched {
+                Some((keys, command)) => {
+                    let (len, command) = (keys.len(), command.clone());
+                    self.held.drain(..len);
+                    match command.strip_prefix("mode ") {
+                        Some(mode) => {
+                            // Keys already typed after the switch are read in the new mode.
+                            self.mode = mode.trim().to_owned();
+                            out.push(Action::Mode(self.mode.clone()));
+                        },
+                        None => out.push(Action::Command(command)),
+                    }
+                },
+                None => out.push(Action::Key(self.held.remove(0))),
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// The library half of the enums example. `main.rs` walks through the basics,
+// while the modules here grow those enums into something you can actually use.
+
+pub mod analytics;
+pub mod batch;
+pub mod bigint;
+pub mod bus;
+pub mod calc;
+pub mod complex;
+pub mod decimal;
+pub mod dispatcher;
+pub mod editor;
+pub mod expr;
+pub mod filter;
+pub mod fuzz;
+pub mod gesture;
+pub mod interval;
+pub mod json;
+pub mod jsonl;
+pub mod keymap;
+pub mod middleware;
+pub mod operations;
+pub mod rational;
+pub mod session;
+pub mod symbolic;
+pub mod terminal;
+pub mod vm;
+pub mod web_event;
+
+pub use bigint::BigInt;
+pub use complex::Complex;
+pub use decimal::{Decimal, RoundingMode};
+pub use interval::Interval;
+pub use operations::{Associativity, Operand, Operations, VeryVerboseEnumOfThingsToDoWithNumbers};
+pub use rational::Rational;
+pub use web_event::{EventKind, WebEvent};
@@ -1,75 +1,81 @@
 // The enum keyword allows the creation of a type which may be one of a few different variants. 
 // Any variant which is valid as a struct is also valid in an enum.
 
-// Create an `enum` to classify a web event. Note how both
-// names and type information together specify the variant:
-// `PageLoad != PageUnload` and `KeyPress(char) != Paste(String)`.
-// Each is different and independent.
-// enum WebEvent {
-//     // An `enum` variant may either be `unit-like`,
-//     PageLoad,
-//     PageUnload,
-//     // like tuple structs,
-//     KeyPress(char),
-//     Paste(String),
-//     // or c-like structures.
-//     Click { x: i64, y: i64 },
-// }
+// The `WebEvent` enum and `inspect` now live in `src/web_event.rs`, so the rest of the
+// library (like the `EventDispatcher`) can use them too.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 
-// // A function which takes a `WebEvent` enum as an argument and
-// // returns nothing.
-// fn inspect(event: WebEvent) {
-//     match event {
-//         WebEvent::PageLoad => println!("page loaded"),
-//         WebEvent::PageUnload => println!("page unloaded"),
-//         // Destructure `c` from inside the `enum` variant.
-//         WebEvent::KeyPress(v) => println!("pressed '{}'.", v), // the 'v' variables can be whatever you want the name to be, think of them like function params
-//         WebEvent::Paste(s) => println!("pasted \"{}\".", s), // same here 
-//         // Destructure `Click` into `x` and `y`.
-//         WebEvent::Click { x, y } => { // x and y represent the click's x and y properties, they count as standalone var's now (within this scope)
-//             println!("clicked at x={}, y={}.", x, y); // they're used here 
-//         },
-//     }
-// }
+use enums::dispatcher::{EventDispatcher, Propagation};
+use enums::expr::Expr;
+use enums::vm::{self, Vm};
+use enums::web_event::inspect;
+use enums::{EventKind, Operations, WebEvent};
 
-// fn main() {
-//     let pressed = WebEvent::KeyPress('x');
-//     // `to_owned()` creates an owned `String` from a string slice.
-//     let pasted  = WebEvent::Paste("my text".to_owned());
-//     let click   = WebEvent::Click { x: 20, y: 80 };
-//     let load    = WebEvent::PageLoad; // just strings
-//     let unload  = WebEvent::PageUnload; // just strings
+fn web_events() {
+    let pressed = WebEvent::KeyPress('x');
+    // `to_owned()` creates an owned `String` from a string slice.
+    let pasted  = WebEvent::Paste("my text".to_owned());
+    let click   = WebEvent::Click { x: 20, y: 80 };
+    let load    = WebEvent::PageLoad; // just strings
+    let unload  = WebEvent::PageUnload; // just strings
 
-//     inspect(pressed);
-//     inspect(pasted);
-//     inspect(click);
-//     inspect(load);
-//     inspect(unload);
-// }
-// ---------------------------------------------------------------- Type Aliases ---------------------------------------------------
-// If you use a type alias, you can refer to each enum variant via its alias. 
-// This might be useful if the enum's name is too long or too generic, and you want to rename it.
-enum VeryVerboseEnumOfThingsToDoWithNumbers {
-    Add,
-    Subtract,
+    inspect(pressed.clone());
+    inspect(pasted.clone());
+    inspect(click.clone());
+    inspect(load.clone());
+    inspect(unload.clone());
+
+    // The same events through a dispatcher. The `Rc<RefCell<..>>` lets the handlers
+    // share a log with the code that registered them.
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut dispatcher = EventDispatcher::new();
+    let clicks = Rc::clone(&log);
+    dispatcher.on(EventKind::Click, move |event| {
+        clicks.borrow_mut().push(format!("click handler: {:?}", event));
+        Propagation::Stop // nothing after this handler sees clicks
+    });
+    let everything = Rc::clone(&log);
+    dispatcher.on_any(move |event| {
+        everything.borrow_mut().push(format!("catch-all: {:?}", event));
+        Propagation::Continue
+    });
+    for event in [pressed, pasted, click, load, unload] {
+        dispatcher.dispatch(&event);
+    }
+    for line in log.borrow().iter() {
+        println!("{}", line);
+    }
 }
 
-// Creates a type alias. Now we can access the enum above from this name
-type Operations = VeryVerboseEnumOfThingsToDoWithNumbers;
+// ---------------------------------------------------------------- Type Aliases ---------------------------------------------------
+// The `Operations` alias and its enum now live in `src/operations.rs`, so the library
+// modules (the expression tree and the bytecode VM) can use them too.
 
+fn main() {
+    web_events();
 
-// The most common place you'll see this is in impl blocks using the Self alias.
+    let x = Operations::Add; // as seen here
+    println!("{:?} 2 3 = {}", x, x.run(2, 3));
 
-impl VeryVerboseEnumOfThingsToDoWithNumbers {
-    fn run(&self, x: i32, y: i32) -> i32 {
-        match self {
-            Self::Add => x + y, // realize how here the self keyword accesses the enum from above's properties
-            Self::Subtract => x - y, // same here
-        }
+    // Both the symbol and the word parse, and `Display` prints the symbol.
+    let parsed: Operations = "Subtract".parse().unwrap();
+    println!("7 {} 4 = {}", parsed, parsed.run(7, 4));
+    for op in Operations::all() {
+        println!("{:<8} {}  precedence {}", op.name(), op, op.precedence());
     }
-}
 
-fn main() {
-    let x = Operations::Add; // as seen here
-}
+    // `if n then total / n else 0`
+    let average = Expr::if_else(
+        Expr::var("n"),
+        Expr::binary(Operations::Divide, Expr::var("total"), Expr::var("n")),
+        Expr::Const(0),
+    );
+    let chunk = vm::compile(&average).unwrap();
+    print!("{}", chunk.disassemble());
 
+    let env = HashMap::from([("total".to_owned(), 42), ("n".to_owned(), 4)]);
+    let slots = chunk.bind(&env).unwrap();
+    println!("tree: {:?}, vm: {:?}", average.eval(&env), Vm::new().run(&chunk, &slots));
+}
@@ -0,0 +1,412 @@
+// Stages that sit between where events come from and the handlers that use them:
+// debouncing a burst of key presses down to the last one, throttling clicks, merging
+// back-to-back pastes, batching, and plain `map` and `filter`.
+//
+// A `Pipeline` chains stages together with a builder:
+//
+//   let mut pipeline = Pipeline::new()
+//       .filter(|event| *event != WebEvent::PageUnload)
+//       .throttle(EventKind::Click, Duration::from_millis(250))
+//       .coalesce_pastes(Duration::from_millis(50))
+//       .batch(16, Duration::from_millis(100));
+//
+// Like the gesture recognizer, stages never look at a clock themselves. Every event
+// comes with the time it happened, and `tick` tells the pipeline that time has passed
+// so that anything being held back (a debounced key, a half-full batch) can come out.
+// `Runner` does that bookkeeping against a `Clock`, so a `ManualClock` can drive a
+// pipeline through minutes of events instantly.
+
+use std::marker::PhantomData;
+use std::mem;
+use std::time::Duration;
+
+use crate::session::Clock;
+use crate::web_event::{EventKind, WebEvent};
+
+pub trait Stage {
+    type In;
+    type Out;
+
+    // Takes an item that arrived at `at` and adds whatever comes out to `out`. Items
+    // arrive in time order.
+    fn push(&mut self, at: Duration, item: Self::In, out: &mut Vec<Self::Out>);
+
+    // Lets anything due by `now` out.
+    fn tick(&mut self, _now: Duration, _out: &mut Vec<Self::Out>) {}
+
+    // The input has ended: let out everything still held back.
+    fn flush(&mut self, _out: &mut Vec<Self::Out>) {}
+
+    // When `tick` next has something to do, if ever.
+    fn deadline(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// Passes everything straight through. Where every pipeline starts.
+pub struct Identity<T>(PhantomData<fn(T) -> T>);
+
+impl<T> Stage for Identity<T> {
+    type In = T;
+    type Out = T;
+
+    fn push(&mut self, _at: Duration, item: T, out: &mut Vec<T>) {
+        out.push(item);
+    }
+}
+
+// `first`, then `second` on whatever comes out of it.
+pub struct Chain<A: Stage, B> {
+    first: A,
+    second: B,
+    // Reused between calls, so passing items along doesn't allocate.
+    between: Vec<A::Out>,
+    // The latest time either stage has been told about.
+    now: Duration,
+}
+
+impl<A, B> Stage for Chain<A, B>
+where
+    A: Stage,
+    B: Stage<In = A::Out>,
+{
+    type In = A::In;
+    type Out = B::Out;
+
+    fn push(&mut self, at: Duration, item: A::In, out: &mut Vec<B::Out>) {
+        self.now = at;
+        self.first.push(at, item, &mut self.between);
+        // `second` only hears about this moment through what `first` lets out, which
+        // may be nothing, so anything it has due by now comes out first.
+        self.second.tick(at, out);
+        for item in self.between.drain(..) {
+            self.second.push(at, item, out);
+        }
+    }
+
+    fn tick(&mut self, now: Duration, out: &mut Vec<B::Out>) {
+        self.now = now;
+        self.first.tick(now, &mut self.between);
+        for item in self.between.drain(..) {
+            self.second.push(now, item, out);
+        }
+        self.second.tick(now, out);
+    }
+
+    fn flush(&mut self, out: &mut Vec<B::Out>) {
+        self.first.flush(&mut self.between);
+        for item in self.between.drain(..) {
+            self.second.push(self.now, item, out);
+        }
+        self.second.flush(out);
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        match (self.first.deadline(), self.second.deadline()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+pub struct Map<F, I>(F, PhantomData<fn(I)>);
+
+impl<F: FnMut(I) -> O, I, O> Stage for Map<F, I> {
+    type In = I;
+    type Out = O;
+
+    fn push(&mut self, _at: Duration, item: I, out: &mut Vec<O>) {
+        out.push((self.0)(item));
+    }
+}
+
+pub struct Filter<F, T>(F, PhantomData<fn(T)>);
+
+impl<F: FnMut(&T) -> bool, T> Stage for Filter<F, T> {
+    type In = T;
+    type Out = T;
+
+    fn push(&mut self, _at: Duration, item: T, out: &mut Vec<T>) {
+        if (self.0)(&item) {
+            out.push(item);
+        }
+    }
+}
+
+// Holds back events of one kind until none has arrived for `quiet`, then lets only
+// the last one through: typing "hello" quickly comes out as just the `o`. Any other
+// kind of event ends the burst, so the held event comes out first and order is kept.
+pub struct Debounce {
+    kind: EventKind,
+    quiet: Duration,
+    held: Option<(Duration, WebEvent)>,
+}
+
+impl Debounce {
+    pub fn new(kind: EventKind, quiet: Duration) -> Debounce {
+        Debounce { kind, quiet, held: None }
+    }
+}
+
+impl Stage for Debounce {
+    type In = WebEvent;
+    type Out = WebEvent;
+
+    fn push(&mut self, at: Duration, event: WebEvent, out: &mut Vec<WebEvent>) {
+        self.tick(at, out);
+        if event.kind() == self.kind {
+            self.held = Some((at, event));
+        } else {
+            self.flush(out);
+            out.push(event);
+        }
+    }
+
+    fn tick(&mut self, now: Duration, out: &mut Vec<WebEvent>) {
+        if self.deadline().is_some_and(|deadline| deadline <= now) {
+            self.flush(out);
+        }
+    }
+
+    fn flush(&mut self, out: &mut Vec<WebEvent>) {
+        out.extend(self.held.take().map(|(_, event)| event));
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        self.held.as_ref().map(|(at, _)| *at + self.quiet)
+    }
+}
+
+// Lets through at most one event of a kind per `interval`: the first, with the rest
+// dropped until the interval is up. Other kinds pass untouched.
+pub struct Throttle {
+    kind: EventKind,
+    interval: Duration,
+    last: Option<Duration>,
+}
+
+impl Throttle {
+    pub fn new(kind: EventKind, interval: Duration) -> Throttle {
+        Throttle { kind, interval, last: None }
+    }
+}
+
+impl Stage for Throttle {
+    type In = WebEvent;
+    type Out = WebEvent;
+
+    fn push(&mut self, at: Duration, event: WebEvent, out: &mut Vec<WebEvent>) {
+        if event.kind() == self.kind {
+            if self.last.is_some_and(|last| at < last + self.interval) {
+                return;
+            }
+            self.last = Some(at);
+        }
+        out.push(event);
+    }
+}
+
+// Merges pastes that come one straight after another, less than `window` apart, into a
+// single paste of all their text. Terminals without bracketed paste tend to deliver a
+// big paste in several pieces.
+pub struct CoalescePastes {
+    window: Duration,
+    pending: Option<(Duration, String)>,
+}
+
+impl CoalescePastes {
+    pub fn new(window: Duration) -> CoalescePastes {
+        CoalescePastes { window, pending: None }
+    }
+}
+
+impl Stage for CoalescePastes {
+    type In = WebEvent;
+    type Out = WebEvent;
+
+    fn push(&mut self, at: Duration, event: WebEvent, out: &mut Vec<WebEvent>) {
+        self.tick(at, out);
+        match (event, &mut self.pending) {
+            (WebEvent::Paste(text), Some((last, pending))) => {
+                pending.push_str(&text);
+                *last = at;
+            },
+            (WebEvent::Paste(text), None) => self.pending = Some((at, text)),
+            (event, _) => {
+                self.flush(out);
+                out.push(event);
+            },
+        }
+    }
+
+    fn tick(&mut self, now: Duration, out: &mut Vec<WebEvent>) {
+        if self.deadline().is_some_and(|deadline| deadline <= now) {
+            self.flush(out);
+        }
+    }
+
+    fn flush(&mut self, out: &mut Vec<WebEvent>) {
+        out.extend(self.pending.take().map(|(_, text)| WebEvent::Paste(text)));
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        self.pending.as_ref().map(|(last, _)| *last + self.window)
+    }
+}
+
+// Groups items into batches of up to `max_len`, sending a batch early if its first
+// item has waited `window`. Handy in front of anything with a per-call cost, like
+// writing to a socket.
+pub struct Batch<T> {
+    max_len: usize,
+    window: Duration,
+    started: Option<Duration>,
+    items: Vec<T>,
+}
+
+impl<T> Batch<T> {
+    pub fn new(max_len: usize, window: Duration) -> Batch<T> {
+        assert!(max_len > 0, "a batch needs room for at least one item");
+        Batch { max_len, window, started: None, items: Vec::new() }
+    }
+}
+
+impl<T> Stage for Batch<T> {
+    type In = T;
+    type Out = Vec<T>;
+
+    fn push(&mut self, at: Duration, item: T, out: &mut Vec<Vec<T>>) {
+        self.tick(at, out);
+        self.started.get_or_insert(at);
+        self.items.push(item);
+        if self.items.len() >= self.max_len {
+            self.flush(out);
+        }
+    }
+
+    fn tick(&mut self, now: Duration, out: &mut Vec<Vec<T>>) {
+        if self.deadline().is_some_and(|deadline| deadline <= now) {
+            self.flush(out);
+        }
+    }
+
+    fn flush(&mut self, out: &mut Vec<Vec<T>>) {
+        self.started = None;
+        if !self.items.is_empty() {
+            out.push(mem::take(&mut self.items));
+        }
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        self.started.map(|started| started + self.window)
+    }
+}
+
+pub struct Pipeline<S> {
+    stage: S,
+}
+
+impl Pipeline<Identity<WebEvent>> {
+    pub fn new() -> Pipeline<Identity<WebEvent>> {
+        Pipeline { stage: Identity(PhantomData) }
+    }
+}
+
+impl Default for Pipeline<Identity<WebEvent>> {
+    fn default() -> Pipeline<Identity<WebEvent>> {
+        Pipeline::new()
+    }
+}
+
+impl<S: Stage> Pipeline<S> {
+    // Adds any stage, including ones written outside this module.
+    pub fn then<B: Stage<In = S::Out>>(self, stage: B) -> Pipeline<Chain<S, B>> {
+        Pipeline { stage: Chain { first: self.stage, second: stage, between: Vec::new(), now: Duration::ZERO } }
+    }
+
+    pub fn map<F: FnMut(S::Out) -> O, O>(self, f: F) -> Pipeline<Chain<S, Map<F, S::Out>>> {
+        self.then(Map(f, PhantomData))
+    }
+
+    pub fn filter<F: FnMut(&S::Out) -> bool>(self, f: F) -> Pipeline<Chain<S, Filter<F, S::Out>>> {
+        self.then(Filter(f, PhantomData))
+    }
+
+    pub fn batch(self, max_len: usize, window: Duration) -> Pipeline<Chain<S, Batch<S::Out>>> {
+        self.then(Batch::new(max_len, window))
+    }
+
+    pub fn push(&mut self, at: Duration, item: S::In) -> Vec<S::Out> {
+        let mut out = Vec::new();
+        self.stage.push(at, item, &mut out);
+        out
+    }
+
+    pub fn tick(&mut self, now: Duration) -> Vec<S::Out> {
+        let mut out = Vec::new();
+        self.stage.tick(now, &mut out);
+        out
+    }
+
+    pub fn flush(&mut self) -> Vec<S::Out> {
+        let mut out = Vec::new();
+        self.stage.flush(&mut out);
+        out
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.stage.deadline()
+    }
+
+    // Connects the pipeline to a clock and to the handler that gets what comes out.
+    pub fn run<C: Clock, F: FnMut(S::Out)>(self, clock: C, handler: F) -> Runner<S, C, F> {
+        Runner { pipeline: self, clock, handler }
+    }
+}
+
+// The stages that only make sense for events.
+impl<S: Stage<Out = WebEvent>> Pipeline<S> {
+    pub fn debounce(self, kind: EventKind, quiet: Duration) -> Pipeline<Chain<S, Debounce>> {
+        self.then(Debounce::new(kind, quiet))
+    }
+
+    pub fn throttle(self, kind: EventKind, interval: Duration) -> Pipeline<Chain<S, Throttle>> {
+        self.then(Throttle::new(kind, interval))
+    }
+
+    pub fn coalesce_pastes(self, window: Duration) -> Pipeline<Chain<S, CoalescePastes>> {
+        self.then(CoalescePastes::new(window))
+    }
+}
+
+// A pipeline that timestamps what it's sent with a clock and hands what comes out to
+// a handler. Call `poll` every so often (at the latest by `deadline`) so that held
+// back events don't wait for the next one to arrive.
+pub struct Runner<S, C, F> {
+    pipeline: Pipeline<S>,
+    clock: C,
+    handler: F,
+}
+
+impl<S: Stage, C: Clock, F: FnMut(S::Out)> Runner<S, C, F> {
+    pub fn send(&mut self, item: S::In) {
+        let out = self.pipeline.push(self.clock.now(), item);
+        out.into_iter().for_each(&mut self.handler);
+    }
+
+    pub fn poll(&mut self) {
+        let out = self.pipeline.tick(self.clock.now());
+        out.into_iter().for_each(&mut self.handler);
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.pipeline.deadline()
+    }
+
+    // Lets everything still held back out, and gives back the pipeline and handler.
+    pub fn finish(mut self) -> (Pipeline<S>, F) {
+        let out = self.pipeline.flush();
+        out.into_iter().for_each(&mut self.handler);
+        (self.pipeline, self.handler)
+    }
+}
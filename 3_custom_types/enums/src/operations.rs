@@ -0,0 +1,166 @@
+use std::fmt;
+use std::str::FromStr;
+
+// ---------------------------------------------------------------- Type Aliases ---------------------------------------------------
+// If you use a type alias, you can refer to each enum variant via its alias.
+// This might be useful if the enum's name is too long or too generic, and you want to rename it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VeryVerboseEnumOfThingsToDoWithNumbers {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+// Creates a type alias. Now we can access the enum above from this name
+pub type Operations = VeryVerboseEnumOfThingsToDoWithNumbers;
+
+// The most common place you'll see this is in impl blocks using the Self alias.
+
+impl VeryVerboseEnumOfThingsToDoWithNumbers {
+    // `run` works for any type that implements `Operand` below, so the same enum drives
+    // `i32`, `i64`, `BigInt` and friends. It panics where the matching operator would
+    // (division by zero), and on overflow even in release builds.
+    pub fn run<T: Operand>(&self, x: T, y: T) -> T {
+        match T::apply(*self, x, y) {
+            Some(result) => result,
+            None => panic!("{:?} overflowed or divided by zero", self),
+        }
+    }
+
+    /// Same as `run`, but returns `None` instead of panicking on overflow or division by zero.
+    pub fn checked_run<T: Operand>(&self, x: T, y: T) -> Option<T> {
+        T::apply(*self, x, y)
+    }
+
+    // Every variant, in declaration order. Handy for building tables and help text.
+    pub fn all() -> impl Iterator<Item = Operations> {
+        [Self::Add, Self::Subtract, Self::Multiply, Self::Divide].into_iter()
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+        }
+    }
+
+    // The lowercase word form, the same one `from_str` accepts.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Subtract => "subtract",
+            Self::Multiply => "multiply",
+            Self::Divide => "divide",
+        }
+    }
+
+    // Higher binds tighter, so `1 + 2 * 3` groups as `1 + (2 * 3)`.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Subtract => 1,
+            Self::Multiply | Self::Divide => 2,
+        }
+    }
+
+    // All four group left to right: `8 - 4 - 2` is `(8 - 4) - 2`.
+    pub fn associativity(&self) -> Associativity {
+        Associativity::Left
+    }
+
+    // Whether `a op b == b op a`. A printer can use this to tell that `a - (b - c)`
+    // needs its parentheses while `a + (b + c)` doesn't.
+    pub fn is_commutative(&self) -> bool {
+        matches!(self, Self::Add | Self::Multiply)
+    }
+}
+
+// Only `Left` so far, since all four operations group left to right. A right-grouping
+// one like `^` would add `Right` here, along with the parser and printer cases for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOperationError(String);
+
+impl fmt::Display for ParseOperationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown operation \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseOperationError {}
+
+// Accepts the symbol (`+`) or a word (`add`, `plus`), ignoring case and surrounding spaces.
+impl FromStr for VeryVerboseEnumOfThingsToDoWithNumbers {
+    type Err = ParseOperationError;
+
+    fn from_str(s: &str) -> Result<Self, ParseOperationError> {
+        match s.trim().to_lowercase().as_str() {
+            "+" | "add" | "plus" => Ok(Self::Add),
+            "-" | "subtract" | "sub" | "minus" => Ok(Self::Subtract),
+            "*" | "multiply" | "mul" | "times" => Ok(Self::Multiply),
+            "/" | "divide" | "div" => Ok(Self::Divide),
+            _ => Err(ParseOperationError(s.to_owned())),
+        }
+    }
+}
+
+// Prints the symbol, so `format!("{} {} {}", 1, Operations::Add, 2)` gives `1 + 2`.
+impl fmt::Display for VeryVerboseEnumOfThingsToDoWithNumbers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+// A type `Operations` can be run on. `None` means the result can't be represented
+// (overflow) or doesn't exist (division by zero).
+pub trait Operand: Sized {
+    fn apply(op: Operations, x: Self, y: Self) -> Option<Self>;
+}
+
+macro_rules! impl_operand_for_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl Operand for $t {
+                fn apply(op: Operations, x: Self, y: Self) -> Option<Self> {
+                    match op {
+                        Operations::Add => x.checked_add(y),
+                        Operations::Subtract => x.checked_sub(y),
+                        Operations::Multiply => x.checked_mul(y),
+                        Operations::Divide => x.checked_div(y),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_operand_for_primitive!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+// Floats never fail on their own, they turn overflow into infinity and 0 / 0 into NaN.
+// To keep the meaning of `None` the same as for integers, a result that isn't finite
+// counts as failed (as long as the inputs were finite to begin with).
+macro_rules! impl_operand_for_float {
+    ($($t:ty),*) => {
+        $(
+            impl Operand for $t {
+                fn apply(op: Operations, x: Self, y: Self) -> Option<Self> {
+                    let result = match op {
+                        Operations::Add => x + y,
+                        Operations::Subtract => x - y,
+                        Operations::Multiply => x * y,
+                        Operations::Divide => x / y,
+                    };
+                    if result.is_finite() || !(x.is_finite() && y.is_finite()) { Some(result) } else { None }
+                }
+            }
+        )*
+    };
+}
+
+impl_operand_for_float!(f32, f64);
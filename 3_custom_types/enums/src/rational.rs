@@ -0,0 +1,522 @@
+// An exact fraction `num / den`, for when `f32`/`f64` rounding isn't good enough.
+// `(10.5 - 9.5) * (10.9 - 9.7)` is `1.1999998` in `f32`, but with `Rational` it's exactly `6/5`.
+//
+// The fraction is always kept in lowest terms with a positive denominator, so every
+// value has exactly one representation and `#[derive(PartialEq)]` is correct.
+// It works over `i64` (fast, but can overflow) or `BigInt` (never overflows).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::bigint::BigInt;
+use crate::operations::{Operand, Operations};
+
+// What `Rational` needs from its numerator and denominator type. The arithmetic is
+// checked so that `i64` can report overflow instead of panicking or wrapping.
+pub trait Integer: Clone + Ord + fmt::Display {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_i64(n: i64) -> Self;
+    fn to_f64(&self) -> f64;
+    // The value as `m * 2^e`, for types that can be too big for `to_f64`.
+    fn to_f64_exp(&self) -> (f64, u64) {
+        (self.to_f64(), 0)
+    }
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+    // Truncating division, like `/` and `%` on `i64`. `None` on division by zero or overflow.
+    fn checked_div_rem(&self, other: &Self) -> Option<(Self, Self)>;
+    // Parses an unsigned run of decimal digits.
+    fn from_decimal_digits(digits: &str) -> Option<Self>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Self::zero().checked_sub(self)
+    }
+}
+
+impl Integer for i64 {
+    fn zero() -> i64 {
+        0
+    }
+
+    fn one() -> i64 {
+        1
+    }
+
+    fn from_i64(n: i64) -> i64 {
+        n
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+
+    fn checked_add(&self, other: &i64) -> Option<i64> {
+        i64::checked_add(*self, *other)
+    }
+
+    fn checked_sub(&self, other: &i64) -> Option<i64> {
+        i64::checked_sub(*self, *other)
+    }
+
+    fn checked_mul(&self, other: &i64) -> Option<i64> {
+        i64::checked_mul(*self, *other)
+    }
+
+    fn checked_div_rem(&self, other: &i64) -> Option<(i64, i64)> {
+        Some((self.checked_div(*other)?, self.checked_rem(*other)?))
+    }
+
+    fn from_decimal_digits(digits: &str) -> Option<i64> {
+        // `i64::from_str` would also accept a sign, which the caller has already handled.
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+impl Integer for BigInt {
+    fn zero() -> BigInt {
+        BigInt::zero()
+    }
+
+    fn one() -> BigInt {
+        BigInt::one()
+    }
+
+    fn from_i64(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    fn to_f64(&self) -> f64 {
+        // Going through the decimal string gets the rounding right for free.
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    fn to_f64_exp(&self) -> (f64, u64) {
+        BigInt::to_f64_exp(self)
+    }
+
+    fn checked_add(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self + other)
+    }
+
+    fn checked_sub(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self - other)
+    }
+
+    fn checked_mul(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self * other)
+    }
+
+    fn checked_div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        BigInt::checked_div_rem(self, other)
+    }
+
+    fn from_decimal_digits(digits: &str) -> Option<BigInt> {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        BigInt::from_str_radix(digits, 10).ok()
+    }
+
+    fn is_negative(&self) -> bool {
+        BigInt::is_negative(self)
+    }
+}
+
+// Euclid's algorithm. Always returns a non-negative value (or `None` on `i64` overflow,
+// which only happens for `gcd(i64::MIN, 0)` and friends).
+fn gcd<T: Integer>(a: &T, b: &T) -> Option<T> {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        let (_, r) = a.checked_div_rem(&b)?;
+        a = b;
+        b = r;
+    }
+    if a.is_negative() { a.checked_neg() } else { Some(a) }
+}
+
+// `2^exp`, or `None` if it doesn't fit in `T`.
+fn checked_pow2<T: Integer>(mut exp: u32) -> Option<T> {
+    let mut base = T::from_i64(2);
+    let mut result = T::one();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(&base)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.checked_mul(&base)?;
+        }
+    }
+    Some(result)
+}
+
+// `x * 2^exp`, in steps small enough that the power of two itself never overflows
+// or underflows.
+fn scale_by_pow2(mut x: f64, mut exp: i64) -> f64 {
+    while exp != 0 && x != 0.0 && x.is_finite() {
+        let step = exp.clamp(-1000, 1000);
+        x *= 2f64.powi(step as i32);
+        exp -= step;
+    }
+    x
+}
+
+// Floor division with a positive divisor: `n = q * d + r` with `0 <= r < d`.
+fn floor_div_rem<T: Integer>(n: &T, d: &T) -> (T, T) {
+    let (q, r) = n.checked_div_rem(d).expect("denominator is positive, so this can't fail");
+    if r.is_negative() {
+        // `q` can't be `MIN` here: that would need `d == 1`, and then `r` would be zero.
+        (q.checked_sub(&T::one()).unwrap(), r.checked_add(d).unwrap())
+    } else {
+        (q, r)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational<T = i64> {
+    num: T,
+    den: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRationalError {
+    Invalid(String),
+    ZeroDenominator,
+    // Only for `Rational<i64>`, when the value doesn't fit.
+    Overflow,
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRationalError::Invalid(s) => write!(f, "invalid rational number \"{}\"", s),
+            ParseRationalError::ZeroDenominator => write!(f, "denominator is zero"),
+            ParseRationalError::Overflow => write!(f, "number too large for the integer type"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+impl<T: Integer> Rational<T> {
+    // Reduces `num / den` to lowest terms. `None` if `den` is zero or normalizing overflows.
+    pub fn checked_new(num: T, den: T) -> Option<Rational<T>> {
+        if den.is_zero() {
+            return None;
+        }
+        let g = gcd(&num, &den)?;
+        let (mut num, _) = num.checked_div_rem(&g)?;
+        let (mut den, _) = den.checked_div_rem(&g)?;
+        if den.is_negative() {
+            num = num.checked_neg()?;
+            den = den.checked_neg()?;
+        }
+        Some(Rational { num, den })
+    }
+
+    pub fn new(num: T, den: T) -> Rational<T> {
+        Rational::checked_new(num, den).expect("zero denominator or overflow in Rational::new")
+    }
+
+    pub fn from_integer(n: T) -> Rational<T> {
+        Rational { num: n, den: T::one() }
+    }
+
+    pub fn zero() -> Rational<T> {
+        Rational::from_integer(T::zero())
+    }
+
+    pub fn one() -> Rational<T> {
+        Rational::from_integer(T::one())
+    }
+
+    pub fn numer(&self) -> &T {
+        &self.num
+    }
+
+    pub fn denom(&self) -> &T {
+        &self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == T::one()
+    }
+
+    // a/b + c/d = (a*d + c*b) / (b*d)
+    pub fn checked_add(&self, other: &Rational<T>) -> Option<Rational<T>> {
+        let num = self.num.checked_mul(&other.den)?.checked_add(&other.num.checked_mul(&self.den)?)?;
+        Rational::checked_new(num, self.den.checked_mul(&other.den)?)
+    }
+
+    pub fn checked_sub(&self, other: &Rational<T>) -> Option<Rational<T>> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    // a/b * c/d = (a*c) / (b*d). Cross-reducing first keeps `i64` from overflowing
+    // on products whose result would fit anyway.
+    pub fn checked_mul(&self, other: &Rational<T>) -> Option<Rational<T>> {
+        let g1 = gcd(&self.num, &other.den)?;
+        let g2 = gcd(&other.num, &self.den)?;
+        let (a, _) = self.num.checked_div_rem(&g1)?;
+        let (d, _) = other.den.checked_div_rem(&g1)?;
+        let (c, _) = other.num.checked_div_rem(&g2)?;
+        let (b, _) = self.den.checked_div_rem(&g2)?;
+        Rational::checked_new(a.checked_mul(&c)?, b.checked_mul(&d)?)
+    }
+
+    // `None` when dividing by zero.
+    pub fn checked_div(&self, other: &Rational<T>) -> Option<Rational<T>> {
+        self.checked_mul(&other.checked_recip()?)
+    }
+
+    pub fn checked_neg(&self) -> Option<Rational<T>> {
+        Some(Rational { num: self.num.checked_neg()?, den: self.den.clone() })
+    }
+
+    pub fn checked_recip(&self) -> Option<Rational<T>> {
+        Rational::checked_new(self.den.clone(), self.num.clone())
+    }
+
+    pub fn abs(&self) -> Rational<T> {
+        if self.num.is_negative() { -self.clone() } else { self.clone() }
+    }
+
+    // Rounds toward negative infinity, like `f64::floor`.
+    pub fn floor(&self) -> T {
+        floor_div_rem(&self.num, &self.den).0
+    }
+
+    // Dividing only the top 64 or so bits of each side, then putting the difference in
+    // size back, keeps a ratio of two huge `BigInt`s from becoming `inf / inf`.
+    pub fn to_f64(&self) -> f64 {
+        let (num, num_exp) = self.num.to_f64_exp();
+        let (den, den_exp) = self.den.to_f64_exp();
+        scale_by_pow2(num / den, num_exp as i64 - den_exp as i64)
+    }
+
+    // Every finite `f64` is a fraction with a power-of-two denominator, so this is exact:
+    // `0.1` becomes `3602879701896397/36028797018963968`, not `1/10`. Use `from_str`
+    // with a decimal string when you mean the decimal value.
+    // `None` for NaN and infinities, or when the value doesn't fit in `T`.
+    pub fn from_f64(x: f64) -> Option<Rational<T>> {
+        if !x.is_finite() {
+            return None;
+        }
+        let bits = x.to_bits();
+        let negative = bits >> 63 == 1;
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        // Normal numbers have an implicit leading 1 bit, subnormals don't.
+        let (mantissa, exponent) = if exponent == 0 { (fraction, -1074) } else { (fraction | (1 << 52), exponent - 1075) };
+
+        if mantissa == 0 {
+            return Some(Rational::zero());
+        }
+        // Cancel the factors of two the mantissa and a power-of-two denominator have in
+        // common, and what's left is already in lowest terms.
+        let common = if exponent < 0 { mantissa.trailing_zeros().min(exponent.unsigned_abs()) } else { 0 };
+        let (mantissa, exponent) = (mantissa >> common, exponent + common as i32);
+        let mantissa = T::from_i64(mantissa as i64);
+        let value = if exponent >= 0 {
+            Rational { num: mantissa.checked_mul(&checked_pow2(exponent as u32)?)?, den: T::one() }
+        } else {
+            Rational { num: mantissa, den: checked_pow2(exponent.unsigned_abs())? }
+        };
+        if negative { value.checked_neg() } else { Some(value) }
+    }
+
+    // Rounds to `places` decimal places (half away from zero) and prints without
+    // an exponent, e.g. `2/3` with 4 places is `0.6667`. The digits come from long
+    // division on the remainder, which never gets bigger than the denominator, so
+    // this works for any value and any number of places.
+    pub fn to_decimal(&self, places: usize) -> String {
+        let (whole, rem) = self.num.checked_div_rem(&self.den).expect("denominator is positive, so this can't fail");
+        // The remainder has the sign of the numerator and is smaller than the
+        // denominator, so negating it can't overflow.
+        let mut rem = if rem.is_negative() { rem.checked_neg().expect("smaller than the denominator") } else { rem };
+        let mut digits: Vec<u8> = whole.to_string().trim_start_matches('-').bytes().collect();
+        for _ in 0..places {
+            let (digit, next) = times_ten_div_rem(&rem, &self.den);
+            digits.push(b'0' + digit);
+            rem = next;
+        }
+        // Round up if what's left is at least half the denominator: `rem >= den - rem`.
+        if rem >= self.den.checked_sub(&rem).expect("both are positive") {
+            round_up(&mut digits);
+        }
+        // Rounding up can add a digit at the front, like 9.99 to 10.00.
+        let int_len = digits.len() - places;
+        let negative = self.num.is_negative() && digits.iter().any(|&d| d != b'0');
+        let digits = String::from_utf8(digits).expect("only ASCII digits");
+        let (int_part, frac_part) = digits.split_at(int_len);
+        let sign = if negative { "-" } else { "" };
+        if places == 0 { format!("{}{}", sign, int_part) } else { format!("{}{}.{}", sign, int_part, frac_part) }
+    }
+}
+
+// `(10 * rem) / den` and `(10 * rem) % den` for `0 <= rem < den`, by adding `rem` ten
+// times and taking `den` away whenever the total reaches it, so nothing goes above `den`.
+fn times_ten_div_rem<T: Integer>(rem: &T, den: &T) -> (u8, T) {
+    let mut digit = 0;
+    let mut total = T::zero();
+    for _ in 0..10 {
+        // `total + rem >= den`, without working out `total + rem`.
+        let room = den.checked_sub(rem).expect("rem is below den");
+        if total >= room {
+            total = total.checked_sub(&room).expect("total is at least room");
+            digit += 1;
+        } else {
+            total = total.checked_add(rem).expect("below den");
+        }
+    }
+    (digit, total)
+}
+
+// Adds one to the last of a run of ASCII digits, carrying as far as it goes.
+fn round_up(digits: &mut Vec<u8>) {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return;
+        }
+    }
+    digits.insert(0, b'1');
+}
+
+// Accepts integers (`-3`), decimals (`10.25`, `.5`) and fractions (`22/7`).
+impl<T: Integer> FromStr for Rational<T> {
+    type Err = ParseRationalError;
+
+    fn from_str(s: &str) -> Result<Rational<T>, ParseRationalError> {
+        let invalid = || ParseRationalError::Invalid(s.to_owned());
+        let trimmed = s.trim();
+        let (negative, body) = match trimmed.as_bytes().first() {
+            Some(b'-') => (true, &trimmed[1..]),
+            Some(b'+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+
+        let value = if let Some((num, den)) = body.split_once('/') {
+            let num = T::from_decimal_digits(num.trim()).ok_or_else(invalid)?;
+            let den = T::from_decimal_digits(den.trim()).ok_or_else(invalid)?;
+            if den.is_zero() {
+                return Err(ParseRationalError::ZeroDenominator);
+            }
+            Rational::checked_new(num, den).ok_or(ParseRationalError::Overflow)?
+        } else {
+            // `12.345` is `12345 / 10^3`.
+            let (int_part, frac_part) = body.split_once('.').unwrap_or((body, ""));
+            if int_part.is_empty() && frac_part.is_empty() {
+                return Err(invalid());
+            }
+            let digits = format!("{}{}", int_part, frac_part);
+            let num = T::from_decimal_digits(&digits).ok_or_else(|| {
+                if digits.bytes().all(|b| b.is_ascii_digit()) { ParseRationalError::Overflow } else { invalid() }
+            })?;
+            let mut den = T::one();
+            for _ in 0..frac_part.len() {
+                den = den.checked_mul(&T::from_i64(10)).ok_or(ParseRationalError::Overflow)?;
+            }
+            Rational::checked_new(num, den).ok_or(ParseRationalError::Overflow)?
+        };
+        if negative { value.checked_neg().ok_or(ParseRationalError::Overflow) } else { Ok(value) }
+    }
+}
+
+// `6/5`, or just `3` when the denominator is 1.
+impl<T: Integer> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() { write!(f, "{}", self.num) } else { write!(f, "{}/{}", self.num, self.den) }
+    }
+}
+
+// Comparing a/b with c/d by cross-multiplying could overflow `i64`, so instead compare
+// the whole parts, and if those are equal compare the reciprocals of the leftover
+// fractions (flipped, because taking the reciprocal reverses the order).
+impl<T: Integer> Ord for Rational<T> {
+    fn cmp(&self, other: &Rational<T>) -> Ordering {
+        let (q1, r1) = floor_div_rem(&self.num, &self.den);
+        let (q2, r2) = floor_div_rem(&other.num, &other.den);
+        match q1.cmp(&q2) {
+            Ordering::Equal => {},
+            unequal => return unequal,
+        }
+        match (r1.is_zero(), r2.is_zero()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            // Both remainders are in (0, den), so both reciprocals are > 1 and well formed.
+            (false, false) => {
+                Rational { num: other.den.clone(), den: r2 }.cmp(&Rational { num: self.den.clone(), den: r1 })
+            },
+        }
+    }
+}
+
+impl<T: Integer> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Rational<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Integer> Neg for Rational<T> {
+    type Output = Rational<T>;
+
+    fn neg(self) -> Rational<T> {
+        self.checked_neg().expect("overflow negating Rational")
+    }
+}
+
+// The operators panic on overflow or division by zero, like the primitive integers.
+// Use the `checked_` methods or `Operations::checked_run` to get an `Option` instead.
+macro_rules! impl_rational_binop {
+    ($($trait:ident $method:ident $checked:ident),*) => {
+        $(
+            impl<T: Integer> $trait for Rational<T> {
+                type Output = Rational<T>;
+
+                fn $method(self, other: Rational<T>) -> Rational<T> {
+                    self.$checked(&other).expect(concat!("overflow or division by zero in Rational::", stringify!($method)))
+                }
+            }
+
+            impl<T: Integer> $trait for &Rational<T> {
+                type Output = Rational<T>;
+
+                fn $method(self, other: &Rational<T>) -> Rational<T> {
+                    self.$checked(other).expect(concat!("overflow or division by zero in Rational::", stringify!($method)))
+                }
+            }
+        )*
+    };
+}
+
+impl_rational_binop!(Add add checked_add, Sub sub checked_sub, Mul mul checked_mul, Div div checked_div);
+
+impl<T: Integer> Operand for Rational<T> {
+    fn apply(op: Operations, x: Rational<T>, y: Rational<T>) -> Option<Rational<T>> {
+        match op {
+            Operations::Add => x.checked_add(&y),
+            Operations::Subtract => x.checked_sub(&y),
+            Operations::Multiply => x.checked_mul(&y),
+            Operations::Divide => x.checked_div(&y),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+// Recording `WebEvent`s with timestamps and playing them back later.
+//
+// A recording is the JSON Lines format from `jsonl` with one extra field in front, the
+// time since recording started in microseconds:
+//
+//   {"t_us":0,"type":"page_load"}
+//   {"t_us":1250000,"type":"click","x":20,"y":80}
+//
+// Both sides take their time from a `Clock`. The real one reads `Instant` and really
+// sleeps, `ManualClock` only moves when told to, so a ten minute session can be
+// replayed "in real time" instantly and with exactly the same timings every run.
+
+use std::cell::Cell;
+use std::io::{self, BufRead, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::json::Value;
+use crate::jsonl::{self, JsonLines, ParseErrorKind};
+use crate::web_event::WebEvent;
+
+pub trait Clock {
+    // Time since some fixed starting point. Never goes backwards.
+    fn now(&self) -> Duration;
+    fn sleep(&self, duration: Duration);
+}
+
+// Lets a clock be shared, like a `ManualClock` that a test keeps advancing while a
+// `Recorder` reads it.
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+// A clock that only moves when it's advanced or slept on. Sleeping returns at once,
+// having moved the time forward by exactly the requested amount.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    // Stops at `Duration::MAX` rather than overflowing.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get().saturating_add(duration));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+// An event and when it happened, relative to the start of the recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recorded {
+    pub at: Duration,
+    pub event: WebEvent,
+}
+
+pub fn to_value(recorded: &Recorded) -> Value {
+    let Value::Object(mut fields) = jsonl::to_value(&recorded.event) else { unreachable!("events encode as objects") };
+    // Saturates after about 584,000 years of recording.
+    let micros = u64::try_from(recorded.at.as_micros()).unwrap_or(u64::MAX);
+    fields.insert(0, ("t_us".to_owned(), Value::Number(micros.to_string())));
+    Value::Object(fields)
+}
+
+pub fn from_value(value: &Value) -> Result<Recorded, ParseErrorKind> {
+    let event = jsonl::from_value(value)?;
+    let at = Duration::from_micros(jsonl::uint_field(value, "t_us")?);
+    Ok(Recorded { at, event })
+}
+
+pub type SessionReader<R> = JsonLines<R, Recorded>;
+
+impl<R: BufRead> SessionReader<R> {
+    pub fn new(input: R) -> SessionReader<R> {
+        JsonLines::with_decoder(input, from_value)
+    }
+}
+
+// Writes each event as it's recorded, so nothing is lost if the program dies halfway.
+pub struct Recorder<W, C> {
+    out: W,
+    clock: C,
+    start: Duration,
+    last: Duration,
+    count: usize,
+}
+
+impl<W: Write, C: Clock> Recorder<W, C> {
+    // Time starts counting from here.
+    pub fn new(out: W, clock: C) -> Recorder<W, C> {
+        let start = clock.now();
+        Recorder { out, clock, start, last: Duration::ZERO, count: 0 }
+    }
+
+    // Returns the timestamp the event was recorded with.
+    pub fn record(&mut self, event: &WebEvent) -> io::Result<Duration> {
+        // The clock shouldn't go backwards, but the file is only useful if its
+        // timestamps never do, so don't rely on it.
+        let at = self.clock.now().saturating_sub(self.start).max(self.last);
+        let recorded = Recorded { at, event: event.clone() };
+        writeln!(self.out, "{}", to_value(&recorded))?;
+        self.last = at;
+        self.count += 1;
+        Ok(at)
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // Flushes and hands back the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pace {
+    // Every event right after the one before it.
+    AsFastAsPossible,
+    // The gaps from the recording, divided by `speed`: 2.0 plays twice as fast,
+    // 0.5 at half speed.
+    Timed { speed: f64 },
+}
+
+// The longest `play` waits for any one event. Without a cap, a recording with a huge
+// gap (or a tiny speed) would leave a replay on a real clock asleep for centuries.
+pub const MAX_WAIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub struct Player<C> {
+    clock: C,
+    pace: Pace,
+}
+
+impl<C: Clock> Player<C> {
+    // `None` unless a timed speed is positive and finite (so not NaN either).
+    pub fn checked_new(clock: C, pace: Pace) -> Option<Player<C>> {
+        match pace {
+            Pace::Timed { speed } if !(speed > 0.0 && speed.is_finite()) => None,
+            _ => Some(Player { clock, pace }),
+        }
+    }
+
+    pub fn new(clock: C, pace: Pace) -> Player<C> {
+        Player::checked_new(clock, pace).expect("replay speed must be positive and finite")
+    }
+
+    // Feeds every event to `handler` and returns how many there were. In timed mode
+    // the first event is due when playback starts plus its own timestamp, just like
+    // in the recording. No single wait is longer than `MAX_WAIT`, so an event that's
+    // due later than that arrives early.
+    pub fn play<I, F>(&mut self, session: I, mut handler: F) -> usize
+    where
+        I: IntoIterator<Item = Recorded>,
+        F: FnMut(&WebEvent),
+    {
+        let start = self.clock.now();
+        let mut count = 0;
+        for recorded in session {
+            if let Pace::Timed { speed } = self.pace {
+                // Each deadline is worked out from the start rather than from the last
+                // event, so time spent in the handler doesn't add up into drift.
+                // A tiny speed can push a deadline past what a `Duration` holds, but
+                // the wait is capped anyway.
+                let delay = Duration::try_from_secs_f64(recorded.at.as_secs_f64() / speed).unwrap_or(Duration::MAX);
+                let due = start.saturating_add(delay);
+                let now = self.clock.now();
+                if due > now {
+                    self.clock.sleep((due - now).min(MAX_WAIT));
+                }
+            }
+            handler(&recorded.event);
+            count += 1;
+        }
+        count
+    }
+}
@@ -0,0 +1,276 @@
+// Symbolic expressions: a tree of `Operations` over exact numbers and named variables,
+// which can be simplified, differentiated, printed back as infix and evaluated.
+//
+//   let f = x * x + 3 * x        (built with the `Sym` helpers)
+//   f.derivative("x")            -> 2 * x + 3
+//
+// Constants are `Rational<BigInt>`, so constant folding is exact and can't overflow.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::bigint::BigInt;
+use crate::expr::EvalError;
+use crate::operations::Operations;
+use crate::rational::Rational;
+
+pub type Number = Rational<BigInt>;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Sym {
+    Num(Number),
+    Var(String),
+    Binary(Operations, Box<Sym>, Box<Sym>),
+}
+
+impl Sym {
+    pub fn int(n: i64) -> Sym {
+        Sym::Num(Rational::from_integer(BigInt::from(n)))
+    }
+
+    pub fn var(name: &str) -> Sym {
+        Sym::Var(name.to_owned())
+    }
+
+    pub fn binary(op: Operations, lhs: Sym, rhs: Sym) -> Sym {
+        Sym::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    fn as_num(&self) -> Option<&Number> {
+        match self {
+            Sym::Num(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn eval(&self, vars: &HashMap<String, f64>) -> Result<f64, EvalError> {
+        match self {
+            Sym::Num(n) => Ok(n.to_f64()),
+            Sym::Var(name) => vars.get(name).copied().ok_or_else(|| EvalError::UnboundVariable(name.clone())),
+            Sym::Binary(op, lhs, rhs) => {
+                let (x, y) = (lhs.eval(vars)?, rhs.eval(vars)?);
+                // Floats don't fail, dividing by zero just gives an infinity or NaN.
+                Ok(match op {
+                    Operations::Add => x + y,
+                    Operations::Subtract => x - y,
+                    Operations::Multiply => x * y,
+                    Operations::Divide => x / y,
+                })
+            },
+        }
+    }
+
+    // Shorthand for evaluating an expression of one variable at a point.
+    pub fn eval_at(&self, var: &str, value: f64) -> Result<f64, EvalError> {
+        self.eval(&HashMap::from([(var.to_owned(), value)]))
+    }
+
+    // d/d`var`, using the sum, product and quotient rules, then simplified.
+    pub fn derivative(&self, var: &str) -> Sym {
+        self.derive(var).simplify()
+    }
+
+    fn derive(&self, var: &str) -> Sym {
+        match self {
+            Sym::Num(_) => Sym::int(0),
+            Sym::Var(name) => Sym::int(if name == var { 1 } else { 0 }),
+            Sym::Binary(op, f, g) => {
+                let (df, dg) = (f.derive(var), g.derive(var));
+                let (f, g) = ((**f).clone(), (**g).clone());
+                match op {
+                    Operations::Add => df + dg,
+                    Operations::Subtract => df - dg,
+                    // (f * g)' = f' * g + f * g'
+                    Operations::Multiply => df * g + f * dg,
+                    // (f / g)' = (f' * g - f * g') / (g * g)
+                    Operations::Divide => (df * g.clone() - f * dg) / (g.clone() * g),
+                }
+            },
+        }
+    }
+
+    // Folds constants, drops identities like `x + 0` and `x * 1`, expands products
+    // of sums and collects like terms, so `x * 2 + 3 * x` becomes `5 * x`.
+    pub fn simplify(&self) -> Sym {
+        match self {
+            Sym::Num(_) | Sym::Var(_) => self.clone(),
+            Sym::Binary(Operations::Divide, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+                match (lhs.as_num(), rhs.as_num()) {
+                    // `x / 0` is left alone, there's nothing sensible to fold it into.
+                    (_, Some(d)) if *d == Rational::zero() => lhs / rhs,
+                    // Like most computer-algebra systems, `0 / x` and `x / x` assume `x` isn't zero.
+                    (Some(n), _) if *n == Rational::zero() => Sym::int(0),
+                    // Dividing by a constant is multiplying by its reciprocal, which
+                    // lets the terms of `lhs` join in like-term collection.
+                    (_, Some(d)) => {
+                        let recip = Rational::one() / d.clone();
+                        from_terms(collect(scale(terms(&lhs), &recip)))
+                    },
+                    _ if lhs == rhs => Sym::int(1),
+                    _ => lhs / rhs,
+                }
+            },
+            Sym::Binary(op, lhs, rhs) => {
+                let simplified = Sym::binary(*op, lhs.simplify(), rhs.simplify());
+                from_terms(collect(terms(&simplified)))
+            },
+        }
+    }
+}
+
+// The operators build tree nodes rather than computing anything, so `x * x + Sym::int(3)`
+// reads like the expression it represents. Call `simplify` to actually fold it.
+macro_rules! impl_sym_binop {
+    ($($trait:ident $method:ident $op:ident),*) => {
+        $(
+            impl $trait for Sym {
+                type Output = Sym;
+
+                fn $method(self, other: Sym) -> Sym {
+                    Sym::binary(Operations::$op, self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_sym_binop!(Add add Add, Sub sub Subtract, Mul mul Multiply, Div div Divide);
+
+// ---------------------------------------------------------------- Like-term collection -----------------------------------------------
+// A simplified expression is a sum of terms, each a coefficient times a sorted list of
+// factors: `3 * x * y - x / y` is `[(3, [x, y]), (-1, [x / y])]`. Sorting the factors
+// makes `x * y` and `y * x` the same term, so their coefficients can be added up.
+
+type Term = (Number, Vec<Sym>);
+
+fn terms(e: &Sym) -> Vec<Term> {
+    match e {
+        Sym::Num(n) => vec![(n.clone(), Vec::new())],
+        Sym::Binary(Operations::Add, lhs, rhs) => {
+            let mut out = terms(lhs);
+            out.extend(terms(rhs));
+            out
+        },
+        Sym::Binary(Operations::Subtract, lhs, rhs) => {
+            let mut out = terms(lhs);
+            out.extend(scale(terms(rhs), &-Rational::one()));
+            out
+        },
+        // (a + b) * (c + d) = a*c + a*d + b*c + b*d
+        Sym::Binary(Operations::Multiply, lhs, rhs) => {
+            let right = terms(rhs);
+            let mut out = Vec::new();
+            for (c1, f1) in terms(lhs) {
+                for (c2, f2) in &right {
+                    let mut factors = f1.clone();
+                    factors.extend(f2.iter().cloned());
+                    out.push((&c1 * c2, factors));
+                }
+            }
+            out
+        },
+        // Variables and non-constant divisions are opaque factors.
+        other => vec![(Rational::one(), vec![other.clone()])],
+    }
+}
+
+// Multiplies every term by a constant.
+fn scale(terms: Vec<Term>, by: &Number) -> Vec<Term> {
+    terms.into_iter().map(|(c, f)| (&c * by, f)).collect()
+}
+
+fn collect(terms: Vec<Term>) -> Vec<Term> {
+    let mut out: Vec<Term> = Vec::new();
+    for (coeff, mut factors) in terms {
+        factors.sort();
+        match out.iter_mut().find(|(_, f)| *f == factors) {
+            Some((c, _)) => *c = &*c + &coeff,
+            None => out.push((coeff, factors)),
+        }
+    }
+    out.retain(|(c, _)| *c != Rational::zero());
+    // Constants go last, and otherwise terms keep the order they first appeared in.
+    out.sort_by(|(_, a), (_, b)| match (a.is_empty(), b.is_empty()) {
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ => Ordering::Equal,
+    });
+    out
+}
+
+fn from_terms(terms: Vec<Term>) -> Sym {
+    let mut result: Option<Sym> = None;
+    for (coeff, factors) in terms {
+        let negative = coeff < Rational::zero();
+        // Negative terms after the first are written as subtractions instead.
+        let magnitude = if negative && result.is_some() { coeff.abs() } else { coeff };
+        let product = factors.into_iter().reduce(|acc, factor| acc * factor);
+        let term = match product {
+            None => Sym::Num(magnitude),
+            Some(product) if magnitude == Rational::one() => product,
+            Some(product) => Sym::Num(magnitude) * product,
+        };
+        result = Some(match result {
+            None => term,
+            Some(sum) if negative => sum - term,
+            Some(sum) => sum + term,
+        });
+    }
+    result.unwrap_or_else(|| Sym::int(0))
+}
+
+// ---------------------------------------------------------------- Printing -----------------------------------------------------------
+
+impl Sym {
+    // `parent` is the operator this node is an operand of, and `right` whether it's the
+    // right-hand operand. That's all that's needed to decide on parentheses:
+    // `(a + b) * c` needs them, and so does `a - (b - c)`, but `(a - b) - c` doesn't.
+    fn fmt_in(&self, f: &mut fmt::Formatter, parent: Option<(Operations, bool)>) -> fmt::Result {
+        match self {
+            // Fractions always get parentheses when they're operands, and negative numbers
+            // do when they're on the right, so `(1/2) * x` and `x - (-1)` can't be misread.
+            Sym::Num(n) => match parent {
+                Some(_) if !n.is_integer() => write!(f, "({})", n),
+                Some((_, true)) if *n < Rational::zero() => write!(f, "({})", n),
+                _ => write!(f, "{}", n),
+            },
+            Sym::Var(name) => write!(f, "{}", name),
+            Sym::Binary(op, lhs, rhs) => {
+                // `-1 * x` reads better as `-x`.
+                let negated = *op == Operations::Multiply && lhs.as_num() == Some(&-Rational::one());
+                let needs_parens = match parent {
+                    None => false,
+                    // Like negative numbers, `-x` on the right gets parentheses: `a - (-x)`, not `a - -x`.
+                    Some((_, true)) if negated => true,
+                    Some((outer, right)) => {
+                        op.precedence() < outer.precedence()
+                            || (right && op.precedence() == outer.precedence() && !outer.is_commutative())
+                    },
+                };
+                if needs_parens {
+                    write!(f, "(")?;
+                }
+                if negated {
+                    write!(f, "-")?;
+                } else {
+                    lhs.fmt_in(f, Some((*op, false)))?;
+                    write!(f, " {} ", op)?;
+                }
+                rhs.fmt_in(f, Some((*op, true)))?;
+                if needs_parens {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl fmt::Display for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_in(f, None)
+    }
+}
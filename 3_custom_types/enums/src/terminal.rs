@@ -0,0 +1,291 @@
+// Turns the bytes a terminal sends (in raw mode) into `WebEvent`s:
+//
+//   - UTF-8 text becomes one `KeyPress` per character
+//   - escape sequences for arrows, Home/End, F1-F12 and so on become `KeyPress` with
+//     the matching `keys` constant (modifiers like Ctrl+Up can't be represented, so
+//     they're dropped and it's just Up)
+//   - a bracketed paste, `ESC[200~ ... ESC[201~`, becomes a single `Paste`
+//   - an xterm SGR mouse report, `ESC[<0;x;yM`, becomes a `Click` for a left button
+//     press, with the 1-based terminal cell turned into 0-based `x` and `y`
+//
+// Reads can end anywhere, including halfway through an escape sequence or a UTF-8
+// character, so whatever might be the start of something longer is kept until the
+// next `feed`. A lone ESC is the one truly ambiguous case: it's either the Escape key
+// or the start of a sequence whose next byte hasn't arrived yet. Terminals don't
+// pause inside a sequence, so call `flush` once no input has come for a little while
+// (vim waits about 25 ms), and it'll be taken as the Escape key.
+
+use std::mem;
+
+use crate::web_event::keys;
+use crate::web_event::WebEvent;
+
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+// Longer than any real sequence. A "sequence" that goes on past this is garbage, and
+// keeping it would just hold up everything after it, so what's there is dropped and so
+// is the rest of it as it arrives.
+const MAX_SEQUENCE: usize = 64;
+
+// What one complete sequence at the front of the input decodes to.
+enum Token {
+    Event(WebEvent),
+    PasteStart,
+    // Recognised but not representable, like a right click or focus reports.
+    Ignored,
+    // The start of a CSI sequence that's gone on too long. Everything up to its final
+    // byte is dropped, however many reads that takes.
+    Runaway,
+}
+
+enum Step {
+    // The input so far could still be the start of a longer sequence.
+    Incomplete,
+    // Consumed this many bytes.
+    Done(usize, Token),
+}
+
+fn key(c: char) -> Step {
+    Step::Done(1, Token::Event(WebEvent::KeyPress(c)))
+}
+
+fn step(bytes: &[u8]) -> Step {
+    match bytes[0] {
+        0x1b => escape(bytes),
+        // Enter sends a carriage return in raw mode.
+        b'\r' => key(keys::ENTER),
+        // Most terminals send DEL for Backspace, some send Ctrl+H.
+        0x7f | 0x08 => key(keys::BACKSPACE),
+        b if b < 0x80 => key(b as char),
+        _ => utf8(bytes),
+    }
+}
+
+fn utf8(bytes: &[u8]) -> Step {
+    let width = match bytes[0] {
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        // A stray continuation byte, or a byte that never appears in UTF-8.
+        _ => return key(char::REPLACEMENT_CHARACTER),
+    };
+    if bytes.len() < width {
+        // Only worth waiting for if what's there so far still fits.
+        let continues = bytes[1..].iter().all(|b| b & 0xc0 == 0x80);
+        return if continues { Step::Incomplete } else { key(char::REPLACEMENT_CHARACTER) };
+    }
+    // `from_utf8` also rejects overlong encodings and surrogates.
+    match std::str::from_utf8(&bytes[..width]) {
+        Ok(s) => Step::Done(width, Token::Event(WebEvent::KeyPress(s.chars().next().unwrap()))),
+        Err(_) => key(char::REPLACEMENT_CHARACTER),
+    }
+}
+
+fn escape(bytes: &[u8]) -> Step {
+    match bytes.get(1) {
+        None => Step::Incomplete,
+        Some(b'[') => csi(bytes),
+        Some(b'O') => match bytes.get(2) {
+            None => Step::Incomplete,
+            Some(&b) => Step::Done(3, ss3(b)),
+        },
+        // Alt+x arrives as ESC x. There's no way to say "Alt" in a `WebEvent`, so this
+        // becomes Escape and then x is decoded on its own.
+        Some(_) => key(keys::ESCAPE),
+    }
+}
+
+// `ESC O x`, which is what arrows and F1-F4 send in "application" mode.
+fn ss3(b: u8) -> Token {
+    let c = match b {
+        b'A' => keys::UP,
+        b'B' => keys::DOWN,
+        b'C' => keys::RIGHT,
+        b'D' => keys::LEFT,
+        b'H' => keys::HOME,
+        b'F' => keys::END,
+        b'P'..=b'S' => keys::function((b - b'P') as u32 + 1).unwrap(),
+        _ => return Token::Ignored,
+    };
+    Token::Event(WebEvent::KeyPress(c))
+}
+
+// `ESC [`, some parameter bytes like `1;5` or `<0;10;3`, and a final byte from `@`
+// to `~` that says what the sequence is.
+fn csi(bytes: &[u8]) -> Step {
+    for (i, &b) in bytes.iter().enumerate().skip(2) {
+        match b {
+            0x20..=0x3f => continue,
+            0x40..=0x7e => return Step::Done(i + 1, csi_token(&bytes[2..i], b)),
+            // Not a valid sequence. Drop what there was and start again from here.
+            _ => return Step::Done(i, Token::Ignored),
+        }
+    }
+    if bytes.len() > MAX_SEQUENCE { Step::Done(bytes.len(), Token::Runaway) } else { Step::Incomplete }
+}
+
+fn csi_token(params: &[u8], final_byte: u8) -> Token {
+    let params = std::str::from_utf8(params).unwrap_or("");
+    let c = match final_byte {
+        b'A' => keys::UP,
+        b'B' => keys::DOWN,
+        b'C' => keys::RIGHT,
+        b'D' => keys::LEFT,
+        b'H' => keys::HOME,
+        b'F' => keys::END,
+        b'M' | b'm' if params.starts_with('<') => return sgr_mouse(&params[1..], final_byte == b'M'),
+        b'~' => {
+            // The first parameter is the key, anything after a `;` is modifiers.
+            let code = params.split(';').next().unwrap_or("");
+            match code {
+                "200" => return Token::PasteStart,
+                "1" | "7" => keys::HOME,
+                "2" => keys::INSERT,
+                "3" => keys::DELETE,
+                "4" | "8" => keys::END,
+                "5" => keys::PAGE_UP,
+                "6" => keys::PAGE_DOWN,
+                // F5 to F12 skip 16 and 22, for historical reasons.
+                "15" => keys::function(5).unwrap(),
+                "17" | "18" | "19" | "20" | "21" => keys::function(code.parse::<u32>().unwrap() - 11).unwrap(),
+                "23" | "24" => keys::function(code.parse::<u32>().unwrap() - 12).unwrap(),
+                _ => return Token::Ignored,
+            }
+        },
+        _ => return Token::Ignored,
+    };
+    Token::Event(WebEvent::KeyPress(c))
+}
+
+// `button;x;y`, where the low two bits of `button` pick the button (0 is left) and
+// higher bits flag modifiers (4, 8, 16), motion (32) and the wheel (64).
+fn sgr_mouse(params: &str, pressed: bool) -> Token {
+    let numbers: Vec<Option<i64>> = params.split(';').map(|p| p.parse().ok()).collect();
+    let [Some(button), Some(x), Some(y)] = numbers[..] else { return Token::Ignored };
+    if !pressed || button & 0b1110_0011 != 0 || x < 1 || y < 1 {
+        return Token::Ignored;
+    }
+    Token::Event(WebEvent::Click { x: x - 1, y: y - 1 })
+}
+
+// How many bytes at the end of `bytes` could be the start of `marker`.
+fn partial_suffix(bytes: &[u8], marker: &[u8]) -> usize {
+    (1..marker.len().min(bytes.len() + 1)).rev().find(|&n| bytes.ends_with(&marker[..n])).unwrap_or(0)
+}
+
+// Terminals send line breaks in pastes as carriage returns.
+fn paste_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).replace("\r\n", "\n").replace('\r', "\n")
+}
+
+#[derive(Debug, Default)]
+pub struct TerminalDecoder {
+    // Bytes that might be the start of an unfinished sequence.
+    pending: Vec<u8>,
+    // The text of a bracketed paste that hasn't ended yet.
+    paste: Option<Vec<u8>>,
+    // Dropping the rest of a runaway CSI sequence.
+    discarding: bool,
+}
+
+impl TerminalDecoder {
+    pub fn new() -> TerminalDecoder {
+        TerminalDecoder::default()
+    }
+
+    // Decodes everything in `bytes` that can be decoded, keeping the rest for later.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<WebEvent> {
+        let mut events = Vec::new();
+        self.decode(bytes, &mut events);
+        events
+    }
+
+    // Gives up waiting for the rest of whatever is pending: a lone ESC is the Escape
+    // key, and an unfinished sequence turns into the keys it was made of. An open
+    // paste is left alone, since a paste can legitimately take several reads. So is a
+    // runaway sequence being dropped: nothing that follows it can be trusted until its
+    // final byte shows up.
+    pub fn flush(&mut self) -> Vec<WebEvent> {
+        let mut events = Vec::new();
+        while !self.pending.is_empty() && self.paste.is_none() {
+            let rest = self.pending.split_off(1);
+            let first = mem::take(&mut self.pending)[0];
+            let c = if first == 0x1b { keys::ESCAPE } else { char::REPLACEMENT_CHARACTER };
+            events.push(WebEvent::KeyPress(c));
+            self.decode(&rest, &mut events);
+        }
+        events
+    }
+
+    // True while in the middle of a bracketed paste.
+    pub fn in_paste(&self) -> bool {
+        self.paste.is_some()
+    }
+
+    fn decode(&mut self, bytes: &[u8], events: &mut Vec<WebEvent>) {
+        self.pending.extend_from_slice(bytes);
+        let mut pos = 0;
+        loop {
+            let rest = &self.pending[pos..];
+            if let Some(paste) = &mut self.paste {
+                match rest.windows(PASTE_END.len()).position(|w| w == PASTE_END) {
+                    Some(end) => {
+                        paste.extend_from_slice(&rest[..end]);
+                        events.push(WebEvent::Paste(paste_text(paste)));
+                        self.paste = None;
+                        pos += end + PASTE_END.len();
+                    },
+                    None => {
+                        // Hold back anything that could be the start of the end marker.
+                        let keep = partial_suffix(rest, PASTE_END);
+                        paste.extend_from_slice(&rest[..rest.len() - keep]);
+                        pos += rest.len() - keep;
+                        break;
+                    },
+                }
+                continue;
+            }
+            if rest.is_empty() {
+                break;
+            }
+            if self.discarding {
+                // Same rules as `csi`: parameter bytes go on, a final byte ends the
+                // sequence, and anything else ends it without being part of it.
+                match rest.iter().position(|b| !(0x20..=0x3f).contains(b)) {
+                    Some(i) => {
+                        pos += if (0x40..=0x7e).contains(&rest[i]) { i + 1 } else { i };
+                        self.discarding = false;
+                    },
+                    None => pos += rest.len(),
+                }
+                continue;
+            }
+            match step(rest) {
+                Step::Incomplete => break,
+                Step::Done(n, token) => {
+                    pos += n;
+                    match token {
+                        Token::Event(event) => events.push(event),
+                        Token::PasteStart => self.paste = Some(Vec::new()),
+                        Token::Ignored => {},
+                        Token::Runaway => self.discarding = true,
+                    }
+                },
+            }
+        }
+        self.pending.drain(..pos);
+    }
+}
+
+// Decodes a complete recording in one go, flushing at the end.
+pub fn decode_all(bytes: &[u8]) -> Vec<WebEvent> {
+    let mut decoder = TerminalDecoder::new();
+    let mut events = decoder.feed(bytes);
+    events.extend(decoder.flush());
+    events
+}
+
+// What to write to the terminal to turn bracketed paste and SGR mouse reports on
+// (button presses only), and to turn them off again before exiting.
+pub const ENABLE_REPORTING: &str = "\x1b[?2004h\x1b[?1000h\x1b[?1006h";
+pub const DISABLE_REPORTING: &str = "\x1b[?1006l\x1b[?1000l\x1b[?2004l";
@@ -0,0 +1,185 @@
+// A bytecode compiler and stack VM for `Expr`. Instead of walking the tree every time,
+// the tree is flattened once into a list of instructions, and the VM then runs that
+// list with a plain loop and a stack of values.
+//
+// For `x + 3` the compiler emits:
+//   LOAD_VAR 0 (x)    -> stack: [x]
+//   PUSH_CONST 3      -> stack: [x, 3]
+//   APPLY Add         -> stack: [x + 3]
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::expr::{EvalError, Expr};
+use crate::operations::Operations;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    PushConst(i32),
+    // Variables are resolved to slot indexes at compile time, so the VM never hashes a name.
+    LoadVar(u16),
+    // Pops the right then the left operand and pushes the result.
+    Apply(Operations),
+    // Jump targets are absolute instruction indexes.
+    Jump(u32),
+    // Pops the condition and jumps if it's zero.
+    JumpIfZero(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    code: Vec<Instr>,
+    // `vars[slot]` is the name of the variable loaded by `LoadVar(slot)`.
+    vars: Vec<String>,
+}
+
+impl Chunk {
+    pub fn code(&self) -> &[Instr] {
+        &self.code
+    }
+
+    pub fn vars(&self) -> &[String] {
+        &self.vars
+    }
+
+    pub fn slot(&self, name: &str) -> Option<usize> {
+        self.vars.iter().position(|v| v == name)
+    }
+
+    // Turns a name -> value map into the slot layout this chunk expects.
+    pub fn bind(&self, env: &HashMap<String, i32>) -> Result<Vec<i32>, EvalError> {
+        self.vars
+            .iter()
+            .map(|name| env.get(name).copied().ok_or_else(|| EvalError::UnboundVariable(name.clone())))
+            .collect()
+    }
+
+    pub fn disassemble(&self) -> String {
+        let mut out = String::new();
+        for (i, instr) in self.code.iter().enumerate() {
+            // `write!` into a `String` can't fail, so the result is safe to ignore.
+            let _ = match *instr {
+                Instr::PushConst(n) => writeln!(out, "{:04}  PUSH_CONST    {}", i, n),
+                Instr::LoadVar(slot) => writeln!(out, "{:04}  LOAD_VAR      {} ({})", i, slot, self.vars[slot as usize]),
+                Instr::Apply(op) => writeln!(out, "{:04}  APPLY         {:?}", i, op),
+                Instr::Jump(target) => writeln!(out, "{:04}  JUMP          {:04}", i, target),
+                Instr::JumpIfZero(target) => writeln!(out, "{:04}  JUMP_IF_ZERO  {:04}", i, target),
+            };
+        }
+        out
+    }
+}
+
+// The limits of the instruction encoding. Anything past them is an error rather than
+// being cut down to fit, which would quietly load the wrong variable or jump to the
+// wrong place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompileError {
+    // More distinct variables than a `u16` slot index can address.
+    TooManyVariables,
+    // A jump target past what a `u32` can hold.
+    TooLong,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompileError::TooManyVariables => write!(f, "more than {} distinct variables", u16::MAX as u32 + 1),
+            CompileError::TooLong => write!(f, "more than {} instructions", u32::MAX),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+pub fn compile(expr: &Expr) -> Result<Chunk, CompileError> {
+    let mut chunk = Chunk { code: Vec::new(), vars: Vec::new() };
+    emit(expr, &mut chunk, &mut HashMap::new())?;
+    Ok(chunk)
+}
+
+// `slots` maps the names in `chunk.vars` back to their slots, so looking one up doesn't
+// mean searching the whole list.
+fn emit(expr: &Expr, chunk: &mut Chunk, slots: &mut HashMap<String, u16>) -> Result<(), CompileError> {
+    match expr {
+        Expr::Const(n) => chunk.code.push(Instr::PushConst(*n)),
+        Expr::Var(name) => {
+            let slot = match slots.get(name) {
+                Some(&slot) => slot,
+                None => {
+                    let slot = u16::try_from(chunk.vars.len()).map_err(|_| CompileError::TooManyVariables)?;
+                    chunk.vars.push(name.clone());
+                    slots.insert(name.clone(), slot);
+                    slot
+                },
+            };
+            chunk.code.push(Instr::LoadVar(slot));
+        },
+        Expr::Binary(op, lhs, rhs) => {
+            emit(lhs, chunk, slots)?;
+            emit(rhs, chunk, slots)?;
+            chunk.code.push(Instr::Apply(*op));
+        },
+        // cond; JUMP_IF_ZERO else; then; JUMP end; else: otherwise; end:
+        // The jump targets aren't known until the branches are emitted, so placeholders
+        // are pushed first and patched afterwards.
+        Expr::If { cond, then, otherwise } => {
+            emit(cond, chunk, slots)?;
+            let jump_to_else = chunk.code.len();
+            chunk.code.push(Instr::JumpIfZero(0));
+            emit(then, chunk, slots)?;
+            let jump_to_end = chunk.code.len();
+            chunk.code.push(Instr::Jump(0));
+            chunk.code[jump_to_else] = Instr::JumpIfZero(jump_target(chunk)?);
+            emit(otherwise, chunk, slots)?;
+            chunk.code[jump_to_end] = Instr::Jump(jump_target(chunk)?);
+        },
+    }
+    Ok(())
+}
+
+// The index of the next instruction to be emitted.
+fn jump_target(chunk: &Chunk) -> Result<u32, CompileError> {
+    u32::try_from(chunk.code.len()).map_err(|_| CompileError::TooLong)
+}
+
+// The stack is kept between runs, so evaluating the same chunk many times doesn't allocate.
+#[derive(Debug, Default)]
+pub struct Vm {
+    stack: Vec<i32>,
+}
+
+impl Vm {
+    pub fn new() -> Vm {
+        Vm::default()
+    }
+
+    pub fn run(&mut self, chunk: &Chunk, slots: &[i32]) -> Result<i32, EvalError> {
+        if slots.len() < chunk.vars.len() {
+            return Err(EvalError::UnboundVariable(chunk.vars[slots.len()].clone()));
+        }
+        self.stack.clear();
+        let mut pc = 0;
+        // A chunk produced by `compile` is always well formed, so the `expect`s below
+        // can only fire on a hand-built chunk, which isn't possible outside this module.
+        while let Some(instr) = chunk.code.get(pc) {
+            pc += 1;
+            match *instr {
+                Instr::PushConst(n) => self.stack.push(n),
+                Instr::LoadVar(slot) => self.stack.push(slots[slot as usize]),
+                Instr::Apply(op) => {
+                    let y = self.stack.pop().expect("stack underflow");
+                    let x = self.stack.pop().expect("stack underflow");
+                    self.stack.push(op.checked_run(x, y).ok_or(EvalError::Arithmetic(op))?);
+                },
+                Instr::Jump(target) => pc = target as usize,
+                Instr::JumpIfZero(target) => {
+                    if self.stack.pop().expect("stack underflow") == 0 {
+                        pc = target as usize;
+                    }
+                },
+            }
+        }
+        Ok(self.stack.pop().expect("empty stack at end of chunk"))
+    }
+}
@@ -0,0 +1,138 @@
+// Create an `enum` to classify a web event. Note how both
+// names and type information together specify the variant:
+// `PageLoad != PageUnload` and `KeyPress(char) != Paste(String)`.
+// Each is different and independent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WebEvent {
+    // An `enum` variant may either be `unit-like`,
+    PageLoad,
+    PageUnload,
+    // like tuple structs,
+    KeyPress(char),
+    Paste(String),
+    // or c-like structures.
+    Click { x: i64, y: i64 },
+}
+
+// Just the variant, without the data inside it. Handy for registering handlers
+// ("every `Click`") and for counting events per variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventKind {
+    PageLoad,
+    PageUnload,
+    KeyPress,
+    Paste,
+    Click,
+}
+
+impl EventKind {
+    pub fn all() -> impl Iterator<Item = EventKind> {
+        [EventKind::PageLoad, EventKind::PageUnload, EventKind::KeyPress, EventKind::Paste, EventKind::Click].into_iter()
+    }
+}
+
+impl WebEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            WebEvent::PageLoad => EventKind::PageLoad,
+            WebEvent::PageUnload => EventKind::PageUnload,
+            // `(_)` and `{ .. }` match the variant without caring what's inside.
+            WebEvent::KeyPress(_) => EventKind::KeyPress,
+            WebEvent::Paste(_) => EventKind::Paste,
+            WebEvent::Click { .. } => EventKind::Click,
+        }
+    }
+}
+
+// `KeyPress` only carries a `char`, so keys that don't type anything get one too.
+// Control keys use their ASCII control characters, and the rest use the private-use
+// characters macOS uses for its function keys (`NSUpArrowFunctionKey` is U+F700 and
+// so on), which no real text contains.
+pub mod keys {
+    pub const BACKSPACE: char = '\u{8}';
+    pub const TAB: char = '\t';
+    pub const ENTER: char = '\n';
+    pub const ESCAPE: char = '\u{1b}';
+
+    pub const UP: char = '\u{f700}';
+    pub const DOWN: char = '\u{f701}';
+    pub const LEFT: char = '\u{f702}';
+    pub const RIGHT: char = '\u{f703}';
+    pub const INSERT: char = '\u{f727}';
+    pub const DELETE: char = '\u{f728}';
+    pub const HOME: char = '\u{f729}';
+    pub const END: char = '\u{f72b}';
+    pub const PAGE_UP: char = '\u{f72c}';
+    pub const PAGE_DOWN: char = '\u{f72d}';
+
+    // `function(1)` is F1, up to F35.
+    pub fn function(n: u32) -> Option<char> {
+        if (1..=35).contains(&n) { char::from_u32(0xf703 + n) } else { None }
+    }
+
+    // Ctrl plus a letter, which terminals send as the letter's position in the
+    // alphabet: Ctrl+A is 1, Ctrl+C is 3.
+    pub fn ctrl(letter: char) -> Option<char> {
+        if letter.is_ascii_alphabetic() { char::from_u32(letter.to_ascii_uppercase() as u32 - 'A' as u32 + 1) } else { None }
+    }
+
+    // A key as it's written in a config file: a single character like `a` or `?`,
+    // `Ctrl+K`, or a name like `Enter`, `Up` or `F5`. Names are case-insensitive.
+    pub fn parse(name: &str) -> Option<char> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(c);
+        }
+        let lower = name.to_ascii_lowercase();
+        if let Some(letter) = lower.strip_prefix("ctrl+") {
+            let mut chars = letter.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => ctrl(c),
+                _ => None,
+            };
+        }
+        if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            return function(n);
+        }
+        Some(match lower.as_str() {
+            "backspace" => BACKSPACE,
+            "tab" => TAB,
+            "enter" => ENTER,
+            "esc" | "escape" => ESCAPE,
+            "space" => ' ',
+            "up" => UP,
+            "down" => DOWN,
+            "left" => LEFT,
+            "right" => RIGHT,
+            "insert" => INSERT,
+            "delete" => DELETE,
+            "home" => HOME,
+            "end" => END,
+            "pageup" => PAGE_UP,
+            "pagedown" => PAGE_DOWN,
+            _ => return None,
+        })
+    }
+}
+
+// A function which takes a `WebEvent` enum as an argument and
+// returns nothing.
+pub fn inspect(event: WebEvent) {
+    println!("{}", describe(&event));
+}
+
+// What `inspect` prints, as a `String`, so it can be logged or tested instead.
+pub fn describe(event: &WebEvent) -> String {
+    match event {
+        WebEvent::PageLoad => "page loaded".to_owned(),
+        WebEvent::PageUnload => "page unloaded".to_owned(),
+        // Destructure `c` from inside the `enum` variant.
+        WebEvent::KeyPress(v) => format!("pressed '{}'.", v), // the 'v' variables can be whatever you want the name to be, think of them like function params
+        WebEvent::Paste(s) => format!("pasted \"{}\".", s), // same here
+        // Destructure `Click` into `x` and `y`.
+        WebEvent::Click { x, y } => {
+            // x and y represent the click's x and y properties, they count as standalone var's now (within this scope)
+            format!("clicked at x={}, y={}.", x, y) // they're used here
+        },
+    }
+}
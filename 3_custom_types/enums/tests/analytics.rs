@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use enums::analytics::{Heatmap, SessionStats, MAX_CELLS};
+use enums::session::{Recorded, SessionReader};
+use enums::{EventKind, WebEvent};
+
+fn at(millis: u64, event: WebEvent) -> Recorded {
+    Recorded { at: Duration::from_millis(millis), event }
+}
+
+fn session() -> Vec<Recorded> {
+    vec![
+        at(0, WebEvent::PageLoad),
+        // Five keys 200ms apart, a long pause, then two more 100ms apart. The pause
+        // doesn't count, so that's 5 gaps in 0.9s: 5 keys / 0.9s = 333 per minute.
+        at(1_000, WebEvent::KeyPress('h')),
+        at(1_200, WebEvent::KeyPress('e')),
+        at(1_400, WebEvent::KeyPress('l')),
+        at(1_600, WebEvent::KeyPress('l')),
+        at(1_800, WebEvent::KeyPress('o')),
+        at(9_000, WebEvent::KeyPress('!')),
+        at(9_100, WebEvent::KeyPress('!')),
+        at(9_200, WebEvent::Paste("abc".to_owned())),
+        at(9_300, WebEvent::Paste("héllo 🦀".to_owned())),
+        at(10_000, WebEvent::Click { x: 0, y: 0 }),
+        at(10_100, WebEvent::Click { x: 99, y: 49 }),
+        at(10_200, WebEvent::Click { x: 99, y: 49 }),
+        at(10_300, WebEvent::Click { x: 50, y: 0 }),
+        at(12_000, WebEvent::PageUnload),
+        // A second visit, and a third that's still open when the log ends.
+        at(20_000, WebEvent::PageLoad),
+        at(23_000, WebEvent::PageUnload),
+        at(30_000, WebEvent::PageLoad),
+    ]
+}
+
+#[test]
+fn summarises_a_session() {
+    let session = session();
+    let stats = SessionStats::from_session(session.clone());
+    assert_eq!(stats.total(), 18);
+    assert_eq!(stats.count(EventKind::KeyPress), 7);
+    assert_eq!(stats.count(EventKind::Click), 4);
+    assert_eq!(stats.count(EventKind::PageLoad), 3);
+    let speed = stats.keys_per_minute().unwrap();
+    assert!((speed - 5.0 * 60.0 / 0.9).abs() < 1e-9, "{}", speed);
+    assert_eq!(stats.average_paste_length(), Some(5.0));
+    assert_eq!(stats.page_visits(), [Duration::from_secs(12), Duration::from_secs(3)]);
+    assert_eq!(stats.total_dwell_time(), Duration::from_secs(15));
+
+    // The same events without timestamps: counts and clicks, but nothing about time.
+    let events: Vec<WebEvent> = session.iter().map(|r| r.event.clone()).collect();
+    let untimed = SessionStats::from_events(&events);
+    assert_eq!(untimed.total(), 18);
+    assert_eq!(untimed.keys_per_minute(), None);
+    assert!(untimed.page_visits().is_empty());
+    assert_eq!(untimed.clicks(), stats.clicks());
+}
+
+#[test]
+fn empty_stats_have_nothing_to_report() {
+    // Nothing at all.
+    let empty = SessionStats::new();
+    assert_eq!((empty.keys_per_minute(), empty.average_paste_length(), empty.heatmap(10, 10)), (None, None, None));
+}
+
+#[test]
+fn heatmap_fits_the_clicks() {
+    // The heatmap fits the clicks, with y growing downwards: (0, 0) is top left and
+    // the two clicks at (99, 49) are bottom right.
+    let heatmap = SessionStats::from_session(session()).heatmap(4, 2).unwrap();
+    assert_eq!((heatmap.get(0, 0), heatmap.get(2, 0), heatmap.get(3, 1)), (1, 1, 2));
+    assert_eq!(heatmap.max(), 2);
+    let expected = concat!("+----+\n", "|. . |\n", "|   @|\n", "+----+\n");
+    assert_eq!(heatmap.to_string(), expected);
+    assert!(heatmap.render_ansi().contains("\x1b[48;5;255m"));
+}
+
+#[test]
+fn heatmap_bounds_and_extremes() {
+    // Fixed bounds, with clicks outside them counted separately, and edge-of-the-world
+    // coordinates that would overflow a naive `max - min`.
+    let mut heatmap = Heatmap::new((0, 9), (0, 9), 2, 2);
+    for (x, y) in [(0, 0), (4, 4), (5, 5), (9, 9), (10, 0), (-1, 5)] {
+        heatmap.add(x, y);
+    }
+    assert_eq!((heatmap.get(0, 0), heatmap.get(1, 1), heatmap.outside()), (2, 2, 2));
+    let extremes = [(i64::MIN, i64::MIN), (i64::MAX, i64::MAX), (0, 0)];
+    let heatmap = Heatmap::fit(&extremes, 2, 2).unwrap();
+    assert_eq!((heatmap.get(0, 0), heatmap.get(1, 1), heatmap.outside()), (1, 2, 0));
+    // With only one click there is nothing to spread out, so it lands in the first cell.
+    let heatmap = Heatmap::fit(&[(7, 7)], 3, 3).unwrap();
+    assert_eq!(heatmap.get(0, 0), 1);
+}
+
+#[test]
+fn stats_from_a_recorded_log() {
+    // Straight from a recorded log.
+    let log = concat!(
+        r#"{"t_us":0,"type":"page_load"}"#,
+        "\n",
+        r#"{"t_us":500000,"type":"click","x":3,"y":4}"#,
+        "\n",
+        r#"{"t_us":2500000,"type":"page_unload"}"#,
+        "\n",
+    );
+    let stats = SessionStats::from_session(SessionReader::new(log.as_bytes()).map(Result::unwrap));
+    assert_eq!(stats.page_visits(), [Duration::from_millis(2_500)]);
+    assert!(stats.to_string().contains("clicks: 1 between (3, 4) and (3, 4)"));
+}
+
+#[test]
+fn heatmap_sizes_are_checked() {
+    let bounds = ((0, 9), (0, 9));
+    assert!(Heatmap::checked_new(bounds.0, bounds.1, MAX_CELLS, 1).is_some());
+    assert_eq!(Heatmap::checked_new(bounds.0, bounds.1, MAX_CELLS + 1, 1), None);
+    assert_eq!(Heatmap::checked_new(bounds.0, bounds.1, 100_000, 100_000), None);
+    // A product that overflows `usize` is too big, not small.
+    assert_eq!(Heatmap::checked_new(bounds.0, bounds.1, usize::MAX, 2), None);
+    assert_eq!(Heatmap::checked_new(bounds.0, bounds.1, 0, 5), None);
+    assert_eq!(Heatmap::checked_new((9, 0), bounds.1, 2, 2), None);
+    assert!(std::panic::catch_unwind(|| Heatmap::new(bounds.0, bounds.1, usize::MAX, 2)).is_err());
+    assert_eq!(SessionStats::from_session(session()).heatmap(100_000, 100_000), None);
+}
@@ -0,0 +1,46 @@
+use enums::batch::{eval_line, LineError};
+use enums::Operations;
+
+#[test]
+fn evaluates_lines() {
+    assert_eq!(eval_line("add 3 4"), Some(Ok(7)));
+    assert_eq!(eval_line("  Subtract   10 -5  "), Some(Ok(15)));
+    assert_eq!(eval_line("* 6 7"), Some(Ok(42)));
+    assert_eq!(eval_line("divide -7 2"), Some(Ok(-3)));
+}
+
+#[test]
+fn skips_blank_lines_and_comments() {
+    assert_eq!(eval_line(""), None);
+    assert_eq!(eval_line("   \t"), None);
+    assert_eq!(eval_line("# add 1 2"), None);
+    assert_eq!(eval_line("  #indented"), None);
+}
+
+#[test]
+fn reports_errors() {
+    let err = |line: &str| eval_line(line).unwrap().unwrap_err();
+    assert!(matches!(err("modulo 3 4"), LineError::UnknownOperation(_)));
+    assert_eq!(err("add 3.5 4"), LineError::InvalidOperand("3.5".to_owned()));
+    assert_eq!(err("add 3 ten"), LineError::InvalidOperand("ten".to_owned()));
+    assert_eq!(err("add 3"), LineError::WrongArity(1));
+    assert_eq!(err("add 1 2 3"), LineError::WrongArity(3));
+    assert_eq!(err("divide 10 0"), LineError::DivisionByZero);
+    assert_eq!(err(&format!("multiply {} 2", i64::MAX)), LineError::Overflow(Operations::Multiply));
+    assert_eq!(err(&format!("divide {} -1", i64::MIN)), LineError::Overflow(Operations::Divide));
+}
+
+#[test]
+fn errors_have_stable_kinds_and_messages() {
+    let cases = [
+        ("modulo 3 4", "unknown_operation", "unknown operation \"modulo\""),
+        ("add x 4", "invalid_operand", "`x` is not an integer"),
+        ("add", "wrong_arity", "expected 2 operands, found 0"),
+        ("divide 1 0", "division_by_zero", "division by zero"),
+        ("add 9223372036854775807 1", "overflow", "add overflowed"),
+    ];
+    for (line, kind, message) in cases {
+        let e = eval_line(line).unwrap().unwrap_err();
+        assert_eq!((e.kind(), e.to_string().as_str()), (kind, message), "{}", line);
+    }
+}
@@ -0,0 +1,68 @@
+use enums::{BigInt, Operations};
+
+fn factorial(n: u32) -> BigInt {
+    (1..=n).fold(BigInt::one(), |acc, i| Operations::Multiply.run(acc, BigInt::from(i)))
+}
+
+#[test]
+fn factorials_match_u128() {
+    // 34! is the biggest factorial that fits in a `u128`.
+    let mut expected = 1u128;
+    for n in 1..=34u32 {
+        expected *= n as u128;
+        assert_eq!(factorial(n).to_u128(), Some(expected));
+        assert_eq!(factorial(n).to_string(), expected.to_string());
+    }
+    assert_eq!(factorial(35).to_u128(), None);
+}
+
+#[test]
+fn operations_match_u128() {
+    let samples = [0u128, 1, 7, 0xffff_ffff, 1 << 32, 0xdead_beef_cafe_babe, u64::MAX as u128 + 12345, u128::MAX / 3];
+    for &a in &samples {
+        for &b in &samples {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(Operations::Add.checked_run(x.clone(), y.clone()).and_then(|r| r.to_u128()), a.checked_add(b));
+            assert_eq!(Operations::Multiply.checked_run(x.clone(), y.clone()).and_then(|r| r.to_u128()), a.checked_mul(b));
+            assert_eq!(Operations::Divide.checked_run(x.clone(), y.clone()).map(|r| r.to_u128().unwrap()), a.checked_div(b));
+            if b != 0 {
+                assert_eq!((&x % &y).to_u128(), Some(a % b));
+            }
+            if a >= b {
+                assert_eq!(Operations::Subtract.run(x.clone(), y.clone()).to_u128(), Some(a - b));
+            }
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            assert_eq!(format!("{:x}", x), format!("{:x}", a));
+            assert_eq!(format!("{:#b}", x), format!("{:#b}", a));
+            assert_eq!(x.pow(2).to_u128(), a.checked_pow(2));
+        }
+    }
+}
+
+#[test]
+fn karatsuba_products_divide_back() {
+    // Numbers big enough that multiplication goes through Karatsuba: (a * b) / b == a.
+    let a: BigInt = "7".repeat(1000).parse().unwrap();
+    let b = BigInt::from(3u32).pow(2000) - BigInt::from(1u32);
+    let product = &a * &b;
+    assert_eq!(product.div_rem(&b), (a.clone(), BigInt::zero()));
+    assert_eq!(&product / &a, b);
+}
+
+#[test]
+fn parses_and_prints_in_every_base() {
+    let n: BigInt = "-0xDeadBeefDeadBeefDeadBeefDeadBeef1".parse().unwrap();
+    assert_eq!(n, BigInt::from_str_radix(&n.to_str_radix(2), 2).unwrap());
+    assert_eq!(n.to_string().parse::<BigInt>().unwrap(), n);
+    assert_eq!(format!("{:#x}", n), "-0xdeadbeefdeadbeefdeadbeefdeadbeef1");
+}
+
+#[test]
+fn signed_division_truncates_toward_zero() {
+    for (a, b) in [(7, 2), (-7, 2), (7, -2), (-7, -2)] {
+        let (q, r) = BigInt::from(a).div_rem(&BigInt::from(b));
+        assert_eq!((q.to_i128(), r.to_i128()), (Some((a / b) as i128), Some((a % b) as i128)));
+    }
+    assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(Operations::Divide.checked_run(BigInt::one(), BigInt::zero()), None);
+}
@@ -0,0 +1,157 @@
+use std::thread;
+use std::time::Duration;
+
+use enums::bus::{BusClosed, EventBus, Metrics, Overflow, RecvError};
+use enums::WebEvent;
+
+fn click(n: i64) -> WebEvent {
+    WebEvent::Click { x: n, y: 0 }
+}
+
+#[test]
+fn many_producers_and_consumers_lose_nothing() {
+    // Four producers and two consumers, with queues small enough that producers
+    // really do have to wait. Nothing is lost, and each producer's events arrive in
+    // the order it sent them.
+    let bus = EventBus::new();
+    let consumers: Vec<_> = (0..2)
+        .map(|_| {
+            let subscriber = bus.subscribe(8, Overflow::Block);
+            thread::spawn(move || subscriber.iter().collect::<Vec<WebEvent>>())
+        })
+        .collect();
+    let producers: Vec<_> = (0..4)
+        .map(|p| {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                for i in 0..1_000 {
+                    bus.publish(WebEvent::Click { x: p, y: i }).unwrap();
+                }
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    bus.shutdown();
+    for consumer in consumers {
+        let received = consumer.join().unwrap();
+        assert_eq!(received.len(), 4_000);
+        for p in 0..4 {
+            let ys: Vec<i64> = received
+                .iter()
+                .filter_map(|e| match e {
+                    WebEvent::Click { x, y } if *x == p => Some(*y),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(ys, (0..1_000).collect::<Vec<i64>>());
+        }
+    }
+    assert_eq!(bus.metrics(), Metrics { published: 4_000, delivered: 8_000, dropped: 0 });
+    assert_eq!(bus.publish(WebEvent::PageLoad), Err(BusClosed));
+}
+
+#[test]
+fn overflow_policies() {
+    // With nobody reading, a queue of 3 keeps the first three or the last three.
+    let bus = EventBus::new();
+    let newest = bus.subscribe(3, Overflow::DropNewest);
+    let oldest = bus.subscribe(3, Overflow::DropOldest);
+    for n in 0..5 {
+        bus.publish(click(n)).unwrap();
+    }
+    assert_eq!(newest.metrics(), Metrics { published: 5, delivered: 0, dropped: 2 });
+    assert_eq!(oldest.len(), 3);
+    bus.shutdown();
+    assert_eq!(newest.iter().collect::<Vec<_>>(), [click(0), click(1), click(2)]);
+    assert_eq!(oldest.iter().collect::<Vec<_>>(), [click(2), click(3), click(4)]);
+    assert_eq!(bus.metrics(), Metrics { published: 5, delivered: 6, dropped: 4 });
+    assert_eq!(newest.try_recv(), Err(RecvError::Closed));
+}
+
+#[test]
+fn publish_counts_queued_subscribers() {
+    // `publish` reports how many subscribers actually queued the event.
+    let bus = EventBus::new();
+    let full = bus.subscribe(1, Overflow::DropNewest);
+    let _roomy = bus.subscribe(10, Overflow::DropNewest);
+    assert_eq!(bus.publish(click(1)), Ok(2));
+    assert_eq!(bus.publish(click(2)), Ok(1));
+    assert_eq!(full.try_recv(), Ok(click(1)));
+    assert_eq!(full.try_recv(), Err(RecvError::Empty));
+    assert_eq!(full.recv_timeout(Duration::from_millis(10)), Err(RecvError::Empty));
+}
+
+#[test]
+fn blocked_publishers_are_released_by_shutdown() {
+    // A publisher stuck on a full `Block` queue is let go by a shutdown...
+    let bus = EventBus::new();
+    let stuck = bus.subscribe(1, Overflow::Block);
+    bus.publish(click(1)).unwrap();
+    let publisher = {
+        let bus = bus.clone();
+        thread::spawn(move || bus.publish(click(2)))
+    };
+    thread::sleep(Duration::from_millis(20));
+    bus.shutdown();
+    assert_eq!(publisher.join().unwrap(), Ok(0));
+    // ...and what was queued before the shutdown is still delivered.
+    assert_eq!(stuck.recv(), Some(click(1)));
+    assert_eq!(stuck.recv(), None);
+}
+
+#[test]
+fn blocked_publishers_are_released_when_the_subscriber_goes() {
+    // A publisher stuck on a full `Block` queue is let go by the subscriber going away.
+    let bus = EventBus::new();
+    let stuck = bus.subscribe(1, Overflow::Block);
+    bus.publish(click(1)).unwrap();
+    let publisher = {
+        let bus = bus.clone();
+        thread::spawn(move || bus.publish(click(2)))
+    };
+    thread::sleep(Duration::from_millis(20));
+    drop(stuck);
+    assert_eq!(publisher.join().unwrap(), Ok(0));
+    assert_eq!(bus.subscriber_count(), 0);
+}
+
+#[test]
+fn slow_consumers_pace_the_publisher() {
+    // A slow consumer with a `Block` queue slows the publisher down to its pace.
+    let bus = EventBus::new();
+    let slow = bus.subscribe(2, Overflow::Block);
+    let consumer = thread::spawn(move || {
+        let mut seen = 0;
+        while let Some(_event) = slow.recv() {
+            thread::sleep(Duration::from_millis(1));
+            seen += 1;
+        }
+        seen
+    });
+    for n in 0..50 {
+        bus.publish(click(n)).unwrap();
+    }
+    bus.shutdown();
+    assert_eq!(consumer.join().unwrap(), 50);
+
+    // Subscribing after a shutdown gives a subscriber that's closed from the start.
+    let late = bus.subscribe(4, Overflow::Block);
+    assert_eq!(late.recv(), None);
+}
+
+#[test]
+fn endless_timeouts_wait_like_recv() {
+    // `Duration::MAX` can't be added to the current time, so it waits without a
+    // deadline: for an event...
+    let bus = EventBus::new();
+    let subscriber = bus.subscribe(4, Overflow::DropNewest);
+    bus.publish(click(1)).unwrap();
+    assert_eq!(subscriber.recv_timeout(Duration::MAX), Ok(click(1)));
+    // ...or for the bus to shut down.
+    let waiting = thread::spawn(move || subscriber.recv_timeout(Duration::MAX));
+    thread::sleep(Duration::from_millis(20));
+    bus.shutdown();
+    assert_eq!(waiting.join().unwrap(), Err(RecvError::Closed));
+}
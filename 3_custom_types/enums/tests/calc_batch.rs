@@ -0,0 +1,88 @@
+// Runs the `calc-batch` binary itself, feeding it input on stdin.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn calc_batch(args: &[&str], stdin: impl AsRef<[u8]>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_calc-batch"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_ref()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn all_lines_succeeding_exits_0() {
+    let output = calc_batch(&["-"], "add 3 4\n\n# a comment\nmultiply 6 7\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "line,input,result,error\n1,add 3 4,7,\n4,multiply 6 7,42,\n");
+    assert_eq!(stderr(&output), "2 succeeded, 0 failed\n");
+}
+
+#[test]
+fn a_failed_line_exits_1_and_keeps_going() {
+    let output = calc_batch(&["-"], "divide 10 0\nadd 1 2\nadd 1,5 2\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "line,input,result,error\n1,divide 10 0,,division_by_zero\n2,add 1 2,3,\n3,\"add 1,5 2\",,invalid_operand\n");
+    let stderr = stderr(&output);
+    assert!(stderr.starts_with("1 succeeded, 2 failed\n"), "{}", stderr);
+    assert!(stderr.contains("  line 1: division by zero (division_by_zero)\n"), "{}", stderr);
+    assert!(stderr.contains("  line 3: `1,5` is not an integer (invalid_operand)\n"), "{}", stderr);
+}
+
+#[test]
+fn json_lines() {
+    let output = calc_batch(&["--format", "json", "-"], "add 3 4\nfrobnicate \"x\" 1\n");
+    assert_eq!(output.status.code(), Some(1));
+    let lines: Vec<String> = stdout(&output).lines().map(str::to_owned).collect();
+    assert_eq!(lines[0], r#"{"line":1,"input":"add 3 4","result":7}"#);
+    assert_eq!(lines[1], r#"{"line":2,"input":"frobnicate \"x\" 1","error":"unknown_operation","message":"unknown operation \"frobnicate\""}"#);
+}
+
+#[test]
+fn help_goes_to_stdout_and_exits_0() {
+    for flag in ["--help", "-h"] {
+        let output = calc_batch(&[flag], "");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).starts_with("usage: calc-batch"));
+        assert_eq!(stderr(&output), "");
+    }
+}
+
+#[test]
+fn bad_arguments_and_missing_files_exit_2() {
+    for args in [&[][..], &["--format", "xml", "-"], &["a", "b"], &["--output"]] {
+        let output = calc_batch(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert_eq!(stdout(&output), "");
+    }
+    let output = calc_batch(&["/nonexistent/calc-batch-input"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("calc-batch: /nonexistent/calc-batch-input: "));
+}
+
+#[test]
+fn a_line_that_isnt_utf8_fails_on_its_own() {
+    let output = calc_batch(&["-"], b"add 1 2\nadd \xff 2\nmultiply 6 7\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "line,input,result,error\n1,add 1 2,3,\n2,add \u{fffd} 2,,invalid_utf8\n3,multiply 6 7,42,\n");
+    assert!(stderr(&output).contains("  line 2: line is not valid UTF-8 (invalid_utf8)\n"));
+}
+
+#[test]
+fn carriage_returns_are_quoted_in_csv() {
+    let output = calc_batch(&["-"], "add 1\r2\n");
+    assert_eq!(stdout(&output), "line,input,result,error\n1,\"add 1\r2\",3,\n");
+}
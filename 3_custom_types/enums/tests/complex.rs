@@ -0,0 +1,102 @@
+use std::f64::consts::{E, PI};
+
+use enums::calc::{self, CalcError, MAX_DEPTH};
+use enums::{Complex, Operations, Rational};
+
+fn c(re: f64, im: f64) -> Complex {
+    Complex::new(re, im)
+}
+
+fn close(a: Complex, b: Complex) -> bool {
+    (a - b).modulus() < 1e-12
+}
+
+#[test]
+fn arithmetic() {
+    // (1+2i)(3-i) = 3 - i + 6i - 2i^2 = 5 + 5i
+    assert_eq!(c(1.0, 2.0) * c(3.0, -1.0), c(5.0, 5.0));
+    // (1+2i)/(3-i) = (1+2i)(3+i)/10 = (1 + 7i)/10
+    assert!(close(c(1.0, 2.0) / c(3.0, -1.0), c(0.1, 0.7)));
+    assert_eq!(c(3.0, 4.0).modulus(), 5.0);
+    assert_eq!(c(3.0, 4.0).conj(), c(3.0, -4.0));
+    assert_eq!(Complex::<f64>::i().powi(2), c(-1.0, 0.0));
+    assert_eq!(c(1.0, 1.0).powi(8), c(16.0, 0.0));
+    assert_eq!(c(0.0, 2.0).sqrt(), c(1.0, 1.0));
+    assert_eq!(c(-4.0, 0.0).sqrt(), c(0.0, 2.0));
+    assert_eq!(c(2.0, 0.0).checked_recip(), Some(c(0.5, 0.0)));
+    assert_eq!(c(0.0, 0.0).checked_recip(), None);
+}
+
+#[test]
+fn division_does_not_overflow_or_underflow() {
+    // Squaring 1e200 overflows, so `conj(w) / |w|^2` gave 0 here.
+    let huge = c(1e200, 1e200);
+    assert_eq!(huge / huge, c(1.0, 0.0));
+    assert_eq!(Operations::Divide.checked_run(huge, huge), Some(c(1.0, 0.0)));
+    assert_eq!(c(1e300, 0.0) / c(0.0, 1e300), c(0.0, -1.0));
+    // And squaring 1e-200 underflows to 0, which made this a division by zero.
+    let tiny = c(1e-200, -1e-200);
+    assert_eq!(tiny / tiny, c(1.0, 0.0));
+    assert_eq!(c(2.0, 0.0) / tiny, c(1e200, 1e200));
+    assert_eq!(Operations::Divide.checked_run(c(1.0, 0.0), c(0.0, 0.0)), None);
+    // A quotient that really is too big is still an overflow.
+    assert_eq!(Operations::Divide.checked_run(huge, c(1e-200, 0.0)), None);
+}
+
+#[test]
+fn polar_form_and_euler() {
+    assert!(close(Complex::from_polar(2.0, PI / 2.0), c(0.0, 2.0)));
+    assert_eq!(c(-1.0, 0.0).to_polar(), (1.0, PI));
+    // e^(i*pi) = -1, ln(-1) = i*pi, and i^i = e^(-pi/2) is real.
+    assert!(close(c(0.0, PI).exp(), c(-1.0, 0.0)));
+    assert!(close(c(-1.0, 0.0).ln(), c(0.0, PI)));
+    assert!(close(Complex::i().pow(&Complex::i()), c(E.powf(-PI / 2.0), 0.0)));
+}
+
+#[test]
+fn powers_of_zero() {
+    let zero = c(0.0, 0.0);
+    assert_eq!(zero.pow(&c(0.0, 0.0)), c(1.0, 0.0));
+    assert_eq!(zero.pow(&c(2.0, 5.0)), c(0.0, 0.0));
+    assert_eq!(zero.pow(&c(-1.0, 0.0)), c(f64::INFINITY, 0.0));
+    assert_eq!(zero.pow(&c(-0.5, 3.0)), c(f64::INFINITY, 0.0));
+    assert!(zero.pow(&c(0.0, 1.0)).re.is_nan());
+    assert_eq!(zero.powi(-1), c(f64::INFINITY, 0.0));
+}
+
+#[test]
+fn display() {
+    assert_eq!(c(5.0, 5.0).to_string(), "5+5i");
+    assert_eq!(c(3.0, -1.0).to_string(), "3-1i");
+    assert_eq!(c(3.0, 0.0).conj().to_string(), "3-0i");
+    assert_eq!(c(3.0, 0.0).to_string(), "3+0i");
+    assert_eq!(format!("{:.2}", c(0.5, -1.25)), "0.50-1.25i");
+}
+
+#[test]
+fn calculator() {
+    for (input, expected) in [("(1+2i)*(3-i)", c(5.0, 5.0)), ("i * i", c(-1.0, 0.0)), ("-(2 - 3i) + 4", c(2.0, 3.0)), ("(1+2i)/(3-i)", c(0.1, 0.7))] {
+        let value: Complex = calc::eval(input).unwrap();
+        assert!(close(value, expected), "{} = {}", input, value);
+    }
+    // The same parser over other value types.
+    assert_eq!(calc::eval::<i64>("8 - 4 - 2 * 3"), Ok(-2));
+    assert_eq!(calc::eval::<Rational>("1/3 + 1/6"), Ok(Rational::new(1, 2)));
+    assert_eq!(calc::eval::<i64>("2i"), Err(CalcError::InvalidLiteral("2i".to_owned())));
+    assert_eq!(calc::eval::<i64>("1 / (2 - 2)"), Err(CalcError::Arithmetic(2, Operations::Divide)));
+    assert_eq!(calc::eval::<f64>("(1 + 2"), Err(CalcError::UnexpectedEnd));
+    assert_eq!(calc::eval::<f64>("1 + * 2"), Err(CalcError::UnexpectedToken(4)));
+}
+
+#[test]
+fn calculator_nesting_is_limited() {
+    // Right at the limit is fine.
+    let parens = format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+    assert_eq!(calc::eval::<i64>(&parens), Ok(1));
+    assert_eq!(calc::eval::<i64>(&format!("{}1", "-".repeat(MAX_DEPTH))), Ok(1));
+    // One more is an error pointing at it, rather than a stack overflow.
+    assert_eq!(calc::eval::<i64>(&format!("({})", parens)), Err(CalcError::TooDeep(MAX_DEPTH)));
+    assert_eq!(calc::eval::<i64>(&format!("{}1", "-".repeat(MAX_DEPTH + 1))), Err(CalcError::TooDeep(MAX_DEPTH)));
+    assert_eq!(calc::eval::<i64>(&format!("{}1", "(-".repeat(1_000_000))), Err(CalcError::TooDeep(MAX_DEPTH)));
+    assert_eq!(CalcError::TooDeep(3).to_string(), "nested more than 128 levels deep at 3");
+}
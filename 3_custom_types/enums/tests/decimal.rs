@@ -0,0 +1,89 @@
+use enums::{Decimal, Operations, RoundingMode};
+
+type Money = Decimal<2>;
+type Token = Decimal<18>;
+
+fn d(s: &str) -> Money {
+    s.parse().unwrap()
+}
+
+#[test]
+fn addition_is_exact() {
+    assert_eq!(d("0.1") + d("0.2"), d("0.3"));
+    assert_eq!((d("0.10") + d("0.20")).to_string(), "0.30");
+}
+
+#[test]
+fn rect_area() {
+    let width = Operations::Subtract.run(d("10.5"), d("9.5")).abs();
+    let height = Operations::Subtract.run(d("10.9"), d("9.7")).abs();
+    assert_eq!(Operations::Multiply.run(width, height), d("1.20"));
+}
+
+#[test]
+fn rounding_modes() {
+    let modes = [RoundingMode::HalfEven, RoundingMode::HalfUp, RoundingMode::Floor, RoundingMode::Ceil, RoundingMode::Truncate];
+    let expected = [
+        // 2.345  -2.345  2.355  2.3451
+        ["2.34", "-2.34", "2.36", "2.35"],
+        ["2.35", "-2.35", "2.36", "2.35"],
+        ["2.34", "-2.35", "2.35", "2.34"],
+        ["2.35", "-2.34", "2.36", "2.35"],
+        ["2.34", "-2.34", "2.35", "2.34"],
+    ];
+    for (mode, row) in modes.iter().zip(expected) {
+        let got: Vec<String> = ["2.345", "-2.345", "2.355", "2.3451"].iter().map(|s| Money::parse_rounded(s, *mode).unwrap().to_string()).collect();
+        assert_eq!(got, row, "{:?}", mode);
+    }
+    // Exact parsing refuses to silently drop digits.
+    assert!("2.345".parse::<Money>().is_err());
+    assert_eq!("2.3400".parse::<Money>(), Ok(d("2.34")));
+}
+
+#[test]
+fn splitting_leaves_an_explicit_remainder() {
+    let total = d("100");
+    let share = total.checked_div(&Money::from_int(3), RoundingMode::Floor).unwrap();
+    assert_eq!((share, total - share - share - share), (d("33.33"), d("0.01")));
+}
+
+#[test]
+fn token_math_goes_through_bigint() {
+    let amount: Token = "1.5".parse().unwrap();
+    let price: Token = "2000.123456789012345678".parse().unwrap();
+    let cost = amount * price;
+    assert_eq!(cost.to_string(), "3000.185185183518518517");
+    // `i128` overflows in the middle of that multiplication.
+    assert_eq!(Operations::Divide.checked_run(cost, amount), Some(price));
+    assert_eq!(Operations::Divide.checked_run(cost, Token::ZERO), None);
+}
+
+#[test]
+fn rescale_and_round_dp() {
+    let price: Token = "2000.123456789012345678".parse().unwrap();
+    assert_eq!(price.rescale::<2>(RoundingMode::HalfEven), Some(d("2000.12")));
+    assert_eq!(d("2000.12").rescale::<18>(RoundingMode::HalfEven), Some("2000.12".parse::<Token>().unwrap()));
+    assert_eq!(d("-7.25").round_dp(1, RoundingMode::HalfEven).to_string(), "-7.20");
+    assert_eq!(d("-7.25").round_dp(5, RoundingMode::HalfEven), d("-7.25"));
+    assert_eq!(format!("{:>10}", d("-3.5")), "     -3.50");
+}
+
+#[test]
+fn out_of_range_is_none_not_a_panic() {
+    // Scales above 38 need a power of ten bigger than any `i128`.
+    assert_eq!(d("1").rescale::<39>(RoundingMode::HalfEven), None);
+    assert_eq!(Money::ZERO.rescale::<40>(RoundingMode::HalfEven), Some(Decimal::from_raw(0)));
+    assert_eq!(Decimal::<40>::from_raw(i128::MAX).rescale::<0>(RoundingMode::HalfEven), Some(Decimal::from_raw(0)));
+    assert_eq!(Decimal::<40>::from_raw(-5).rescale::<1>(RoundingMode::Floor), Some(Decimal::from_raw(-1)));
+    assert_eq!(Money::MAX.rescale::<3>(RoundingMode::HalfEven), None);
+
+    assert_eq!(Money::MIN.checked_abs(), None);
+    assert_eq!(Money::MAX.checked_abs(), Some(Money::MAX));
+    assert_eq!(d("-1.5").checked_abs(), Some(d("1.5")));
+
+    assert_eq!(Money::MAX.checked_round_dp(0, RoundingMode::Ceil), None);
+    assert_eq!(Money::MIN.checked_round_dp(1, RoundingMode::Floor), None);
+    assert_eq!(Money::MAX.checked_round_dp(0, RoundingMode::Floor), Some(Money::from_raw(i128::MAX / 100 * 100)));
+    assert_eq!(Decimal::<40>::from_raw(7).checked_round_dp(0, RoundingMode::HalfEven), Some(Decimal::ZERO));
+    assert_eq!(Decimal::<40>::from_raw(7).checked_round_dp(0, RoundingMode::Ceil), None);
+}
@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use enums::dispatcher::{Dispatched, EventDispatcher, Propagation};
+use enums::{EventKind, WebEvent};
+
+type Seen = Rc<RefCell<Vec<&'static str>>>;
+
+// A handler that records its name and then does whatever `result` says.
+fn record(seen: &Seen, name: &'static str, result: Propagation) -> impl FnMut(&WebEvent) -> Propagation {
+    let seen = Rc::clone(seen);
+    move |_: &WebEvent| {
+        seen.borrow_mut().push(name);
+        result
+    }
+}
+
+#[test]
+fn priority_then_registration_order() {
+    let seen = Seen::default();
+    let mut dispatcher = EventDispatcher::new();
+    dispatcher.on_any(record(&seen, "any", Propagation::Continue));
+    dispatcher.on(EventKind::KeyPress, record(&seen, "keys", Propagation::Continue));
+    dispatcher.register(Some(EventKind::KeyPress), 10, record(&seen, "urgent keys", Propagation::Continue));
+
+    let result = dispatcher.dispatch(&WebEvent::KeyPress('a'));
+    assert_eq!(*seen.borrow(), ["urgent keys", "any", "keys"]);
+    assert_eq!(result, Dispatched { handled: 3, stopped: false });
+
+    // Nobody listens for clicks specifically, so only the catch-all runs.
+    assert_eq!(dispatcher.dispatch(&WebEvent::Click { x: 1, y: 2 }).handled, 1);
+}
+
+#[test]
+fn stop_skips_the_remaining_handlers() {
+    let seen = Seen::default();
+    let mut dispatcher = EventDispatcher::new();
+    dispatcher.on_any(record(&seen, "any", Propagation::Continue));
+    dispatcher.register(Some(EventKind::Paste), 5, record(&seen, "paste guard", Propagation::Stop));
+
+    let result = dispatcher.dispatch(&WebEvent::Paste("secret".to_owned()));
+    assert_eq!(*seen.borrow(), ["paste guard"]);
+    assert_eq!(result, Dispatched { handled: 1, stopped: true });
+}
+
+#[test]
+fn unregistering_by_handle() {
+    let seen = Seen::default();
+    let mut dispatcher = EventDispatcher::new();
+    dispatcher.on_any(record(&seen, "any", Propagation::Continue));
+    let keys = dispatcher.on(EventKind::KeyPress, record(&seen, "keys", Propagation::Continue));
+
+    assert!(dispatcher.off(keys));
+    assert!(!dispatcher.off(keys));
+    dispatcher.dispatch(&WebEvent::KeyPress('b'));
+    assert_eq!(*seen.borrow(), ["any"]);
+}
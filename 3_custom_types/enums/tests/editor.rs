@@ -0,0 +1,188 @@
+use enums::editor::TextBuffer;
+use enums::session::SessionReader;
+use enums::terminal::decode_all;
+use enums::web_event::keys;
+use enums::WebEvent;
+
+fn typed(s: &str) -> Vec<WebEvent> {
+    s.chars().map(WebEvent::KeyPress).collect()
+}
+
+fn key(c: char) -> WebEvent {
+    WebEvent::KeyPress(c)
+}
+
+fn ctrl(c: char) -> WebEvent {
+    WebEvent::KeyPress(keys::ctrl(c).unwrap())
+}
+
+fn run(buffer: &mut TextBuffer, events: &[WebEvent]) {
+    for event in events {
+        buffer.apply(event);
+    }
+}
+
+#[test]
+fn typing_groups_into_undo_steps() {
+    // Typing, moving and fixing a typo.
+    let mut buffer = TextBuffer::new();
+    run(&mut buffer, &typed("helo wrld"));
+    run(&mut buffer, &[key(keys::LEFT), key(keys::LEFT), key(keys::LEFT), key('o')]);
+    run(&mut buffer, &[key(keys::HOME), key(keys::RIGHT), key(keys::RIGHT), key(keys::RIGHT), key('l')]);
+    assert_eq!(buffer.text(), "hello world");
+    assert_eq!(buffer.cursor(), 4);
+
+    // Each run of typing is one undo step, and the moves in between split them.
+    assert!(buffer.apply(&ctrl('z')));
+    assert_eq!(buffer.text(), "helo world");
+    assert_eq!(buffer.cursor(), 3);
+    assert!(buffer.apply(&ctrl('z')));
+    assert_eq!(buffer.text(), "helo wrld");
+    assert!(buffer.apply(&ctrl('z')));
+    assert_eq!(buffer.text(), "");
+    assert!(!buffer.apply(&ctrl('z')));
+    assert!(buffer.apply(&ctrl('y')));
+    assert!(buffer.apply(&ctrl('y')));
+    assert_eq!(buffer.text(), "helo world");
+    assert_eq!(buffer.cursor(), 7);
+
+    // A new edit after undoing throws the redo history away.
+    buffer.apply(&ctrl('z'));
+    buffer.apply(&key('!'));
+    assert_eq!(buffer.text(), "helo w!rld");
+    assert!(!buffer.can_redo());
+}
+
+#[test]
+fn backspace_and_delete() {
+    // Backspaces group together, but not with typing.
+    let mut buffer = TextBuffer::from("abc");
+    run(&mut buffer, &[key(keys::BACKSPACE), key(keys::BACKSPACE)]);
+    run(&mut buffer, &typed("xy"));
+    assert_eq!(buffer.text(), "axy");
+    buffer.undo();
+    assert_eq!(buffer.text(), "a");
+    buffer.undo();
+    assert_eq!(buffer.text(), "abc");
+    assert_eq!(buffer.cursor(), 3);
+    assert!(!buffer.can_undo());
+
+    // Delete removes forwards, and does nothing at the end.
+    run(&mut buffer, &[key(keys::HOME), key(keys::DELETE), key(keys::END), key(keys::DELETE)]);
+    assert_eq!(buffer.text(), "bc");
+}
+
+#[test]
+fn typing_over_a_selection() {
+    // Select all, then type over it. Undo brings back both the text and the selection.
+    let mut buffer = TextBuffer::from("old text");
+    run(&mut buffer, &[ctrl('a')]);
+    assert_eq!(buffer.selected_text(), "old text");
+    run(&mut buffer, &typed("new"));
+    assert_eq!(buffer.text(), "new");
+    buffer.undo();
+    assert_eq!(buffer.text(), "old text");
+    buffer.undo();
+    assert_eq!(buffer.text(), "old text");
+    assert_eq!(buffer.selection(), Some(0..8));
+}
+
+#[test]
+fn selections_through_the_api() {
+    // Selections through the API: Backspace removes them, Left and Right collapse them.
+    let mut buffer = TextBuffer::from("one two three");
+    buffer.select(4..7);
+    assert_eq!(buffer.selected_text(), "two");
+    buffer.apply(&key(keys::RIGHT));
+    assert_eq!((buffer.cursor(), buffer.selection()), (7, None));
+    buffer.move_to(4, false);
+    buffer.move_to(8, true);
+    buffer.apply(&key(keys::BACKSPACE));
+    assert_eq!(buffer.text(), "one three");
+    buffer.select(0..4);
+    buffer.apply(&key(keys::LEFT));
+    assert_eq!(buffer.cursor(), 0);
+}
+
+#[test]
+fn pastes_are_one_step() {
+    // Pastes are a single step and replace the selection. Unicode counts as characters.
+    let mut buffer = TextBuffer::new();
+    run(&mut buffer, &[WebEvent::Paste("naïve 🦀".to_owned()), key(keys::BACKSPACE)]);
+    assert_eq!(buffer.text(), "naïve ");
+    assert_eq!(buffer.len(), 6);
+    buffer.undo();
+    assert_eq!(buffer.text(), "naïve 🦀");
+    buffer.select(0..5);
+    buffer.apply(&WebEvent::Paste("clever".to_owned()));
+    assert_eq!(buffer.text(), "clever 🦀");
+}
+
+#[test]
+fn moving_between_lines() {
+    // Lines: Enter, Up and Down keep their column through shorter lines, and a click
+    // goes to a line and column.
+    let mut buffer = TextBuffer::new();
+    run(&mut buffer, &typed("a long line"));
+    run(&mut buffer, &[key(keys::ENTER)]);
+    run(&mut buffer, &typed("ab"));
+    run(&mut buffer, &[key(keys::ENTER)]);
+    run(&mut buffer, &typed("another line"));
+    run(&mut buffer, &[key(keys::LEFT), key(keys::UP), key(keys::UP)]);
+    assert_eq!(buffer.line_and_column(), (0, 11));
+    run(&mut buffer, &[key(keys::DOWN)]);
+    assert_eq!(buffer.line_and_column(), (1, 2));
+    run(&mut buffer, &[key(keys::DOWN)]);
+    assert_eq!(buffer.line_and_column(), (2, 11));
+    run(&mut buffer, &[key(keys::DOWN)]);
+    assert_eq!(buffer.line_and_column(), (2, 12));
+    run(&mut buffer, &[WebEvent::Click { x: 1, y: 1 }, key('X')]);
+    assert_eq!(buffer.text(), "a long line\naXb\nanother line");
+    run(&mut buffer, &[WebEvent::Click { x: 100, y: 100 }]);
+    assert_eq!(buffer.line_and_column(), (2, 12));
+    run(&mut buffer, &[WebEvent::Click { x: -5, y: -5 }]);
+    assert_eq!(buffer.cursor(), 0);
+
+    // Keys that don't edit are ignored.
+    assert!(!buffer.apply(&key(keys::function(5).unwrap())));
+    assert!(!buffer.apply(&key(keys::ESCAPE)));
+    assert!(!buffer.apply(&WebEvent::PageLoad));
+}
+
+#[test]
+fn replaying_a_recorded_session() {
+    // Replaying a recorded session into an editor.
+    let session = concat!(
+        r#"{"t_us":0,"type":"page_load"}"#,
+        "\n",
+        r#"{"t_us":100000,"type":"key_press","key":"H"}"#,
+        "\n",
+        r#"{"t_us":200000,"type":"key_press","key":"i"}"#,
+        "\n",
+        r#"{"t_us":300000,"type":"paste","text":", there"}"#,
+        "\n",
+        r#"{"t_us":400000,"type":"key_press","key":"\b"}"#,
+        "\n",
+        r#"{"t_us":500000,"type":"key_press","key":"e"}"#,
+        "\n",
+        r#"{"t_us":600000,"type":"key_press","key":"\u001a"}"#,
+        "\n",
+    );
+    let mut buffer = TextBuffer::new();
+    for recorded in SessionReader::new(session.as_bytes()) {
+        buffer.apply(&recorded.unwrap().event);
+    }
+    // The Ctrl+Z (\u001a) at the end only takes back the `e`: typing after a Backspace
+    // starts a new group.
+    assert_eq!(buffer.text(), "Hi, ther");
+}
+
+#[test]
+fn typing_into_a_terminal() {
+    // And one typed into a terminal: "abc", Left, Backspace, then a bracketed paste.
+    let bytes = b"abc\x1b[D\x7f\x1b[200~XY\x1b[201~";
+    let mut buffer = TextBuffer::new();
+    run(&mut buffer, &decode_all(bytes));
+    assert_eq!(buffer.text(), "aXYc");
+    assert_eq!(buffer.cursor(), 3);
+}
@@ -0,0 +1,65 @@
+// Runs the `event-filter` binary itself, feeding it a log on stdin.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn event_filter(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_event-filter"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+const LOG: &str = concat!(
+    r#"{"type":"page_load"}"#,
+    "\n",
+    r#"{"type":"click","x":300,"y":4}"#,
+    "\n",
+    r#"{"type":"click","x":3,"y":4}"#,
+    "\n",
+);
+
+#[test]
+fn prints_matching_lines() {
+    let output = event_filter(&["type == click && x > 100"], LOG);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "{\"type\":\"click\",\"x\":300,\"y\":4}\n");
+    let output = event_filter(&["--count", "type == click", "-"], LOG);
+    assert_eq!(stdout(&output), "2\n");
+}
+
+#[test]
+fn help_goes_to_stdout_and_exits_0() {
+    for flag in ["--help", "-h"] {
+        let output = event_filter(&[flag], "");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).starts_with("usage: event-filter"));
+        assert_eq!(stderr(&output), "");
+    }
+}
+
+#[test]
+fn bad_filters_and_arguments_exit_2() {
+    for args in [&[][..], &["type ==="], &["a", "b", "c"]] {
+        let output = event_filter(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert_eq!(stdout(&output), "");
+    }
+    let deep = format!("{}x == 1", "!".repeat(100_000));
+    let output = event_filter(&[&deep], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("event-filter: invalid filter: nested more than 128 levels deep\n"));
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use enums::filter::{CompareOp, Field, Filter, FilterError, FilterErrorKind, Literal, MAX_DEPTH};
+use enums::web_event::keys;
+use enums::WebEvent;
+
+#[test]
+fn filters_match_the_right_events() {
+    let events = [
+        WebEvent::PageLoad,
+        WebEvent::Click { x: 150, y: 20 },
+        WebEvent::Click { x: 150, y: 90 },
+        WebEvent::Click { x: 50, y: 20 },
+        WebEvent::KeyPress('a'),
+        WebEvent::KeyPress('"'),
+        WebEvent::KeyPress(keys::UP),
+        WebEvent::Paste("see https://example.com".to_owned()),
+        WebEvent::Paste("plain text".to_owned()),
+        WebEvent::PageUnload,
+    ];
+    // (filter, indexes of the events it should match)
+    let cases: &[(&str, &[usize])] = &[
+        ("type == click && x > 100 && y < 50", &[1]),
+        ("paste contains \"http\"", &[7]),
+        ("paste contains http", &[7]),
+        ("type == click", &[1, 2, 3]),
+        ("type != click", &[0, 4, 5, 6, 7, 8, 9]),
+        ("x >= 150 || key == a", &[1, 2, 4]),
+        // `&&` binds tighter than `||`...
+        ("type == page_load || type == click && y > 50", &[0, 2]),
+        // ...unless there are parentheses.
+        ("(type == page_load || type == click) && y < 50", &[1, 3]),
+        // A field the event doesn't have never matches, so `!` picks everything else.
+        ("x < 1000", &[1, 2, 3]),
+        ("!(x < 1000)", &[0, 4, 5, 6, 7, 8, 9]),
+        ("!!(x < 100)", &[3]),
+        ("key == \"\\\"\"", &[5]),
+        ("paste starts_with plain && paste ends_with text", &[8]),
+        ("paste contains \"\"", &[7, 8]),
+        ("x == -5", &[]),
+        ("t < 100", &[]),
+    ];
+    for (text, expected) in cases {
+        let filter: Filter = text.parse().unwrap_or_else(|e| panic!("{}: {}", text, e));
+        let matched: Vec<usize> = (0..events.len()).filter(|&i| filter.matches(&events[i])).collect();
+        assert_eq!(&matched, expected, "{}", text);
+        // Printing gives back an equivalent filter.
+        assert_eq!(filter.to_string().parse::<Filter>().unwrap(), filter, "{}", text);
+    }
+
+}
+
+#[test]
+fn printing() {
+    let filter = Filter::parse("type == click && x > 100 && y < 50").unwrap();
+    assert_eq!(filter.to_string(), "type == \"click\" && x > 100 && y < 50");
+    assert_eq!(Filter::parse("x == 1").unwrap(), Filter::Compare(Field::X, CompareOp::Eq, Literal::Int(1)));
+    let nested = Filter::parse("x == 1 || (y == 2 || !(x == 3 && y == 4))").unwrap();
+    assert_eq!(nested.to_string(), "x == 1 || (y == 2 || !(x == 3 && y == 4))");
+
+}
+
+#[test]
+fn string_literals_round_trip_through_display() {
+    for text in ["say \"hi\"", "C:\\path\\", "\\\"", "tab\there\r\n", "bell \u{7} \u{8} \u{c}", "café 🦀", ""] {
+        let filter = Filter::Compare(Field::Paste, CompareOp::Contains, Literal::Str(text.to_owned()));
+        let printed = filter.to_string();
+        assert_eq!(printed.parse::<Filter>(), Ok(filter), "{}", printed);
+    }
+    assert_eq!(Filter::parse(r#"paste == "\"\\""#).unwrap().to_string(), r#"paste == "\"\\""#);
+    // JSON's other escapes, surrogate pairs included.
+    let parsed = Filter::parse(r#"paste == "caf\u00e9 \ud83e\udd80 \/ \b\f""#).unwrap();
+    assert_eq!(parsed, Filter::Compare(Field::Paste, CompareOp::Eq, Literal::Str("café 🦀 / \u{8}\u{c}".to_owned())));
+}
+
+#[test]
+fn time_only_matches_recorded_events() {
+    // `t` only has a value for events from a recorded session.
+    let filter = Filter::parse("t >= 1500 && type == key_press").unwrap();
+    let key = WebEvent::KeyPress('k');
+    assert!(filter.matches_at(&key, Some(Duration::from_millis(1_500))));
+    assert!(!filter.matches_at(&key, Some(Duration::from_millis(1_499))));
+    assert!(!filter.matches(&key));
+
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let error = |at, kind| Err::<Filter, _>(FilterError { at, kind });
+    assert_eq!(Filter::parse("z > 1"), error(0, FilterErrorKind::UnknownField("z".to_owned())));
+    assert_eq!(Filter::parse("type == scroll"), error(8, FilterErrorKind::UnknownType("scroll".to_owned())));
+    assert_eq!(
+        Filter::parse("x > \"100\""),
+        error(4, FilterErrorKind::TypeMismatch { field: Field::X, expected: "text, but it holds numbers" })
+    );
+    assert_eq!(
+        Filter::parse("paste == 5"),
+        error(9, FilterErrorKind::TypeMismatch { field: Field::Paste, expected: "a number, but it holds text" })
+    );
+    assert_eq!(Filter::parse("x contains 5"), error(2, FilterErrorKind::InvalidOperator { field: Field::X, op: CompareOp::Contains }));
+    assert_eq!(Filter::parse("key < b"), error(4, FilterErrorKind::InvalidOperator { field: Field::Key, op: CompareOp::Lt }));
+    assert_eq!(
+        Filter::parse("type contains cl"),
+        error(5, FilterErrorKind::InvalidOperator { field: Field::Type, op: CompareOp::Contains })
+    );
+    assert_eq!(Filter::parse("x > 1 &&"), error(8, FilterErrorKind::Expected("a field name or `(`")));
+    assert_eq!(Filter::parse("(x > 1"), error(6, FilterErrorKind::Expected("`)`")));
+    assert_eq!(Filter::parse("x > 1 y"), error(6, FilterErrorKind::Expected("`&&`, `||` or the end of the filter")));
+    assert_eq!(Filter::parse("x 1"), error(2, FilterErrorKind::Expected("a comparison like `==` or `contains`")));
+    assert_eq!(Filter::parse("x > 1 & y > 2"), error(6, FilterErrorKind::UnexpectedChar('&')));
+    assert_eq!(Filter::parse("paste == \"open"), error(9, FilterErrorKind::UnterminatedString));
+    assert_eq!(Filter::parse("x > 99999999999999999999"), error(4, FilterErrorKind::InvalidNumber("99999999999999999999".to_owned())));
+    assert_eq!(Filter::parse(""), error(0, FilterErrorKind::Expected("a field name or `(`")));
+    assert_eq!(Filter::parse(r#"paste == "a\u12""#), error(11, FilterErrorKind::InvalidEscape));
+    assert_eq!(Filter::parse(r#"paste == "\ud83e""#), error(10, FilterErrorKind::InvalidEscape));
+    assert_eq!(Filter::parse(r#"paste == "\udd80""#), error(10, FilterErrorKind::InvalidEscape));
+    assert_eq!(Filter::parse(r#"paste == "\ud83e\u0041""#), error(10, FilterErrorKind::InvalidEscape));
+
+    let message = Filter::parse("z > 1").unwrap_err().kind.to_string();
+    assert_eq!(message, "unknown field \"z\", expected one of: type, x, y, key, paste, t");
+}
+
+#[test]
+fn deep_nesting_is_an_error_not_a_stack_overflow() {
+    // Right at the limit is fine, either way of nesting.
+    let nots = format!("{}x == 1", "!".repeat(MAX_DEPTH));
+    assert!(Filter::parse(&nots).is_ok());
+    let parens = format!("{}x == 1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+    assert!(Filter::parse(&parens).is_ok());
+    // One more points at the `!` or `(` that went too deep.
+    let too_deep = FilterError { at: MAX_DEPTH, kind: FilterErrorKind::TooDeep };
+    assert_eq!(Filter::parse(&format!("!{}", nots)), Err(too_deep.clone()));
+    assert_eq!(Filter::parse(&format!("({})", parens)), Err(too_deep));
+    assert_eq!(FilterErrorKind::TooDeep.to_string(), "nested more than 128 levels deep");
+    // And a million of them, mixed, is just as quick to turn down.
+    let huge = format!("{}x == 1", "!(".repeat(500_000));
+    assert_eq!(Filter::parse(&huge).unwrap_err().kind, FilterErrorKind::TooDeep);
+}
@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use enums::analytics::SessionStats;
+use enums::editor::TextBuffer;
+use enums::filter::Filter;
+use enums::fuzz::{self, FuzzConfig, Generator, Rng, Weights};
+use enums::gesture::{GestureConfig, GestureRecognizer};
+use enums::jsonl;
+use enums::keymap::Keymap;
+use enums::{EventKind, WebEvent};
+
+// Enough events to find the bugs below without making the tests slow.
+fn small_config() -> FuzzConfig {
+    FuzzConfig { streams: 100, stream_len: 200, ..FuzzConfig::default() }
+}
+
+#[test]
+fn the_same_seed_gives_the_same_events() {
+    // The same seed gives the same events.
+    let first: Vec<WebEvent> = Generator::new(42).take(1_000).collect();
+    assert_eq!(first, Generator::new(42).take(1_000).collect::<Vec<_>>());
+    assert_ne!(first, Generator::new(43).take(1_000).collect::<Vec<_>>());
+    // And all the awkward cases turn up.
+    let events: Vec<WebEvent> = Generator::new(7).take(100_000).collect();
+    let kinds = |kind| events.iter().filter(|e| e.kind() == kind).count();
+    assert!(kinds(EventKind::KeyPress) > kinds(EventKind::Click) && kinds(EventKind::Click) > kinds(EventKind::PageLoad));
+    assert!(events.iter().any(|e| matches!(e, WebEvent::Click { x: i64::MIN, .. })));
+    assert!(events.iter().any(|e| matches!(e, WebEvent::Click { y: i64::MAX, .. })));
+    assert!(events.iter().any(|e| matches!(e, WebEvent::Paste(text) if text.is_empty())));
+    assert!(events.iter().any(|e| matches!(e, WebEvent::Paste(text) if text.chars().count() > 5_000)));
+    assert!(events.iter().any(|e| matches!(e, WebEvent::KeyPress(c) if c.len_utf8() == 4)));
+    assert!(events.contains(&WebEvent::KeyPress('\0')));
+    let pastes: Vec<WebEvent> = Generator::new(1).weights(Weights::only(EventKind::Paste)).max_paste_len(10).take(100).collect();
+    assert!(pastes.iter().all(|e| matches!(e, WebEvent::Paste(text) if text.chars().count() <= 10)));
+    // `weighted` picks in proportion.
+    let mut rng = Rng::new(0);
+    let picks = (0..10_000).filter(|_| rng.weighted(&[1, 3]) == 1).count();
+    assert!((7_000..8_000).contains(&picks), "{}", picks);
+}
+
+#[test]
+fn library_handlers_survive() {
+    // The library's own handlers, which shouldn't panic on anything.
+    let config = small_config();
+    let total = fuzz::fuzz(&config, || {
+        let mut buffer = TextBuffer::new();
+        move |event: &WebEvent| {
+            buffer.apply(event);
+            assert!(buffer.cursor() <= buffer.len());
+        }
+    });
+    assert_eq!(total, Ok(20_000));
+    fuzz::fuzz(&config, || {
+        let mut stats = SessionStats::new();
+        let mut at = Duration::ZERO;
+        move |event: &WebEvent| {
+            at += Duration::from_millis(37);
+            stats.add(event, Some(at));
+            if let Some(heatmap) = stats.heatmap(7, 5) {
+                heatmap.render_ascii();
+            }
+        }
+    })
+    .unwrap();
+    fuzz::fuzz(&config, || {
+        let mut keymap = Keymap::from_config("[normal]\ng g = top\nAlt+x = palette\ni = mode insert\n[insert]\nEsc = mode normal").unwrap();
+        let mut gestures = GestureRecognizer::new(GestureConfig::default());
+        let mut at = Duration::ZERO;
+        move |event: &WebEvent| {
+            at += Duration::from_millis(300);
+            keymap.feed(at, event);
+            gestures.feed(at, event.clone().into());
+        }
+    })
+    .unwrap();
+    let filter = Filter::parse("paste contains \"\u{301}\" || x < 0 || key == \"\\u0000\"").unwrap();
+    fuzz::fuzz(&config, || {
+        |event: &WebEvent| {
+            filter.matches(event);
+            // Every event survives a trip through JSON Lines.
+            assert_eq!(jsonl::from_json(&jsonl::to_json(event)).as_ref(), Ok(event));
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn a_bad_slice_shrinks_to_one_paste() {
+    let config = small_config();
+    // A handler with a bug: it shows the first ten bytes of a paste, which panics
+    // when byte ten falls inside a character. It shrinks to a single paste.
+    let failure = fuzz::fuzz(&config, || {
+        |event: &WebEvent| {
+            if let WebEvent::Paste(text) = event {
+                if text.len() > 10 {
+                    let _preview = &text[..10];
+                }
+            }
+        }
+    })
+    .unwrap_err();
+    assert_eq!(failure.events.len(), 1, "{}", failure);
+    assert!(failure.message.contains("is not a char boundary"));
+    let WebEvent::Paste(text) = &failure.events[0] else { panic!("{}", failure) };
+    assert!(text.chars().count() <= 11, "{}", failure);
+    // The failing stream can be made again from its seed, ending with the paste that did it.
+    let again: Vec<WebEvent> = Generator::new(failure.stream_seed).take(failure.original_len).collect();
+    assert!(matches!(again.last(), Some(WebEvent::Paste(text)) if text.len() > 10 && !text.is_char_boundary(10)));
+}
+
+#[test]
+fn a_bug_that_needs_history_keeps_only_what_it_needs() {
+    let config = small_config();
+    // A bug that needs history: unloading twice in a row. Out of hundreds of events,
+    // only the two that matter are left.
+    let failure = fuzz::fuzz(&config, || {
+        let mut loaded = true;
+        move |event: &WebEvent| match event {
+            WebEvent::PageLoad => loaded = true,
+            WebEvent::PageUnload => {
+                assert!(loaded, "unloaded a page that wasn't loaded");
+                loaded = false;
+            },
+            _ => {},
+        }
+    })
+    .unwrap_err();
+    assert_eq!(failure.events, [WebEvent::PageUnload, WebEvent::PageUnload]);
+    assert_eq!(failure.message, "unloaded a page that wasn't loaded");
+    assert!(failure.original_len > 2);
+    assert!(failure.to_string().ends_with("{\"type\":\"page_unload\"}\n{\"type\":\"page_unload\"}\n"));
+}
+
+#[test]
+fn clicks_shrink_towards_zero() {
+    let config = small_config();
+    // Clicks shrink towards zero, keeping what the bug needs.
+    let failure = fuzz::fuzz(&config, || {
+        |event: &WebEvent| {
+            if let WebEvent::Click { x, y } = event {
+                assert!(*x < 1_000 || *y < 1_000, "far corner");
+            }
+        }
+    })
+    .unwrap_err();
+    let [WebEvent::Click { x, y }] = failure.events[..] else { panic!("{}", failure) };
+    assert!((1_000..2_000).contains(&x) && (1_000..2_000).contains(&y), "{}", failure);
+}
+
+#[test]
+fn a_panic_making_the_handler_is_a_failure() {
+    let failure = fuzz::fuzz(&small_config(), || -> fn(&WebEvent) { panic!("no handler") }).unwrap_err();
+    assert_eq!((failure.events.len(), failure.original_len), (0, 0));
+    assert_eq!(failure.message, "no handler");
+}
+
+#[test]
+#[should_panic(expected = "at least one kind of event needs a weight")]
+fn weights_are_checked_before_fuzzing() {
+    let config = FuzzConfig { weights: Weights { page_load: 0, page_unload: 0, key_press: 0, paste: 0, click: 0 }, ..small_config() };
+    let _ = fuzz::fuzz(&config, || |_: &WebEvent| {});
+}
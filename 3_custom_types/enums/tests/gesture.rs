@@ -0,0 +1,210 @@
+// Gesture recognition over synthetic input streams, one table row per case.
+
+use std::time::Duration;
+
+use enums::gesture::{ChordError, Gesture, GestureConfig, GestureRecognizer, Input, PointerAction};
+use enums::web_event::keys;
+use enums::WebEvent;
+
+fn click(x: i64, y: i64) -> Input {
+    WebEvent::Click { x, y }.into()
+}
+
+fn key(c: char) -> Input {
+    WebEvent::KeyPress(c).into()
+}
+
+fn ctrl(c: char) -> Input {
+    key(keys::ctrl(c).unwrap())
+}
+
+fn down(x: i64, y: i64) -> Input {
+    Input::Pointer { action: PointerAction::Down, x, y }
+}
+
+fn moved(x: i64, y: i64) -> Input {
+    Input::Pointer { action: PointerAction::Move, x, y }
+}
+
+fn up(x: i64, y: i64) -> Input {
+    Input::Pointer { action: PointerAction::Up, x, y }
+}
+
+fn clicked(x: i64, y: i64, count: u32) -> Gesture {
+    Gesture::Click { x, y, count }
+}
+
+fn chord(name: &str) -> Gesture {
+    Gesture::Chord(name.to_owned())
+}
+
+fn recognizer(config: GestureConfig) -> GestureRecognizer {
+    let mut recognizer = GestureRecognizer::new(config);
+    recognizer.chord("Ctrl+K Ctrl+C", "comment").unwrap();
+    recognizer.chord("Ctrl+K Ctrl+U", "uncomment").unwrap();
+    recognizer.chord("g", "go").unwrap();
+    recognizer.chord("g g", "top").unwrap();
+    recognizer
+}
+
+// (what's being checked, config, [(time in ms, input)], expected gestures,
+//  time to tick at after the last input)
+type Case = (&'static str, GestureConfig, Vec<(u64, Input)>, Vec<Gesture>, Option<u64>);
+
+#[test]
+fn recognizes_gestures() {
+    let slow = GestureConfig { multi_click_interval: Duration::from_millis(1_000), drag_threshold: 10, ..GestureConfig::default() };
+
+    let cases: Vec<Case> = vec![
+        ("single click", GestureConfig::default(), vec![(0, click(5, 5))], vec![clicked(5, 5, 1)], None),
+        (
+            "double click",
+            GestureConfig::default(),
+            vec![(0, click(5, 5)), (300, click(6, 4))],
+            vec![clicked(5, 5, 1), clicked(6, 4, 2)],
+            None,
+        ),
+        (
+            "triple click, then a fourth starts over",
+            GestureConfig::default(),
+            vec![(0, click(5, 5)), (200, click(5, 5)), (400, click(5, 5)), (600, click(5, 5))],
+            vec![clicked(5, 5, 1), clicked(5, 5, 2), clicked(5, 5, 3), clicked(5, 5, 1)],
+            None,
+        ),
+        (
+            "exactly at the interval still counts, just over doesn't",
+            GestureConfig::default(),
+            vec![(0, click(0, 0)), (500, click(0, 0)), (1_001, click(0, 0))],
+            vec![clicked(0, 0, 1), clicked(0, 0, 2), clicked(0, 0, 1)],
+            None,
+        ),
+        (
+            "too slow for the default, fine for a longer interval",
+            slow,
+            vec![(0, click(0, 0)), (800, click(0, 0))],
+            vec![clicked(0, 0, 1), clicked(0, 0, 2)],
+            None,
+        ),
+        (
+            "too far apart",
+            GestureConfig::default(),
+            vec![(0, click(0, 0)), (100, click(5, 0)), (200, click(5, 4))],
+            vec![clicked(0, 0, 1), clicked(5, 0, 1), clicked(5, 4, 2)],
+            None,
+        ),
+        (
+            "press and release with a wobble is a click where it went down",
+            GestureConfig::default(),
+            vec![(0, down(10, 10)), (50, moved(12, 13)), (100, up(13, 13)), (200, down(10, 10)), (250, up(10, 10))],
+            vec![clicked(10, 10, 1), clicked(10, 10, 2)],
+            None,
+        ),
+        (
+            "drag",
+            GestureConfig::default(),
+            vec![(0, down(10, 10)), (20, moved(12, 10)), (40, moved(20, 10)), (60, moved(30, 15)), (80, up(31, 15))],
+            vec![
+                Gesture::DragStart { x: 10, y: 10 },
+                Gesture::DragMove { x: 20, y: 10 },
+                Gesture::DragMove { x: 30, y: 15 },
+                Gesture::DragEnd { x: 31, y: 15 },
+            ],
+            None,
+        ),
+        (
+            "the same motion is only a click with a bigger threshold",
+            slow,
+            vec![(0, down(10, 10)), (20, moved(12, 10)), (40, moved(20, 10)), (80, up(20, 10))],
+            vec![clicked(10, 10, 1)],
+            None,
+        ),
+        ("moving with nothing pressed does nothing", GestureConfig::default(), vec![(0, moved(1, 1)), (10, up(50, 50))], vec![], None),
+        (
+            "a drag isn't part of a double click",
+            GestureConfig::default(),
+            vec![(0, click(0, 0)), (100, down(0, 0)), (150, moved(0, 50)), (200, up(0, 50)), (300, click(0, 0))],
+            vec![
+                clicked(0, 0, 1),
+                Gesture::DragStart { x: 0, y: 0 },
+                Gesture::DragMove { x: 0, y: 50 },
+                Gesture::DragEnd { x: 0, y: 50 },
+                clicked(0, 0, 2),
+            ],
+            None,
+        ),
+        ("chord", GestureConfig::default(), vec![(0, ctrl('k')), (400, ctrl('c'))], vec![chord("comment")], None),
+        ("the other chord with the same prefix", GestureConfig::default(), vec![(0, ctrl('k')), (400, ctrl('u'))], vec![chord("uncomment")], None),
+        (
+            "a broken chord gives its keys back",
+            GestureConfig::default(),
+            vec![(0, ctrl('k')), (100, key('x'))],
+            vec![Gesture::Key(keys::ctrl('k').unwrap()), Gesture::Key('x')],
+            None,
+        ),
+        (
+            "too slow, so not a chord",
+            GestureConfig::default(),
+            vec![(0, ctrl('k')), (1_500, ctrl('c'))],
+            vec![Gesture::Key(keys::ctrl('k').unwrap()), Gesture::Key(keys::ctrl('c').unwrap())],
+            None,
+        ),
+        (
+            "an unfinished chord is let go by a tick after the timeout",
+            GestureConfig::default(),
+            vec![(0, key('a')), (10, ctrl('k'))],
+            vec![Gesture::Key('a'), Gesture::Key(keys::ctrl('k').unwrap())],
+            Some(1_100),
+        ),
+        ("a chord that's a prefix of another waits...", GestureConfig::default(), vec![(0, key('g'))], vec![], Some(900)),
+        ("...and fires on the timeout", GestureConfig::default(), vec![(0, key('g'))], vec![chord("go")], Some(1_100)),
+        ("...or becomes the longer one", GestureConfig::default(), vec![(0, key('g')), (300, key('g'))], vec![chord("top")], None),
+        (
+            "...or fires when something else comes",
+            GestureConfig::default(),
+            vec![(0, key('g')), (100, key('x')), (200, key('g')), (300, click(1, 1))],
+            vec![chord("go"), Gesture::Key('x'), chord("go"), clicked(1, 1, 1)],
+            None,
+        ),
+        (
+            "a press during a drag ends the drag first",
+            GestureConfig::default(),
+            vec![(0, down(0, 0)), (20, moved(20, 0)), (40, moved(25, 5)), (60, down(100, 100)), (80, up(100, 100))],
+            vec![
+                Gesture::DragStart { x: 0, y: 0 },
+                Gesture::DragMove { x: 20, y: 0 },
+                Gesture::DragMove { x: 25, y: 5 },
+                Gesture::DragEnd { x: 25, y: 5 },
+                clicked(100, 100, 1),
+            ],
+            None,
+        ),
+        (
+            "other events pass through",
+            GestureConfig::default(),
+            vec![(0, WebEvent::PageLoad.into()), (5, WebEvent::Paste("hi".to_owned()).into())],
+            vec![Gesture::Other(WebEvent::PageLoad), Gesture::Other(WebEvent::Paste("hi".to_owned()))],
+            None,
+        ),
+    ];
+
+    for (name, config, inputs, expected, tick) in cases {
+        let mut recognizer = recognizer(config);
+        let mut gestures = Vec::new();
+        for (ms, input) in inputs {
+            gestures.extend(recognizer.feed(Duration::from_millis(ms), input));
+        }
+        if let Some(ms) = tick {
+            gestures.extend(recognizer.tick(Duration::from_millis(ms)));
+        }
+        assert_eq!(gestures, expected, "{}", name);
+    }
+
+}
+
+#[test]
+fn chord_specs_are_checked() {
+    let mut recognizer = GestureRecognizer::new(GestureConfig::default());
+    assert_eq!(recognizer.chord("Ctrl+K Hyper+X", "nope"), Err(ChordError::UnknownKey("Hyper+X".to_owned())));
+    assert_eq!(recognizer.chord("   ", "nope"), Err(ChordError::Empty));
+    assert_eq!(recognizer.chord("Esc F5 Enter PageDown", "named keys"), Ok(()));
+}
@@ -0,0 +1,80 @@
+use enums::{Interval, Operations};
+
+fn d(s: &str) -> Interval {
+    Interval::from_decimal_str(s).unwrap()
+}
+
+#[test]
+fn rect_area_contains_the_exact_answer() {
+    let width = Operations::Subtract.run(d("9.5"), d("10.5")).abs();
+    let height = Operations::Subtract.run(d("10.9"), d("9.7")).abs();
+    let area = Operations::Multiply.run(width, height);
+    assert!(area.contains(1.2));
+    assert!(area.width() < 1e-13);
+}
+
+#[test]
+fn repeated_addition_keeps_the_exact_sum() {
+    let mut sum = 0.0;
+    let mut bounds = Interval::point(0.0);
+    for _ in 0..10 {
+        sum += 0.1;
+        bounds = Operations::Add.run(bounds, d("0.1"));
+    }
+    assert!(sum != 1.0 && bounds.contains(1.0));
+}
+
+#[test]
+fn mixed_signs_division_sqrt_and_abs() {
+    let a = Interval::new(-2.0, 3.0);
+    let b = Interval::new(4.0, 5.0);
+    assert!((a * b).contains(-10.0) && (a * b).contains(15.0));
+    assert!((a / b).contains(-0.5) && (a / b).contains(0.75));
+    assert_eq!(Operations::Divide.checked_run(b, a), None);
+    assert!(Interval::point(2.0).sqrt().contains(std::f64::consts::SQRT_2));
+    assert_eq!(a.abs(), Interval::new(0.0, 3.0));
+    assert_eq!(Interval::new(-3.0, -1.0).checked_sqrt(), None);
+}
+
+#[test]
+fn diagonal_from_f32_points() {
+    let (x1, y1, x2, y2) = (Interval::point(0.1f32 as f64), Interval::point(0.2f32 as f64), Interval::point(0.4f32 as f64), Interval::point(0.6f32 as f64));
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let diagonal = (dx * dx + dy * dy).sqrt();
+    assert!(diagonal.contains(((0.4f32 as f64 - 0.1f32 as f64).powi(2) + (0.6f32 as f64 - 0.2f32 as f64).powi(2)).sqrt()));
+}
+
+#[test]
+fn nan_is_rejected() {
+    assert_eq!(Interval::checked_new(f64::NAN, 1.0), None);
+    assert_eq!(Interval::checked_new(0.0, f64::NAN), None);
+    assert_eq!(Interval::checked_new(f64::NAN, f64::NAN), None);
+    assert_eq!(Interval::checked_new(2.0, 1.0), None);
+    assert!(std::panic::catch_unwind(|| Interval::new(f64::NAN, f64::NAN)).is_err());
+    assert_eq!(Interval::checked_point(f64::NAN), None);
+    assert_eq!(Interval::checked_point(f32::NAN as f64), None);
+    assert!(std::panic::catch_unwind(|| Interval::point(f64::NAN)).is_err());
+    assert_eq!(Interval::from_decimal_str("NaN"), None);
+
+    // Arithmetic that would produce a NaN bound gives `None` instead.
+    let everything = Interval::new(f64::NEG_INFINITY, f64::INFINITY);
+    assert_eq!(Operations::Add.checked_run(everything, everything), Some(everything));
+    let up = Interval::point(f64::INFINITY);
+    assert_eq!(Operations::Subtract.checked_run(up, up), None);
+    assert_eq!(Operations::Add.checked_run(up, -up), None);
+    // Except that an infinite bound times zero is zero: an unbounded interval still
+    // only holds finite values.
+    assert_eq!(Operations::Multiply.checked_run(up, Interval::point(0.0)), Some(Interval::point(0.0)));
+}
+
+#[test]
+fn zero_times_an_unbounded_interval() {
+    let unbounded = Interval::new(1.0, f64::INFINITY);
+    assert_eq!(Interval::new(0.0, 1.0).checked_mul(&unbounded), Some(Interval::new(0.0, f64::INFINITY)));
+    assert_eq!(Interval::new(-1.0, 0.0).checked_mul(&unbounded), Some(Interval::new(f64::NEG_INFINITY, 0.0)));
+    assert_eq!(Interval::new(-1.0, 2.0).checked_mul(&unbounded), Some(Interval::new(f64::NEG_INFINITY, f64::INFINITY)));
+    // Products that aren't zero are still widened.
+    let product = Interval::new(2.0, 3.0).checked_mul(&Interval::new(0.0, 0.1)).unwrap();
+    assert_eq!(product.lo(), 0.0);
+    assert!(product.hi() > 0.30000000000000004);
+}
@@ -0,0 +1,114 @@
+use enums::json::{self, JsonErrorKind, Value, MAX_DEPTH};
+use enums::jsonl::{from_json, to_json, write_event, EventReader, ParseError, ParseErrorKind};
+use enums::WebEvent;
+
+fn events() -> Vec<WebEvent> {
+    vec![
+        WebEvent::PageLoad,
+        WebEvent::KeyPress('x'),
+        WebEvent::KeyPress('é'),
+        WebEvent::KeyPress('\u{1f980}'),
+        WebEvent::KeyPress('\\'),
+        WebEvent::KeyPress('\n'),
+        WebEvent::Paste(String::new()),
+        WebEvent::Paste("my text".to_owned()),
+        WebEvent::Paste("quotes \" and \\backslashes\\ and\r\nnewlines".to_owned()),
+        WebEvent::Paste("日本語 🦀 \u{0} \u{7f} \u{2028}".to_owned()),
+        WebEvent::Click { x: 20, y: 80 },
+        WebEvent::Click { x: i64::MIN, y: i64::MAX },
+        WebEvent::PageUnload,
+    ]
+}
+
+#[test]
+fn encoding_is_fixed() {
+    assert_eq!(to_json(&WebEvent::Click { x: 20, y: 80 }), r#"{"type":"click","x":20,"y":80}"#);
+    assert_eq!(to_json(&WebEvent::PageLoad), r#"{"type":"page_load"}"#);
+    assert_eq!(to_json(&WebEvent::KeyPress('"')), r#"{"type":"key_press","key":"\""}"#);
+    assert_eq!(to_json(&WebEvent::Paste("a\tb\n".to_owned())), r#"{"type":"paste","text":"a\tb\n"}"#);
+    // Non-ASCII goes out as UTF-8, only control characters get `\u` escapes.
+    assert_eq!(to_json(&WebEvent::Paste("ü\u{1}".to_owned())), r#"{"type":"paste","text":"ü\u0001"}"#);
+}
+
+#[test]
+fn events_round_trip_alone_and_as_a_stream() {
+    let events = events();
+    for event in &events {
+        assert_eq!(from_json(&to_json(event)), Ok(event.clone()), "{:?}", event);
+    }
+    let mut log = Vec::new();
+    for event in &events {
+        write_event(&mut log, event).unwrap();
+    }
+    let read: Result<Vec<WebEvent>, ParseError> = EventReader::new(&log[..]).collect();
+    assert_eq!(read.unwrap(), events);
+}
+
+#[test]
+fn reading_is_lenient_about_layout() {
+    // Other field orders, whitespace, `\u` escapes (including surrogate pairs) and
+    // fields it doesn't know about.
+    assert_eq!(from_json(r#" { "y" : -3, "x": 4, "type": "click", "button": "left" } "#), Ok(WebEvent::Click { x: 4, y: -3 }));
+    assert_eq!(from_json(r#"{"type":"key_press","key":"\ud83e\udd80"}"#), Ok(WebEvent::KeyPress('🦀')));
+    assert_eq!(from_json(r#"{"type":"paste","text":"caf\u00e9\/"}"#), Ok(WebEvent::Paste("café/".to_owned())));
+}
+
+#[test]
+fn errors_carry_their_line_and_reading_carries_on() {
+    // A byte string, so it can hold a line that isn't UTF-8.
+    let input: &[u8] = b"{\"type\":\"page_load\"}\n\
+                 \n\
+                 {\"type\":\"scroll\"}\n\
+                 {\"type\":\"click\",\"x\":1}\n\
+                 {\"type\":\"click\",\"x\":1.5,\"y\":2}\n\
+                 {\"type\":\"key_press\",\"key\":\"ab\"}\n\
+                 {\"type\":\"paste\",\"text\":\"unterminated}\n\
+                 [1,2]\n\
+                 \xff\n\
+                 {\"type\":\"page_unload\"}\n";
+    let results: Vec<_> = EventReader::new(input).collect();
+    let error = |line, kind| Err(ParseError { line, kind });
+    assert_eq!(results[0], Ok(WebEvent::PageLoad));
+    // Line 2 is blank and skipped, but still counted.
+    assert_eq!(results[1], error(3, ParseErrorKind::UnknownType("scroll".to_owned())));
+    assert_eq!(results[2], error(4, ParseErrorKind::MissingField("y")));
+    assert_eq!(results[3], error(5, ParseErrorKind::WrongType { field: "x", expected: "an integer" }));
+    assert_eq!(results[4], error(6, ParseErrorKind::InvalidKey("ab".to_owned())));
+    assert!(matches!(&results[5], Err(ParseError { line: 7, kind: ParseErrorKind::Json(_) })));
+    assert_eq!(results[6], error(8, ParseErrorKind::NotAnObject));
+    assert_eq!(results[7], error(9, ParseErrorKind::InvalidUtf8));
+    assert_eq!(results[8], Ok(WebEvent::PageUnload));
+    assert_eq!(results.len(), 9);
+    assert_eq!(results[2].as_ref().unwrap_err().to_string(), "line 4: missing field \"y\"");
+}
+
+#[test]
+fn json_parser_rejects_what_json_does_not_allow() {
+    for bad in ["", "{", "{\"a\" 1}", "[1,]", "01", "-", "1.", "\"\\x\"", "\"\\ud800\"", "\"a\nb\"", "{} {}", "tru"] {
+        let err = json::parse(bad).unwrap_err();
+        assert_eq!(err.kind, JsonErrorKind::Syntax, "{:?}", bad);
+    }
+}
+
+#[test]
+fn nesting_is_limited() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(json::parse(&nested(MAX_DEPTH)).is_ok());
+    let err = json::parse(&nested(MAX_DEPTH + 1)).unwrap_err();
+    assert_eq!((err.kind, err.offset), (JsonErrorKind::TooDeep, MAX_DEPTH));
+    assert_eq!(err.to_string(), "nested too deeply at byte 128");
+
+    // Deep enough to overflow the stack without the limit.
+    let err = json::parse(&"[".repeat(100_000)).unwrap_err();
+    assert_eq!(err.kind, JsonErrorKind::TooDeep);
+    let objects = "{\"a\":".repeat(200) + "1" + &"}".repeat(200);
+    assert_eq!(json::parse(&objects).unwrap_err().kind, JsonErrorKind::TooDeep);
+
+    // Siblings don't add up: depth goes back down after each one closes.
+    let wide = format!("[{}]", vec![nested(MAX_DEPTH - 1); 3].join(","));
+    assert!(matches!(json::parse(&wide), Ok(Value::Array(items)) if items.len() == 3));
+
+    // And a too-deep line in a log is just another bad line.
+    let line = format!("{{\"type\":\"page_load\",\"extra\":{}}}", nested(200));
+    assert!(matches!(from_json(&line), Err(ParseErrorKind::Json(e)) if e.kind == JsonErrorKind::TooDeep));
+}
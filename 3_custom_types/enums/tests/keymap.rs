@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use enums::keymap::{parse_keys, Action, ConfigError, ConfigErrorKind, KeySpecError, Keymap};
+use enums::web_event::keys;
+use enums::WebEvent;
+
+const CONFIG: &str = "
+# Half a second to finish a sequence.
+timeout = 500
+
+[normal]
+h = move-left
+l = move-right
+g = go-to-line
+g g = go-to-top
+Ctrl+W v = split-vertical
+Ctrl+W s = split-horizontal
+Alt+x = command-palette
+Shift+z Shift+z = save-and-quit
+= = equalize
+i = mode insert
+
+[insert]
+Esc = mode normal
+Ctrl+W = delete-word
+";
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn command(name: &str) -> Action {
+    Action::Command(name.to_owned())
+}
+
+fn mode(name: &str) -> Action {
+    Action::Mode(name.to_owned())
+}
+
+// Feeds keys 10ms apart, starting at `start` milliseconds.
+fn type_keys(keymap: &mut Keymap, start: u64, keys: &[char]) -> Vec<Action> {
+    let mut out = Vec::new();
+    for (i, &c) in keys.iter().enumerate() {
+        out.extend(keymap.feed(ms(start + 10 * i as u64), &WebEvent::KeyPress(c)));
+    }
+    out
+}
+
+#[test]
+fn loads_a_config() {
+    let keymap = Keymap::from_config(CONFIG).unwrap();
+    assert_eq!(keymap.mode(), "normal");
+    assert_eq!(keymap.lookup("normal", "Ctrl+W v"), Some("split-vertical"));
+    assert_eq!(keymap.lookup("insert", "h"), None);
+}
+
+#[test]
+fn single_keys_fire_straight_away() {
+    let mut keymap = Keymap::from_config(CONFIG).unwrap();
+    // Single keys with nothing longer starting the same way fire straight away.
+    assert_eq!(type_keys(&mut keymap, 0, &['h', 'l', 'h']), [command("move-left"), command("move-right"), command("move-left")]);
+}
+
+#[test]
+fn prefixes_wait_for_the_rest_of_a_sequence() {
+    let mut keymap = Keymap::from_config(CONFIG).unwrap();
+    // `g` could be the start of `g g`, so it waits...
+    assert_eq!(type_keys(&mut keymap, 100, &['g']), []);
+    assert_eq!(keymap.deadline(), Some(ms(600)));
+    // ...and either the second `g` comes...
+    assert_eq!(type_keys(&mut keymap, 110, &['g']), [command("go-to-top")]);
+    // ...or the timeout passes and it's just `g`...
+    type_keys(&mut keymap, 1_000, &['g']);
+    assert_eq!(keymap.tick(ms(1_500)), []);
+    assert_eq!(keymap.tick(ms(1_501)), [command("go-to-line")]);
+    // ...or a different key shows up, which settles the `g` and then counts on its own.
+    assert_eq!(type_keys(&mut keymap, 2_000, &['g', 'h']), [command("go-to-line"), command("move-left")]);
+}
+
+#[test]
+fn modifiers_and_broken_sequences() {
+    let mut keymap = Keymap::from_config(CONFIG).unwrap();
+    // Modifiers: Ctrl+W is the control character, Alt+x is Escape then `x`, Shift+z is `Z`.
+    let ctrl_w = keys::ctrl('w').unwrap();
+    assert_eq!(type_keys(&mut keymap, 3_000, &[ctrl_w, 's']), [command("split-horizontal")]);
+    assert_eq!(type_keys(&mut keymap, 3_100, &[keys::ESCAPE, 'x']), [command("command-palette")]);
+    assert_eq!(type_keys(&mut keymap, 3_200, &['Z', 'Z']), [command("save-and-quit")]);
+    assert_eq!(type_keys(&mut keymap, 3_300, &['=', '=']), [command("equalize"), command("equalize")]);
+
+    // A sequence that goes wrong partway gives back its keys, which get another look.
+    assert_eq!(type_keys(&mut keymap, 3_400, &[ctrl_w, 'h']), [Action::Key(ctrl_w), command("move-left")]);
+    assert_eq!(type_keys(&mut keymap, 3_500, &['q']), [Action::Key('q')]);
+}
+
+#[test]
+fn modes_switch_bindings() {
+    let mut keymap = Keymap::from_config(CONFIG).unwrap();
+    let ctrl_w = keys::ctrl('w').unwrap();
+    // Modes. In insert mode nothing is bound to letters, so they come out as keys to type.
+    let out = type_keys(&mut keymap, 4_000, &['i', 'h', 'i', ctrl_w]);
+    assert_eq!(out, [mode("insert"), Action::Key('h'), Action::Key('i'), command("delete-word")]);
+    assert_eq!(keymap.mode(), "insert");
+    // Escape is bound on its own in insert mode, so it doesn't wait for an Alt sequence.
+    let out = type_keys(&mut keymap, 4_100, &[keys::ESCAPE, 'h']);
+    assert_eq!(out, [mode("normal"), command("move-left")]);
+}
+
+#[test]
+fn other_events_settle_held_keys() {
+    let mut keymap = Keymap::from_config(CONFIG).unwrap();
+    // Other events settle anything held and pass through.
+    type_keys(&mut keymap, 5_000, &['g']);
+    let click = WebEvent::Click { x: 1, y: 2 };
+    assert_eq!(keymap.feed(ms(5_010), &click), [command("go-to-line"), Action::Other(click)]);
+    type_keys(&mut keymap, 5_100, &[keys::ESCAPE]);
+    assert_eq!(keymap.flush(), [Action::Key(keys::ESCAPE)]);
+}
+
+#[test]
+fn keymaps_built_in_code() {
+    // Keymaps can be built in code, too.
+    let mut keymap = Keymap::new("normal");
+    assert_eq!(keymap.bind("normal", "Ctrl+K Ctrl+C", "comment"), Ok(true));
+    assert_eq!(keymap.bind("normal", "ctrl+k  ctrl+c", "other"), Ok(false));
+    keymap.set_timeout(ms(50));
+    let (k, c) = (keys::ctrl('k').unwrap(), keys::ctrl('c').unwrap());
+    assert_eq!(type_keys(&mut keymap, 0, &[k, c]), [command("comment")]);
+    type_keys(&mut keymap, 100, &[k]);
+    assert_eq!(keymap.tick(ms(151)), [Action::Key(k)]);
+
+    // A timeout that never runs out leaves held keys waiting for the next key or a flush.
+    keymap.set_timeout(Duration::MAX);
+    assert_eq!(type_keys(&mut keymap, 200, &[k]), []);
+    assert_eq!(keymap.deadline(), None);
+    assert_eq!(keymap.tick(Duration::MAX), []);
+    assert_eq!(type_keys(&mut keymap, 300, &[c]), [command("comment")]);
+    type_keys(&mut keymap, 400, &[k]);
+    assert_eq!(keymap.flush(), [Action::Key(k)]);
+}
+
+#[test]
+fn parses_key_specs() {
+    // Key specs.
+    assert_eq!(parse_keys("Ctrl+Shift+a"), Ok(vec!['\u{1}']));
+    assert_eq!(parse_keys("Alt+Enter F5"), Ok(vec![keys::ESCAPE, keys::ENTER, keys::function(5).unwrap()]));
+    assert_eq!(parse_keys("Alt++"), Ok(vec![keys::ESCAPE, '+']));
+    assert_eq!(parse_keys("  "), Err(KeySpecError::Empty));
+    assert_eq!(parse_keys("Hyper+x"), Err(KeySpecError::UnknownKey("Hyper+x".to_owned())));
+    assert_eq!(parse_keys("Shift+Up"), Err(KeySpecError::InvalidModifier("Shift+Up".to_owned())));
+    assert_eq!(parse_keys("Ctrl+1"), Err(KeySpecError::InvalidModifier("Ctrl+1".to_owned())));
+}
+
+#[test]
+fn config_errors_say_which_line() {
+    // Config errors say which line is wrong.
+    let error = |line, kind| Err::<Keymap, _>(ConfigError { line, kind });
+    let check = |config: &str, expected: Result<Keymap, ConfigError>| {
+        assert_eq!(Keymap::from_config(config).map(|_| ()), expected.map(|_| ()), "{}", config);
+    };
+    check("h = left", error(1, ConfigErrorKind::BindingOutsideMode));
+    check("[normal]\n\nh left", error(3, ConfigErrorKind::Syntax));
+    check("[normal]\nh =", error(2, ConfigErrorKind::EmptyCommand));
+    check("[normal]\nh = a\nh = b", error(3, ConfigErrorKind::Duplicate("h".to_owned())));
+    check("[normal]\nSuper+h = a", error(2, ConfigErrorKind::Keys(KeySpecError::UnknownKey("Super+h".to_owned()))));
+    check("[normal]\ni = mode insret\n[insert]", error(2, ConfigErrorKind::UnknownMode("insret".to_owned())));
+    check("timeout = soon\n[normal]", error(1, ConfigErrorKind::InvalidTimeout("soon".to_owned())));
+    let message = Keymap::from_config("[normal]\nh = a\nh = b").unwrap_err().to_string();
+    assert_eq!(message, "line 3: \"h\" is already bound in this mode");
+}
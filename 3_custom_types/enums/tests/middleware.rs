@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use enums::middleware::{Pipeline, Stage};
+use enums::session::{Clock, ManualClock};
+use enums::{EventKind, WebEvent};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn key(c: char) -> WebEvent {
+    WebEvent::KeyPress(c)
+}
+
+fn click(x: i64) -> WebEvent {
+    WebEvent::Click { x, y: 0 }
+}
+
+fn paste(text: &str) -> WebEvent {
+    WebEvent::Paste(text.to_owned())
+}
+
+// Feeds `(millis, event)` pairs through a pipeline, ticking whenever it has something
+// due, and collects what comes out along with when it did.
+fn drive<S: Stage<In = WebEvent>>(pipeline: &mut Pipeline<S>, events: &[(u64, WebEvent)], end: u64) -> Vec<(u64, S::Out)> {
+    let mut out = Vec::new();
+    let tick_until = |pipeline: &mut Pipeline<S>, out: &mut Vec<(u64, S::Out)>, until: u64| {
+        while let Some(deadline) = pipeline.deadline().filter(|d| *d <= ms(until)) {
+            let at = deadline.as_millis() as u64;
+            out.extend(pipeline.tick(deadline).into_iter().map(|o| (at, o)));
+        }
+    };
+    for (at, event) in events {
+        tick_until(pipeline, &mut out, *at);
+        out.extend(pipeline.push(ms(*at), event.clone()).into_iter().map(|o| (*at, o)));
+    }
+    tick_until(pipeline, &mut out, end);
+    out
+}
+
+// A stage written outside the library: numbers the events it sees.
+struct Numbered(usize);
+
+impl Stage for Numbered {
+    type In = WebEvent;
+    type Out = (usize, WebEvent);
+
+    fn push(&mut self, _at: Duration, event: WebEvent, out: &mut Vec<(usize, WebEvent)>) {
+        self.0 += 1;
+        out.push((self.0, event));
+    }
+}
+
+#[test]
+fn debounce_lets_out_the_last_of_a_burst() {
+    // Debouncing: a burst of keys comes out as its last key, once things are quiet.
+    let mut pipeline = Pipeline::new().debounce(EventKind::KeyPress, ms(100));
+    let events = [(0, key('h')), (50, key('e')), (120, key('y')), (400, key('!')), (499, key('?'))];
+    assert_eq!(drive(&mut pipeline, &events, 1_000), [(220, key('y')), (599, key('?'))]);
+    // Other events go straight through, after letting out the key they interrupted.
+    let events = [(0, key('a')), (10, click(1)), (20, key('b'))];
+    assert_eq!(drive(&mut pipeline, &events, 1_000), [(10, key('a')), (10, click(1)), (120, key('b'))]);
+    // And the end of the input lets out whatever is still held.
+    pipeline.push(ms(2_000), key('z'));
+    assert_eq!(pipeline.flush(), [key('z')]);
+}
+
+#[test]
+fn throttle_lets_one_through_per_interval() {
+    // Throttling: one click per 250ms, counting from the last one let through.
+    let mut pipeline = Pipeline::new().throttle(EventKind::Click, ms(250));
+    let events = [(0, click(0)), (100, click(1)), (120, key('k')), (249, click(2)), (250, click(3)), (600, click(4))];
+    let out: Vec<WebEvent> = drive(&mut pipeline, &events, 1_000).into_iter().map(|(_, e)| e).collect();
+    assert_eq!(out, [click(0), key('k'), click(3), click(4)]);
+}
+
+#[test]
+fn coalesce_merges_close_pastes() {
+    // Coalescing: pastes less than 50ms apart become one.
+    let mut pipeline = Pipeline::new().coalesce_pastes(ms(50));
+    let events = [(0, paste("a")), (30, paste("b")), (79, paste("c")), (200, paste("d")), (300, paste("x")), (310, key('k'))];
+    let expected = [(129, paste("abc")), (250, paste("d")), (310, paste("x")), (310, key('k'))];
+    assert_eq!(drive(&mut pipeline, &events, 1_000), expected);
+    // Exactly `window` apart is too far.
+    let events = [(0, paste("a")), (50, paste("b"))];
+    assert_eq!(drive(&mut pipeline, &events, 1_000), [(50, paste("a")), (100, paste("b"))]);
+}
+
+#[test]
+fn batch_by_count_or_age() {
+    // Batching: by count, or when the first in the batch has waited long enough.
+    let mut pipeline = Pipeline::new().batch(3, ms(100));
+    let events = [(0, click(0)), (10, click(1)), (20, click(2)), (30, click(3)), (40, click(4))];
+    let expected = [(20, vec![click(0), click(1), click(2)]), (130, vec![click(3), click(4)])];
+    assert_eq!(drive(&mut pipeline, &events, 1_000), expected);
+    assert!(pipeline.flush().is_empty());
+}
+
+#[test]
+fn stages_compose() {
+    // Stages compose, and the types follow along: after `batch` the pipeline carries
+    // `Vec<WebEvent>`s, after `map` whatever the function returns.
+    let mut pipeline = Pipeline::new()
+        .filter(|event| *event != WebEvent::PageUnload)
+        .map(|event| match event {
+            WebEvent::Click { x, y } => WebEvent::Click { x: x * 2, y },
+            other => other,
+        })
+        .debounce(EventKind::KeyPress, ms(100))
+        .batch(10, ms(500))
+        .map(|batch| batch.len());
+    let events = [(0, click(1)), (10, key('a')), (20, key('b')), (30, WebEvent::PageUnload), (40, paste("p"))];
+    // The `b` is let out by the paste at 40, so the batch started at 0 is due at 500.
+    assert_eq!(drive(&mut pipeline, &events, 1_000), [(500, 3)]);
+}
+
+#[test]
+fn ticked_output_starts_the_next_window_late() {
+    // A debounced key that comes out on a tick starts the next stage's window then,
+    // not when the key was pressed.
+    let mut pipeline = Pipeline::new().debounce(EventKind::KeyPress, ms(100)).batch(5, ms(100));
+    assert_eq!(drive(&mut pipeline, &[(0, key('a'))], 1_000), [(200, vec![key('a')])]);
+}
+
+#[test]
+fn custom_stages_plug_in_with_then() {
+    // Custom stages plug in with `then`.
+    let mut pipeline = Pipeline::new().throttle(EventKind::Click, ms(100)).then(Numbered(0));
+    let events = [(0, click(0)), (50, click(1)), (60, key('k'))];
+    let out: Vec<(usize, WebEvent)> = drive(&mut pipeline, &events, 1_000).into_iter().map(|(_, o)| o).collect();
+    assert_eq!(out, [(1, click(0)), (2, key('k'))]);
+}
+
+#[test]
+fn runner_drives_a_pipeline_from_a_clock() {
+    // A `Runner` takes the time from a clock and hands results to a handler, the way a
+    // real event loop would use it.
+    let clock = ManualClock::new();
+    let mut received = Vec::new();
+    let mut runner = Pipeline::new()
+        .debounce(EventKind::KeyPress, ms(300))
+        .throttle(EventKind::Click, ms(1_000))
+        .run(&clock, |event| received.push((clock.now(), event)));
+    for c in "search".chars() {
+        runner.send(key(c));
+        clock.advance(ms(80));
+    }
+    // The click cuts the burst short, so the `h` comes out with it. The second click
+    // is throttled away.
+    runner.send(click(1));
+    runner.send(click(2));
+    clock.advance(ms(100));
+    runner.poll();
+    runner.send(key('!'));
+    assert_eq!(runner.deadline(), Some(ms(880)));
+    clock.advance(ms(299));
+    runner.poll();
+    clock.advance(ms(1));
+    runner.poll();
+    runner.send(key('?'));
+    let (_pipeline, _handler) = runner.finish();
+    let expected = [(ms(480), key('h')), (ms(480), click(1)), (ms(880), key('!')), (ms(880), key('?'))];
+    assert_eq!(received, expected);
+}
+
+#[test]
+fn later_stages_see_time_pass_when_earlier_ones_hold_back() {
+    // The key is held by the debounce, so nothing reaches `batch`, but the click batch
+    // was due at 50 and comes out with the push at 200 rather than waiting for a tick.
+    let mut pipeline = Pipeline::new().debounce(EventKind::KeyPress, ms(100)).batch(10, ms(50));
+    assert!(pipeline.push(ms(0), click(0)).is_empty());
+    assert_eq!(pipeline.push(ms(200), key('a')), [vec![click(0)]]);
+    assert_eq!(pipeline.flush(), [vec![key('a')]]);
+}
@@ -0,0 +1,29 @@
+use enums::{Associativity, Operations};
+
+#[test]
+fn symbols_and_names_round_trip() {
+    for op in Operations::all() {
+        assert_eq!(op.symbol().parse::<Operations>(), Ok(op));
+        assert_eq!(op.name().parse::<Operations>(), Ok(op));
+        assert_eq!(op.to_string().parse::<Operations>(), Ok(op));
+        assert_eq!(op.name().to_uppercase().parse::<Operations>(), Ok(op));
+    }
+    assert_eq!(Operations::all().count(), 4);
+}
+
+#[test]
+fn parses_aliases_and_rejects_the_rest() {
+    assert_eq!(" Plus ".parse(), Ok(Operations::Add));
+    assert_eq!("times".parse(), Ok(Operations::Multiply));
+    assert_eq!("div".parse(), Ok(Operations::Divide));
+    let err = "%".parse::<Operations>().unwrap_err();
+    assert_eq!(err.to_string(), "unknown operation \"%\"");
+}
+
+#[test]
+fn precedence_and_associativity() {
+    assert!(Operations::Multiply.precedence() > Operations::Add.precedence());
+    assert_eq!(Operations::Divide.precedence(), Operations::Multiply.precedence());
+    assert!(Operations::all().all(|op| op.associativity() == Associativity::Left));
+    assert!(Operations::Add.is_commutative() && !Operations::Subtract.is_commutative());
+}
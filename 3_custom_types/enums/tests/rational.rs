@@ -0,0 +1,114 @@
+use enums::{BigInt, Operations, Rational};
+
+fn r(s: &str) -> Rational {
+    s.parse().unwrap()
+}
+
+fn big(s: &str) -> Rational<BigInt> {
+    s.parse().unwrap()
+}
+
+// The same calculation as `rect_area` in structs, but exact.
+fn rect_area(top_left: (Rational, Rational), bottom_right: (Rational, Rational)) -> Rational {
+    let width = Operations::Subtract.run(bottom_right.0, top_left.0).abs();
+    let height = Operations::Subtract.run(top_left.1, bottom_right.1).abs();
+    Operations::Multiply.run(width, height)
+}
+
+#[test]
+fn rect_area_is_exact() {
+    let area = rect_area((r("10.5"), r("10.9")), (r("9.5"), r("9.7")));
+    assert_eq!(area, r("6/5"));
+    assert_eq!(area.to_decimal(2), "1.20");
+}
+
+#[test]
+fn kept_in_lowest_terms() {
+    assert_eq!(Rational::<i64>::new(6, -4), r("-3/2"));
+    assert_eq!(r("0.125"), Rational::new(1, 8));
+    assert_eq!(r("-.5").to_decimal(3), "-0.500");
+    assert_eq!(r("2/3").to_decimal(4), "0.6667");
+    assert_eq!(r("-1/3").to_decimal(0), "0");
+}
+
+#[test]
+fn ordering_does_not_overflow() {
+    let big = Rational::<i64>::new(i64::MAX, i64::MAX - 1);
+    let bigger = Rational::<i64>::new(i64::MAX - 1, i64::MAX - 2);
+    assert!(big < bigger);
+    assert!(r("-7/2") < r("-10/3"));
+}
+
+#[test]
+fn i64_reports_overflow() {
+    let huge = Rational::<i64>::from_integer(i64::MAX);
+    assert_eq!(Operations::Add.checked_run(huge.clone(), huge.clone()), None);
+    assert_eq!(Operations::Divide.checked_run(r("1"), r("0")), None);
+}
+
+#[test]
+fn bigint_harmonic_number() {
+    // 1 + 1/2 + ... + 1/100 overflows `i64`, but not `BigInt`.
+    let harmonic = (1..=100).fold(Rational::<BigInt>::zero(), |acc, n| Operations::Add.run(acc, Rational::new(BigInt::one(), BigInt::from(n))));
+    assert_eq!(harmonic.to_decimal(10), "5.1873775176");
+}
+
+#[test]
+fn from_f64_is_exact() {
+    assert_eq!(Rational::<i64>::from_f64(0.1), Some(r("3602879701896397/36028797018963968")));
+    assert_eq!(Rational::<i64>::from_f64(-2.75), Some(r("-11/4")));
+    assert_eq!(Rational::<i64>::from_f64(-0.0), Some(Rational::zero()));
+    assert_eq!(Rational::<i64>::from_f64(1024.0), Some(r("1024")));
+    assert_eq!(Rational::<i64>::from_f64(f64::NAN), None);
+    assert_eq!(Rational::<i64>::from_f64(f64::INFINITY), None);
+    // Too big or too small for an `i64` numerator or denominator.
+    assert_eq!(Rational::<i64>::from_f64(1e300), None);
+    assert_eq!(Rational::<i64>::from_f64(1e-300), None);
+    // The smallest subnormal is `1 / 2^1074`.
+    let tiny = Rational::<BigInt>::from_f64(f64::from_bits(1)).unwrap();
+    assert_eq!(*tiny.numer(), BigInt::one());
+    assert_eq!(*tiny.denom(), BigInt::from(2u32).pow(1074));
+}
+
+#[test]
+fn f64_round_trips() {
+    for x in [0.1, -2.75, 1.0 / 3.0, 1e300, -1e-300, f64::MAX, f64::MIN_POSITIVE, f64::from_bits(1), -f64::from_bits(0x000f_ffff_ffff_ffff)] {
+        assert_eq!(Rational::<BigInt>::from_f64(x).unwrap().to_f64(), x, "{:e}", x);
+    }
+    for x in [0.1, -2.75, 1.0 / 3.0, 123456.789] {
+        assert_eq!(Rational::<i64>::from_f64(x).unwrap().to_f64(), x, "{:e}", x);
+    }
+}
+
+#[test]
+fn to_f64_of_huge_bigints() {
+    let ten_400 = BigInt::from(10u32).pow(400);
+    // Both sides are far past `f64::MAX`, but the ratio isn't.
+    let near_one = Rational::new(&ten_400 + &BigInt::one(), ten_400.clone());
+    assert_eq!(near_one.to_f64(), 1.0);
+    let third = Rational::new(ten_400.clone(), &ten_400 * &BigInt::from(3u32));
+    assert_eq!(third.to_f64(), 1.0 / 3.0);
+    assert_eq!(Rational::new(&ten_400 * &BigInt::from(7u32) + BigInt::one(), &ten_400 * &BigInt::from(2u32)).to_f64(), 3.5);
+    assert_eq!(Rational::from_integer(ten_400.clone()).to_f64(), f64::INFINITY);
+    assert_eq!(Rational::new(-ten_400.clone(), BigInt::one()).to_f64(), f64::NEG_INFINITY);
+    assert_eq!(Rational::new(BigInt::one(), ten_400.clone()).to_f64(), 0.0);
+    assert_eq!(big("1/3").to_f64(), 1.0 / 3.0);
+}
+
+#[test]
+fn to_decimal_never_overflows() {
+    // 10^19 doesn't fit in an `i64`, and neither do the denominators times ten.
+    assert_eq!(Rational::<i64>::new(1, 3).to_decimal(19), "0.3333333333333333333");
+    assert_eq!(Rational::<i64>::new(2, 3).to_decimal(40), format!("0.{}7", "6".repeat(39)));
+    assert_eq!(Rational::<i64>::new(i64::MAX - 1, i64::MAX).to_decimal(20), "0.99999999999999999989");
+    assert_eq!(Rational::<i64>::new(i64::MIN, 1).to_decimal(2), "-9223372036854775808.00");
+    assert_eq!(Rational::<i64>::new(i64::MIN + 1, i64::MAX).to_decimal(3), "-1.000");
+    // Rounding carries all the way up, and half rounds away from zero.
+    assert_eq!(r("9.996").to_decimal(2), "10.00");
+    assert_eq!(r("-9.5").to_decimal(0), "-10");
+    assert_eq!(r("-0.0004").to_decimal(3), "0.000");
+    // Agrees with the `BigInt` version, which has all the room it needs.
+    for s in ["22/7", "-1/7", "123456789/1000", "1/1024"] {
+        assert_eq!(r(s).to_decimal(30), big(s).to_decimal(30), "{}", s);
+    }
+}
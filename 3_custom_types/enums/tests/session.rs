@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::time::Duration;
+
+use enums::session::{Clock, ManualClock, Pace, MAX_WAIT, Player, Recorded, Recorder, SessionReader, SystemClock};
+use enums::web_event::describe;
+use enums::WebEvent;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn script() -> Vec<(u64, WebEvent)> {
+    vec![
+        (0, WebEvent::PageLoad),
+        (250, WebEvent::KeyPress('h')),
+        (400, WebEvent::KeyPress('é')),
+        (1_000, WebEvent::Paste("\"quoted\"\n".to_owned())),
+        (0, WebEvent::Click { x: 20, y: 80 }),
+        (2_350, WebEvent::PageUnload),
+    ]
+}
+
+// Records `script` with a clock the test controls, so the timestamps are known exactly.
+fn recorded_session() -> Vec<Recorded> {
+    let clock = ManualClock::new();
+    let mut recorder = Recorder::new(Vec::new(), &clock);
+    for (gap, event) in &script() {
+        clock.advance(ms(*gap));
+        recorder.record(event).unwrap();
+    }
+    let log = recorder.finish().unwrap();
+    SessionReader::new(&log[..]).map(Result::unwrap).collect()
+}
+
+#[test]
+fn records_to_a_file_and_reads_it_back() {
+    let clock = ManualClock::new();
+    clock.advance(ms(5_000)); // time before the recorder starts doesn't count
+    let path = std::env::temp_dir().join(format!("enums-session-{}.jsonl", std::process::id()));
+    let mut recorder = Recorder::new(BufWriter::new(File::create(&path).unwrap()), &clock);
+    for (gap, event) in &script() {
+        clock.advance(ms(*gap));
+        recorder.record(event).unwrap();
+    }
+    assert_eq!(recorder.len(), 6);
+    recorder.finish().unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().nth(1), Some(r#"{"t_us":250000,"type":"key_press","key":"h"}"#));
+    let session: Vec<Recorded> = SessionReader::new(BufReader::new(File::open(&path).unwrap())).map(Result::unwrap).collect();
+    std::fs::remove_file(&path).unwrap();
+
+    let times: Vec<u64> = session.iter().map(|r| r.at.as_millis() as u64).collect();
+    assert_eq!(times, [0, 250, 650, 1_650, 1_650, 4_000]);
+    let events: Vec<WebEvent> = session.iter().map(|r| r.event.clone()).collect();
+    assert_eq!(events, script().into_iter().map(|(_, e)| e).collect::<Vec<_>>());
+}
+
+#[test]
+fn as_fast_as_possible_takes_no_time() {
+    let replay_clock = ManualClock::new();
+    let mut seen = Vec::new();
+    let count = Player::new(&replay_clock, Pace::AsFastAsPossible).play(recorded_session(), |e| seen.push(describe(e)));
+    assert_eq!(count, 6);
+    assert_eq!(seen[0], "page loaded");
+    assert_eq!(seen[3], "pasted \"\"quoted\"\n\".");
+    assert_eq!(replay_clock.now(), Duration::ZERO);
+}
+
+#[test]
+fn timed_playback_keeps_the_gaps() {
+    // At double speed every event arrives at half its recorded time, measured from
+    // when playback started.
+    let replay_clock = ManualClock::new();
+    replay_clock.advance(ms(100));
+    let mut arrivals = Vec::new();
+    Player::new(&replay_clock, Pace::Timed { speed: 2.0 }).play(recorded_session(), |_| {
+        arrivals.push(replay_clock.now().as_millis() as u64 - 100);
+    });
+    assert_eq!(arrivals, [0, 125, 325, 825, 825, 2_000]);
+
+    // A handler slower than the gaps doesn't make later events late on top of that.
+    let replay_clock = ManualClock::new();
+    let mut arrivals = Vec::new();
+    Player::new(&replay_clock, Pace::Timed { speed: 1.0 }).play(recorded_session(), |_| {
+        arrivals.push(replay_clock.now().as_millis() as u64);
+        replay_clock.advance(ms(300));
+    });
+    assert_eq!(arrivals, [0, 300, 650, 1_650, 1_950, 4_000]);
+}
+
+#[test]
+fn bad_speeds_are_rejected() {
+    let clock = ManualClock::new();
+    for speed in [0.0, -0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(Player::checked_new(&clock, Pace::Timed { speed }).is_none(), "{}", speed);
+    }
+    assert!(Player::checked_new(&clock, Pace::Timed { speed: 1e-300 }).is_some());
+    assert!(Player::checked_new(&clock, Pace::AsFastAsPossible).is_some());
+    assert!(std::panic::catch_unwind(|| Player::new(ManualClock::new(), Pace::Timed { speed: f64::NAN })).is_err());
+}
+
+#[test]
+fn waits_are_capped() {
+    let clock = ManualClock::new();
+    clock.advance(ms(1));
+    let session = vec![
+        Recorded { at: Duration::ZERO, event: WebEvent::PageLoad },
+        Recorded { at: Duration::from_secs(1), event: WebEvent::PageUnload },
+    ];
+    let mut arrivals = Vec::new();
+    let count = Player::new(&clock, Pace::Timed { speed: 1e-300 }).play(session, |_| arrivals.push(clock.now()));
+    assert_eq!(count, 2);
+    assert_eq!(arrivals, [ms(1), ms(1) + MAX_WAIT]);
+
+    // A gap that a `Duration` holds fine but is still far too long. Later events keep
+    // their place relative to the start, so they catch up a wait at a time.
+    let clock = ManualClock::new();
+    let day = Duration::from_secs(24 * 60 * 60);
+    let session = vec![
+        Recorded { at: Duration::ZERO, event: WebEvent::PageLoad },
+        Recorded { at: day * 365, event: WebEvent::PageUnload },
+        Recorded { at: day * 365 + ms(1), event: WebEvent::PageLoad },
+    ];
+    let mut arrivals = Vec::new();
+    Player::new(&clock, Pace::Timed { speed: 1.0 }).play(session, |_| arrivals.push(clock.now()));
+    assert_eq!(arrivals, [Duration::ZERO, MAX_WAIT, MAX_WAIT * 2]);
+
+    // Near the top of the range with an ordinary speed, the deadline saturates.
+    let clock = ManualClock::new();
+    clock.advance(Duration::MAX - ms(1));
+    let session = vec![Recorded { at: ms(10), event: WebEvent::PageLoad }];
+    Player::new(&clock, Pace::Timed { speed: 1.0 }).play(session, |_| {});
+    assert_eq!(clock.now(), Duration::MAX);
+}
+
+#[test]
+fn real_clock() {
+    let mut recorder = Recorder::new(Vec::new(), SystemClock::new());
+    for (_, event) in &script() {
+        recorder.record(event).unwrap();
+    }
+    let log = recorder.finish().unwrap();
+    let replayed: Vec<Recorded> = SessionReader::new(&log[..]).map(Result::unwrap).collect();
+    assert!(replayed.windows(2).all(|w| w[0].at <= w[1].at));
+
+    // At a speed where the whole thing takes about 4 ms.
+    let clock = SystemClock::new();
+    assert_eq!(Player::new(&clock, Pace::Timed { speed: 1_000.0 }).play(recorded_session(), |_| {}), 6);
+    assert!(clock.now() >= ms(4));
+}
@@ -0,0 +1,76 @@
+// Runs the `session-report` binary itself, feeding it a log on stdin.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn session_report(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_session-report"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+const LOG: &str = concat!(
+    r#"{"t_us":0,"type":"page_load"}"#,
+    "\n",
+    r#"{"t_us":500000,"type":"click","x":3,"y":4}"#,
+    "\n",
+    r#"{"t_us":2500000,"type":"page_unload"}"#,
+    "\n",
+);
+
+#[test]
+fn a_readable_log_exits_0() {
+    let output = session_report(&["--size", "4x2"], LOG);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("events: 3\n"), "{}", stdout);
+    assert!(stdout.contains("pages: 1 visit(s), 2.5s in total, 2.5s on average\n"), "{}", stdout);
+    assert!(stdout.ends_with("+----+\n|@   |\n|    |\n+----+\n"), "{}", stdout);
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn unreadable_lines_are_skipped_and_exit_1() {
+    let output = session_report(&["-"], &format!("not json\n{}", LOG));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("events: 3\n"));
+    assert!(stderr(&output).starts_with("session-report: line 1: "), "{}", stderr(&output));
+}
+
+#[test]
+fn help_goes_to_stdout_and_exits_0() {
+    for flag in ["--help", "-h"] {
+        let output = session_report(&[flag], "");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).starts_with("usage: session-report"));
+        assert_eq!(stderr(&output), "");
+    }
+}
+
+#[test]
+fn bad_arguments_and_missing_files_exit_2() {
+    for args in [&["--size", "0x2"][..], &["--size"], &["--size", "100000x100000"], &["--size", "99999999999x99999999999"], &["a", "b"]] {
+        let output = session_report(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert_eq!(stdout(&output), "");
+    }
+    let output = session_report(&["--size", "100000x100000"], "");
+    assert_eq!(stderr(&output), "session-report: --size 100000x100000 is too big, the most is 1048576 cells\n");
+    let output = session_report(&["/nonexistent/session-report-input"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("session-report: /nonexistent/session-report-input: "));
+}
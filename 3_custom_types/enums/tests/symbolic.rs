@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use enums::calc;
+use enums::symbolic::Sym;
+
+fn x() -> Sym {
+    Sym::var("x")
+}
+
+fn y() -> Sym {
+    Sym::var("y")
+}
+
+fn n(value: i64) -> Sym {
+    Sym::int(value)
+}
+
+#[test]
+fn identities_and_constant_folding() {
+    assert_eq!((x() + n(0)).simplify(), x());
+    assert_eq!((n(1) * x()).simplify(), x());
+    assert_eq!(((n(2) + n(3)) * x()).simplify().to_string(), "5 * x");
+    assert_eq!((x() / n(2)).simplify().to_string(), "(1/2) * x");
+    assert_eq!((n(0) - x()).simplify().to_string(), "-x");
+}
+
+#[test]
+fn collects_like_terms_in_any_order() {
+    let f = x() * n(2) + y() + (n(3) * x() - y());
+    assert_eq!(f.simplify().to_string(), "5 * x");
+}
+
+#[test]
+fn derivatives() {
+    let f = x() * x() + n(3) * x() - n(7);
+    let df = f.derivative("x");
+    assert_eq!(df.to_string(), "2 * x + 3");
+    assert_eq!(f.eval_at("x", 2.0), Ok(3.0));
+    assert_eq!(df.eval_at("x", 2.0), Ok(7.0));
+
+    // Quotient rule: d/dx 1 / x = -1 / (x * x)
+    assert_eq!((n(1) / x()).derivative("x").eval_at("x", 2.0), Ok(-0.25));
+
+    // Partial derivatives treat other variables as constants.
+    let h = x() * y() * (x() - y());
+    assert_eq!(h.derivative("y").to_string(), "x * x - 2 * x * y");
+    assert!(h.eval_at("z", 1.0).is_err());
+}
+
+#[test]
+fn prints_only_the_parentheses_that_matter() {
+    assert_eq!((x() - (y() - n(1))).to_string(), "x - (y - 1)");
+    assert_eq!((x() - y() - n(1)).to_string(), "x - y - 1");
+    assert_eq!(((x() + y()) * x()).to_string(), "(x + y) * x");
+}
+
+#[test]
+fn negated_right_operands_keep_their_parentheses() {
+    let minus_x = || n(-1) * x();
+    assert_eq!(minus_x().to_string(), "-x");
+    assert_eq!((y() - minus_x()).to_string(), "y - (-x)");
+    assert_eq!((y() * minus_x()).to_string(), "y * (-x)");
+    assert_eq!((minus_x() - y()).to_string(), "-x - y");
+    assert_eq!((n(-1) * minus_x()).to_string(), "-(-x)");
+
+    // Printing with the variables filled in, then evaluating the text, gives the same
+    // value as evaluating the tree.
+    let vars = HashMap::from([("x".to_owned(), 5.0), ("y".to_owned(), 7.0)]);
+    for e in [y() - minus_x(), y() * minus_x(), minus_x() - y(), n(-1) * minus_x(), y() / minus_x()] {
+        let text = e.to_string().replace('x', "5").replace('y', "7");
+        assert_eq!(calc::eval::<f64>(&text), Ok(e.eval(&vars).unwrap()), "{}", text);
+    }
+}
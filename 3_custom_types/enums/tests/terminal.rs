@@ -0,0 +1,115 @@
+use enums::terminal::{decode_all, TerminalDecoder};
+use enums::web_event::keys;
+use enums::WebEvent;
+
+fn keys_of(s: &str) -> Vec<WebEvent> {
+    s.chars().map(WebEvent::KeyPress).collect()
+}
+
+fn paste(s: &str) -> WebEvent {
+    WebEvent::Paste(s.to_owned())
+}
+
+#[test]
+fn fixtures_decode_however_the_input_is_split() {
+    // (what the terminal sent, what it should decode to)
+    let fixtures: Vec<(&[u8], Vec<WebEvent>)> = vec![
+        (b"hi", keys_of("hi")),
+        ("héllo 🦀".as_bytes(), keys_of("héllo 🦀")),
+        (b"a\rb\x7f\x08\t", keys_of("a\nb\u{8}\u{8}\t")),
+        (b"\x03", vec![WebEvent::KeyPress(keys::ctrl('c').unwrap())]),
+        // Arrows in normal and application mode, with and without modifiers.
+        (b"\x1b[A\x1b[B\x1bOC\x1bOD\x1b[1;5A", keys_of("\u{f700}\u{f701}\u{f703}\u{f702}\u{f700}")),
+        (b"\x1b[H\x1b[4~\x1b[3~\x1b[5;2~", vec![
+            WebEvent::KeyPress(keys::HOME),
+            WebEvent::KeyPress(keys::END),
+            WebEvent::KeyPress(keys::DELETE),
+            WebEvent::KeyPress(keys::PAGE_UP),
+        ]),
+        (b"\x1bOP\x1b[15~\x1b[21~\x1b[24~", [1, 5, 10, 12].map(|n| WebEvent::KeyPress(keys::function(n).unwrap())).to_vec()),
+        // Unknown sequences (focus in, a cursor position report) vanish without a trace.
+        (b"x\x1b[I\x1b[12;40Ry", keys_of("xy")),
+        // Alt+x, and a lone Escape at the end.
+        (b"\x1bx\x1b", keys_of("\u{1b}x\u{1b}")),
+        // Bracketed paste: escapes and newlines inside are text, not keys.
+        (b"\x1b[200~line one\r\nline \x1b[A two\x1b[201~!", vec![paste("line one\nline \u{1b}[A two"), WebEvent::KeyPress('!')]),
+        (b"\x1b[200~\x1b[201~", vec![paste("")]),
+        ("\x1b[200~日本語\x1b[201~".as_bytes(), vec![paste("日本語")]),
+        // Mouse: left press becomes a 0-based click. Releases, right clicks, drags and
+        // the wheel are ignored. Ctrl+click is still a click.
+        (b"\x1b[<0;21;81M\x1b[<0;21;81m", vec![WebEvent::Click { x: 20, y: 80 }]),
+        (b"\x1b[<2;5;5M\x1b[<32;6;5M\x1b[<64;5;5M\x1b[<16;1;1M", vec![WebEvent::Click { x: 0, y: 0 }]),
+        (b"\x1b[<0;0;5M\x1b[<0;5M", vec![]),
+        // Invalid UTF-8 is replaced, one replacement per bad byte.
+        (b"a\xffb\xc3(", keys_of("a\u{fffd}b\u{fffd}(")),
+        (b"\xe2\x82", keys_of("\u{fffd}\u{fffd}")),
+    ];
+
+    for (bytes, expected) in &fixtures {
+        // All at once.
+        assert_eq!(&decode_all(bytes), expected, "{:?}", String::from_utf8_lossy(bytes));
+
+        // Split into two reads at every position.
+        for split in 0..=bytes.len() {
+            let mut decoder = TerminalDecoder::new();
+            let mut events = decoder.feed(&bytes[..split]);
+            events.extend(decoder.feed(&bytes[split..]));
+            events.extend(decoder.flush());
+            assert_eq!(&events, expected, "{:?} split at {}", String::from_utf8_lossy(bytes), split);
+        }
+
+        // One byte per read.
+        let mut decoder = TerminalDecoder::new();
+        let mut events: Vec<WebEvent> = bytes.iter().flat_map(|b| decoder.feed(&[*b])).collect();
+        events.extend(decoder.flush());
+        assert_eq!(&events, expected, "{:?} byte by byte", String::from_utf8_lossy(bytes));
+    }
+}
+
+#[test]
+fn incomplete_sequences_wait_for_more_input() {
+    // Nothing is guessed before it has to be: a sequence cut short waits for more...
+    let mut decoder = TerminalDecoder::new();
+    assert_eq!(decoder.feed(b"a\x1b[1;"), keys_of("a"));
+    assert_eq!(decoder.feed(b"5C"), keys_of("\u{f703}"));
+    assert_eq!(decoder.feed(b"\x1b"), vec![]);
+    // ...until a `flush` says no more is coming, and then it's the keys as typed.
+    assert_eq!(decoder.flush(), keys_of("\u{1b}"));
+    assert_eq!(decoder.feed(b"\x1b["), vec![]);
+    assert_eq!(decoder.flush(), keys_of("\u{1b}["));
+
+    // A paste stays open across reads and flushes until its end marker arrives.
+    assert_eq!(decoder.feed(b"\x1b[200~part one, "), vec![]);
+    assert!(decoder.in_paste());
+    assert_eq!(decoder.flush(), vec![]);
+    assert_eq!(decoder.feed(b"part two\x1b[20"), vec![]);
+    assert_eq!(decoder.feed(b"1~"), vec![paste("part one, part two")]);
+    assert!(!decoder.in_paste());
+}
+
+#[test]
+fn runaway_sequences_are_dropped() {
+    // A runaway sequence is dropped, all the way to its final byte, instead of
+    // holding everything up.
+    let mut runaway = b"\x1b[".to_vec();
+    runaway.extend([b'1'; 100]);
+    let mut decoder = TerminalDecoder::new();
+    assert_eq!(decoder.feed(&runaway), vec![]);
+    assert_eq!(decoder.feed(b"1;2mok"), keys_of("ok"));
+
+    // However it's split up, none of it leaks out as key presses.
+    runaway.extend(b"1;2mok\x1b[A");
+    let expected = [keys_of("ok"), vec![WebEvent::KeyPress(keys::UP)]].concat();
+    assert_eq!(decode_all(&runaway), expected);
+    for size in [1, 7, 64, 65] {
+        let mut decoder = TerminalDecoder::new();
+        let mut events: Vec<WebEvent> = runaway.chunks(size).flat_map(|chunk| decoder.feed(chunk)).collect();
+        events.extend(decoder.flush());
+        assert_eq!(events, expected, "fed {} bytes at a time", size);
+    }
+
+    // A byte that can't be in a sequence at all ends it too, and isn't dropped.
+    let mut decoder = TerminalDecoder::new();
+    assert_eq!(decoder.feed(&[b"\x1b[".as_slice(), &[b'1'; 100]].concat()), vec![]);
+    assert_eq!(decoder.feed(b"22\rok"), [vec![WebEvent::KeyPress(keys::ENTER)], keys_of("ok")].concat());
+}
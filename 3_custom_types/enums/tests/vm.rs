@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use enums::expr::{EvalError, Expr};
+use enums::vm::{self, CompileError, Instr, Vm};
+use enums::Operations;
+
+// `if n then total / n else 0`
+fn average() -> Expr {
+    Expr::if_else(Expr::var("n"), Expr::binary(Operations::Divide, Expr::var("total"), Expr::var("n")), Expr::Const(0))
+}
+
+// `v0 + v1 + ... ` over `count` distinct variables, as a balanced tree so that neither
+// compiling nor dropping it recurses deeply.
+fn sum_of_variables(count: usize) -> Expr {
+    let mut level: Vec<Expr> = (0..count).map(|i| Expr::var(&format!("v{}", i))).collect();
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity(level.len().div_ceil(2));
+        let mut items = level.into_iter();
+        while let Some(lhs) = items.next() {
+            next.push(match items.next() {
+                Some(rhs) => Expr::binary(Operations::Add, lhs, rhs),
+                None => lhs,
+            });
+        }
+        level = next;
+    }
+    level.pop().unwrap()
+}
+
+#[test]
+fn compiles_to_the_expected_bytecode() {
+    let chunk = vm::compile(&average()).unwrap();
+    assert_eq!(chunk.vars(), ["n", "total"]);
+    assert_eq!(
+        chunk.code(),
+        [
+            Instr::LoadVar(0),
+            Instr::JumpIfZero(6),
+            Instr::LoadVar(1),
+            Instr::LoadVar(0),
+            Instr::Apply(Operations::Divide),
+            Instr::Jump(7),
+            Instr::PushConst(0),
+        ]
+    );
+    assert!(chunk.disassemble().starts_with("0000  LOAD_VAR      0 (n)\n0001  JUMP_IF_ZERO  0006\n"));
+}
+
+#[test]
+fn vm_agrees_with_the_tree_walker() {
+    let expr = average();
+    let chunk = vm::compile(&expr).unwrap();
+    let mut vm = Vm::new();
+    for (total, n) in [(42, 4), (42, 0), (-7, 2), (i32::MIN, -1)] {
+        let env = HashMap::from([("total".to_owned(), total), ("n".to_owned(), n)]);
+        let slots = chunk.bind(&env).unwrap();
+        assert_eq!(vm.run(&chunk, &slots), expr.eval(&env), "{} / {}", total, n);
+    }
+    assert_eq!(vm.run(&chunk, &[1, i32::MIN]), Ok(i32::MIN));
+    assert_eq!(vm.run(&chunk, &[-1, i32::MIN]), Err(EvalError::Arithmetic(Operations::Divide)));
+}
+
+#[test]
+fn missing_variables_are_reported() {
+    let chunk = vm::compile(&average()).unwrap();
+    let env = HashMap::from([("n".to_owned(), 1)]);
+    assert_eq!(chunk.bind(&env), Err(EvalError::UnboundVariable("total".to_owned())));
+    assert_eq!(Vm::new().run(&chunk, &[1]), Err(EvalError::UnboundVariable("total".to_owned())));
+}
+
+#[test]
+fn every_slot_a_u16_can_address_is_usable() {
+    let count = u16::MAX as usize + 1;
+    let chunk = vm::compile(&sum_of_variables(count)).unwrap();
+    assert_eq!(chunk.vars().len(), count);
+    assert!(chunk.code().contains(&Instr::LoadVar(u16::MAX)));
+    // Only the last slot is non-zero, so a truncated index would give 0.
+    let mut slots = vec![0; count];
+    slots[count - 1] = 7;
+    assert_eq!(Vm::new().run(&chunk, &slots), Ok(7));
+}
+
+#[test]
+fn too_many_variables_is_an_error() {
+    assert_eq!(vm::compile(&sum_of_variables(u16::MAX as usize + 2)), Err(CompileError::TooManyVariables));
+    assert_eq!(CompileError::TooManyVariables.to_string(), "more than 65536 distinct variables");
+}
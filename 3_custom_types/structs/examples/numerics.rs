@@ -0,0 +1,64 @@
+// Approximate float comparisons, and the exact predicates checked against integer
+// arithmetic on points that are as close to collinear (or cocircular) as floats get.
+// Run with `cargo run --example numerics`.
+
+use enums::fuzz::Rng;
+use structs::numerics::{in_circle, on_segment, orientation, segments_intersect};
+use structs::{ApproxEq, Orientation, Point, Polygon, Shape, Triangle};
+
+// What you get without the predicates.
+fn naive_orientation(a: Point<f64>, b: Point<f64>, c: Point<f64>) -> Orientation {
+    let det = (b - a).cross(c - a);
+    if det > 0.0 {
+        Orientation::Counterclockwise
+    } else if det < 0.0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+fn main() {
+    // ULPs: how many representable values apart two floats are.
+    println!("0.1 + 0.2 is {:?} ULP from 0.3", (0.1_f64 + 0.2).ulps(0.3));
+    let tenths: f32 = (0..10).map(|_| 0.1_f32).sum();
+    println!("ten tenths make {} ({:?} ULPs from 1)", tenths, tenths.ulps(1.0));
+    // Near zero ULPs are useless, and a (relative) epsilon works better.
+    println!("1e-20 is {:?} ULPs from 0, within 1e-12: {}", 1e-20_f64.ulps(0.0), 1e-20_f64.approx_eq_eps(0.0, 1e-12));
+    let p = Point::new(0.1_f64, 0.7) * 3.0;
+    println!("{:?} is (0.3, 2.1) give or take a ULP: {}", p, p.approx_eq_ulps(Point::new(0.3, 2.1), 1));
+
+    // Shewchuk's example: points a few ULPs apart near (0.5, 0.5), against a line
+    // through (12, 12) and (24, 24). Plain `f64` gets a good share of these wrong.
+    let tiny = 2f64.powi(-53);
+    let (b, c) = (Point::new(12.0, 12.0), Point::new(24.0, 24.0));
+    let mut naive_mistakes = 0;
+    for i in 0..64 {
+        for j in 0..64 {
+            let a = Point::new(0.5 + i as f64 * tiny, 0.5 + j as f64 * tiny);
+            if naive_orientation(a, b, c) != orientation(a, b, c) {
+                naive_mistakes += 1;
+            }
+        }
+    }
+    println!("the naive orientation test gets {} of 4096 wrong", naive_mistakes);
+
+    // Segments that only just miss each other, and a square's corners on one circle.
+    let (a, b) = (Point::new(0.0, 0.0), Point::new(3e15, 1e15));
+    let above = Point::new(3.0, 1.0_f64.next_up());
+    println!("on the segment: {}, crosses it: {}", on_segment(above, a, b), segments_intersect(a, b, above, Point::new(3.0, 5.0)));
+    let square = [Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(1.0, 1.0), Point::new(0.0, 1.0)];
+    println!("fourth corner: {:?}", in_circle(square[0], square[1], square[2], square[3]));
+    println!("just past it: {:?}", in_circle(square[0], square[1], square[2], Point::new(1.0, 1.0_f64.next_up())));
+
+    // A random point along a kite's diagonal (give or take rounding), and which of its
+    // two halves has it.
+    let mut rng = Rng::new(11);
+    let (start, end) = (Point::new(0.1_f32, 0.1), Point::new(7.3, 2.9));
+    let across = Point::new(start.y - end.y, end.x - start.x) * 0.25;
+    let middle = start.lerp(end, 0.5);
+    let kite = Polygon::new(vec![start, middle - across, end, middle + across]);
+    let first = Triangle::new(start, middle - across, end);
+    let q = start.lerp(end, rng.below(1_001) as f32 / 1_000.0);
+    println!("{:?} is in the kite: {}, in its first half: {}, on the diagonal: {}", q, kite.contains(q), first.contains(q), on_segment(q, start, end));
+}
@@ -0,0 +1,23 @@
+// Points as vectors, with integer and float coordinates, and rectangles over both.
+// Run with `cargo run --example point`.
+
+use structs::{Point, Rectangle};
+
+fn main() {
+    // Integer points: the arithmetic is exact, and division rounds towards zero.
+    let a: Point<i32> = Point::new(3, 4);
+    let b = Point::new(-1, 2);
+    println!("{:?} + {:?} = {:?}, times 3 is {:?}", a, b, a + b, a * 3);
+    println!("dot {}, cross {}, squared length {}", a.dot(b), a.cross(b), a.length_squared());
+    println!("(7, -7) / 2 = {:?}", Point::new(7, -7) / 2);
+
+    // Float points have lengths and directions.
+    let p = Point::new(3.0_f32, 4.0);
+    println!("{:?} has length {} and points along {:?}", p, p.length(), p.normalize());
+    println!("a quarter of the way to (10, 0): {:?}", p.lerp(Point::new(10.0, 0.0), 0.25));
+
+    // Rectangles work with any coordinate type too.
+    let grid: Rectangle<i32> = Rectangle::from_corners(Point::new(8, 0), Point::new(0, 6));
+    let other = Rectangle::from_corners(Point::new(5, 3), Point::new(12, 10));
+    println!("{:?} has area {} and overlaps {:?} in {:?}", grid, grid.area(), other, grid.intersection(&other));
+}
@@ -0,0 +1,28 @@
+// Rectangle geometry, including the awkward cases: corners given in the wrong order,
+// rectangles with no area and rectangles that only touch.
+// Run with `cargo run --example rectangle`.
+
+use structs::{Point, Rectangle};
+
+fn rect(left: f32, top: f32, right: f32, bottom: f32) -> Rectangle {
+    Rectangle { top_left: Point::new(left, top), bottom_right: Point::new(right, bottom) }
+}
+
+fn main() {
+    // Any two opposite corners give the same rectangle. Down is a smaller `y`.
+    let backwards = rect(6.0, 0.0, 0.0, 4.0);
+    let r = backwards.normalize();
+    println!("{:?} normalizes to {:?}", backwards, r);
+    println!("{} by {}, area {}, centred on {:?}", r.width(), r.height(), r.area(), r.center());
+    println!("contains (0, 0): {}, contains (6.1, 2): {}", r.contains(Point::new(0.0, 0.0)), r.contains(Point::new(6.1, 2.0)));
+
+    // Overlapping, apart, and touching along an edge.
+    for other in [rect(4.0, 6.0, 10.0, 2.0), rect(7.0, 4.0, 9.0, 0.0), rect(6.0, 4.0, 8.0, 0.0)] {
+        println!("with {:?}: intersection {:?}, union {:?}", other, r.intersection(&other), r.union(&other));
+    }
+
+    // Inflating and deflating move each edge by the amount given, and deflating by too
+    // much leaves a line through the middle.
+    println!("inflated: {:?}", r.inflate(1.0, 2.0));
+    println!("deflated too far: {:?}", r.deflate(4.0, 1.0));
+}
@@ -0,0 +1,47 @@
+// The `Shape` trait over rectangles, circles, triangles and polygons, mixed together
+// and one at a time, including shapes with no area and points right on the outline.
+// Run with `cargo run --example shapes`.
+
+use std::f32::consts::PI;
+
+use structs::{Circle, Point, Polygon, Rectangle, Shape, Triangle};
+
+fn p(x: f32, y: f32) -> Point {
+    Point::new(x, y)
+}
+
+fn rect(left: f32, top: f32, right: f32, bottom: f32) -> Rectangle {
+    Rectangle { top_left: p(left, top), bottom_right: p(right, bottom) }
+}
+
+fn main() {
+    //   +--+
+    //   |  |
+    //   |  +--+
+    //   |     |
+    //   +-----+
+    let l = Polygon::new(vec![p(0.0, 0.0), p(4.0, 0.0), p(4.0, 2.0), p(2.0, 2.0), p(2.0, 4.0), p(0.0, 4.0)]);
+    let round = Polygon::new((0..1_000).map(|i| {
+        let angle = i as f32 / 1_000.0 * 2.0 * PI;
+        p(1.0 + 3.0 * angle.cos(), 2.0 + 3.0 * angle.sin())
+    }).collect());
+    let shapes: Vec<(&str, Box<dyn Shape>)> = vec![
+        ("rectangle", Box::new(rect(0.0, 4.0, 6.0, 0.0))),
+        ("circle", Box::new(Circle::new(p(1.0, 2.0), 3.0))),
+        ("triangle", Box::new(Triangle::new(p(0.0, 0.0), p(4.0, 0.0), p(0.0, 3.0)))),
+        ("L shape", Box::new(l)),
+        // Close to the circle's measurements.
+        ("1000-gon", Box::new(round)),
+    ];
+    for (name, shape) in &shapes {
+        println!("{}: area {}, perimeter {}, centroid {:?}", name, shape.area(), shape.perimeter(), shape.centroid());
+    }
+
+    // All of them together.
+    let everything = shapes.iter().map(|(_, shape)| shape.bounding_box()).reduce(|a, b| a.union(&b)).unwrap();
+    println!("everything fits in {:?}", everything);
+    for point in [p(1.0, 0.0), p(3.0, 3.0)] {
+        let hit: Vec<&str> = shapes.iter().filter(|(_, shape)| shape.contains(point)).map(|(name, _)| *name).collect();
+        println!("{:?} is in {:?}", point, hit);
+    }
+}
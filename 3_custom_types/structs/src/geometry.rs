@@ -0,0 +1,315 @@
+// `Point` and `Rectangle` from the tutorial in `main.rs`, with the operations you end
+// up needing as soon as you draw anything: containment, overlap, bounding boxes.
+//
+// The axes are the ones `square` uses: moving down means a smaller `y`, so a
+// rectangle's top edge has the larger `y` and its bottom edge the smaller one.
+//
+// Rectangles are closed, so their edges belong to them. A point on the edge is
+// inside, and two rectangles that only touch along an edge do intersect, in a
+// rectangle with no area. Zero-width and zero-height rectangles are fine everywhere.
+//
+// Both are generic over the coordinate type, which defaults to `f32` as in the
+// tutorial. Anything `Coordinate` works, integers included, and the operations that
+// need square roots (lengths, normalizing) are there for `f32` and `f64`.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+// What points and rectangles need from their coordinates. `min` and `max` are here
+// because floats only have `PartialOrd`, and their own `min` and `max` skip NaN.
+pub trait Coordinate:
+    Copy + PartialOrd + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+}
+
+// Coordinates with square roots, for lengths and everything built on them.
+pub trait Float: Coordinate + Neg<Output = Self> {
+    fn sqrt(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_coordinate_for_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                fn zero() -> $t { 0 }
+                fn one() -> $t { 1 }
+                fn min(self, other: $t) -> $t { Ord::min(self, other) }
+                fn max(self, other: $t) -> $t { Ord::max(self, other) }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_coordinate_for_float {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                fn zero() -> $t { 0.0 }
+                fn one() -> $t { 1.0 }
+                fn min(self, other: $t) -> $t { <$t>::min(self, other) }
+                fn max(self, other: $t) -> $t { <$t>::max(self, other) }
+            }
+
+            impl Float for $t {
+                fn sqrt(self) -> $t { <$t>::sqrt(self) }
+                fn hypot(self, other: $t) -> $t { <$t>::hypot(self, other) }
+                fn is_finite(self) -> bool { <$t>::is_finite(self) }
+            }
+        )*
+    };
+}
+
+// Only signed integers: with unsigned ones, half of what `Sub` gives back (every
+// vector pointing left or down) would overflow.
+impl_coordinate_for_integer!(i8, i16, i32, i64, i128, isize);
+impl_coordinate_for_float!(f32, f64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point<T = f32> { // A struct with two fields
+    pub x: T, // both being the same type, floats unless you say otherwise
+    pub y: T,
+}
+
+// Structs can be reused as fields of another struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle<T = f32> {
+    // A rectangle can be specified by where the top left and bottom right
+    // corners are in space.
+    pub top_left: Point<T>, // both properties with Point type values. When you create a struct, you are basically making a new data type.
+    pub bottom_right: Point<T>, // the Point data type needing 2 values, the x property and the y property, both of type T.
+}
+
+impl<T> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    // Changes the coordinate type, as in `point.map(|c| c as f64)` to get the length
+    // of a point with integer coordinates.
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Point<U> {
+        Point { x: f(self.x), y: f(self.y) }
+    }
+}
+
+// Below, a point also stands for the vector from the origin to it, so points can be
+// added, scaled and so on.
+impl<T: Coordinate> Point<T> {
+    pub fn origin() -> Point<T> {
+        Point { x: T::zero(), y: T::zero() }
+    }
+
+    pub fn dot(self, other: Point<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // The `z` of the 3D cross product: positive if `other` is anticlockwise from
+    // `self`, negative if clockwise and zero if they're parallel.
+    pub fn cross(self, other: Point<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    // The squared length needs no square root, so works for integers too, and it
+    // orders points by length just as well.
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn distance_squared(self, other: Point<T>) -> T {
+        (other - self).length_squared()
+    }
+}
+
+impl<T: Float> Point<T> {
+    // Uses `hypot`, so a long vector doesn't overflow on the way.
+    pub fn length(self) -> T {
+        self.x.hypot(self.y)
+    }
+
+    pub fn distance(self, other: Point<T>) -> T {
+        (other - self).length()
+    }
+
+    // The vector in the same direction with length 1. `None` for the zero vector,
+    // which has no direction, and for ones with infinite or NaN coordinates.
+    pub fn normalize(self) -> Option<Point<T>> {
+        let length = self.length();
+        if length > T::zero() && length.is_finite() { Some(self / length) } else { None }
+    }
+
+    // The point `t` of the way from `self` to `other`, so `self` at 0 and `other` at 1
+    // (exactly, which `self + (other - self) * t` isn't always). Other values of `t`
+    // go beyond the ends.
+    pub fn lerp(self, other: Point<T>, t: T) -> Point<T> {
+        self * (T::one() - t) + other * t
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point { x: self.x - other.x, y: self.y - other.y }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Point<T>) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Point<T>) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+// Scaling by a number. Integer points divide the way integers do, rounding towards zero.
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, scale: T) -> Point<T> {
+        Point { x: self.x * scale, y: self.y * scale }
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn div(self, scale: T) -> Point<T> {
+        Point { x: self.x / scale, y: self.y / scale }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point { x: -self.x, y: -self.y }
+    }
+}
+
+impl<T: Coordinate> Rectangle<T> {
+    // The rectangle with `a` and `b` as opposite corners, whichever corners they are.
+    pub fn from_corners(a: Point<T>, b: Point<T>) -> Rectangle<T> {
+        Rectangle {
+            top_left: Point { x: a.x.min(b.x), y: a.y.max(b.y) },
+            bottom_right: Point { x: a.x.max(b.x), y: a.y.min(b.y) },
+        }
+    }
+
+    // The fields are public, so nothing stops `top_left` from actually being the
+    // bottom right. This puts the corners where their names say.
+    pub fn normalize(&self) -> Rectangle<T> {
+        Rectangle::from_corners(self.top_left, self.bottom_right)
+    }
+
+    pub fn is_normalized(&self) -> bool {
+        self.top_left.x <= self.bottom_right.x && self.top_left.y >= self.bottom_right.y
+    }
+
+    // The edges work whichever way round the corners are.
+    pub fn left(&self) -> T {
+        self.top_left.x.min(self.bottom_right.x)
+    }
+
+    pub fn right(&self) -> T {
+        self.top_left.x.max(self.bottom_right.x)
+    }
+
+    pub fn top(&self) -> T {
+        self.top_left.y.max(self.bottom_right.y)
+    }
+
+    pub fn bottom(&self) -> T {
+        self.top_left.y.min(self.bottom_right.y)
+    }
+
+    pub fn width(&self) -> T {
+        self.right() - self.left()
+    }
+
+    pub fn height(&self) -> T {
+        self.top() - self.bottom()
+    }
+
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn center(&self) -> Point<T> {
+        let two = T::one() + T::one();
+        Point { x: self.left() + self.width() / two, y: self.bottom() + self.height() / two }
+    }
+
+    // Points on the edges count as inside.
+    pub fn contains(&self, point: Point<T>) -> bool {
+        (self.left()..=self.right()).contains(&point.x) && (self.bottom()..=self.top()).contains(&point.y)
+    }
+
+    // Whether `other` fits entirely inside, edges included.
+    pub fn contains_rect(&self, other: &Rectangle<T>) -> bool {
+        self.contains(other.top_left) && self.contains(other.bottom_right)
+    }
+
+    // Whether the two have any point in common, so touching counts.
+    pub fn intersects(&self, other: &Rectangle<T>) -> bool {
+        self.left() <= other.right() && other.left() <= self.right() && self.bottom() <= other.top() && other.bottom() <= self.top()
+    }
+
+    // The part the two have in common, which has no area if they only touch.
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Rectangle {
+            top_left: Point { x: self.left().max(other.left()), y: self.top().min(other.top()) },
+            bottom_right: Point { x: self.right().min(other.right()), y: self.bottom().max(other.bottom()) },
+        })
+    }
+
+    // The smallest rectangle holding both.
+    pub fn union(&self, other: &Rectangle<T>) -> Rectangle<T> {
+        Rectangle {
+            top_left: Point { x: self.left().min(other.left()), y: self.top().max(other.top()) },
+            bottom_right: Point { x: self.right().max(other.right()), y: self.bottom().min(other.bottom()) },
+        }
+    }
+
+    // Moves the left and right edges out by `dx` each, and the top and bottom by `dy`.
+    // Negative amounts shrink it, as `deflate` does.
+    pub fn inflate(&self, dx: T, dy: T) -> Rectangle<T> {
+        let center = self.center();
+        let (mut left, mut right) = (self.left() - dx, self.right() + dx);
+        let (mut bottom, mut top) = (self.bottom() - dy, self.top() + dy);
+        // Shrinking by more than there is leaves a line (or a point) through the middle,
+        // rather than turning the rectangle inside out.
+        if left > right {
+            (left, right) = (center.x, center.x);
+        }
+        if bottom > top {
+            (bottom, top) = (center.y, center.y);
+        }
+        Rectangle { top_left: Point { x: left, y: top }, bottom_right: Point { x: right, y: bottom } }
+    }
+
+    pub fn deflate(&self, dx: T, dy: T) -> Rectangle<T> {
+        self.inflate(T::zero() - dx, T::zero() - dy)
+    }
+}
@@ -0,0 +1,10 @@
+// The library half of the structs example. `main.rs` walks through the basics,
+// while the modules here give those structs some behaviour of their own.
+
+pub mod geometry;
+pub mod numerics;
+pub mod shape;
+
+pub use geometry::{Coordinate, Float, Point, Rectangle};
+pub use numerics::{ApproxEq, CirclePosition, Orientation};
+pub use shape::{Circle, Polygon, Shape, Triangle};
@@ -7,6 +7,10 @@
 // An attribute to hide warnings for unused code.
 #![allow(dead_code)]
 
+// `Interval` lives in the enums example's library, next to the other number types.
+use enums::{Interval, Operations};
+use structs::{ApproxEq, Circle, Point, Rectangle, Shape, Triangle};
+
 #[derive(Debug)]
 struct Person {
     name: String,
@@ -19,20 +23,8 @@ struct Unit;
 // A tuple struct
 struct Pair(i32, f32);
 
-#[derive(Debug)]
-struct Point { // A struct with two fields
-    x: f32, // both being floats
-    y: f32,
-}
-
-// Structs can be reused as fields of another struct
-#[derive(Debug)]
-struct Rectangle {
-    // A rectangle can be specified by where the top left and bottom right
-    // corners are in space.
-    top_left: Point, // both properties with Point type values. When you create a struct, you are basically making a new data type.
-    bottom_right: Point, // the Point data type needing 2 values, the x property (which must be a float) and the y property (which must be a float).
-}
+// `Point` and `Rectangle` now live in `src/geometry.rs`, where they have methods for
+// containment, intersection and the like.
 
 fn main() {
     // Create struct with field init shorthand
@@ -45,6 +37,7 @@ fn main() {
 
     // Instantiate a `Point`
     let point: Point = Point { x: 5.2, y: 0.4 }; // aka an instance of point, creating a point instance. (For terminology sake)
+    #[allow(unused_variables)]
     let another_point: Point = Point { x: 10.3, y: 0.2 };
 
     // Access the fields of the point
@@ -61,6 +54,7 @@ fn main() {
     // Destructure the point using a `let` binding
     let Point { x: left_edge, y: top_edge } = point; // the variable won't be usable, but the properties made within would be, and they can be used separately, mking one of the few use cases of this destructuring
 
+    #[allow(clippy::redundant_field_names)]
     let _rectangle = Rectangle {
         // struct instantiation is an expression too
         top_left: Point { x: left_edge, y: top_edge },
@@ -86,24 +80,73 @@ fn main() {
 
         let width = (x2 - x1).abs(); // this line and the next 2 area needed for calculating purposes
         let height = (y1 - y2).abs(); // .abs() is used to ensure value is always positive. For instance, 9.5 - 10.5 would usually be -1, but here would be 1
-        let area = (width * height * 100.0).round() / 100.0; // without the 100's the round function will try to round 1.1999999 whatever the hell and fail because the dec is too long, so we multiply by 100 to get 119.9, which then rounds to 120, then divide by the 100 again to get 1.2
-        area // returns area
+        width * height // returns area, float noise and all: compare it with `approx_eq_ulps` rather than rounding it away
+    }
+
+    // The same calculation with every coordinate wrapped in an `Interval`. Instead of one `f32`
+    // with some float noise in it, this gives bounds that the exact area is guaranteed to be between.
+    // `None` if a coordinate is NaN, since there's no interval to put it in.
+    fn rect_area_bounds(rectangle_struct: &Rectangle) -> Option<Interval> {
+        let Rectangle { top_left: Point { x: x1, y: y1 }, bottom_right: Point { x: x2, y: y2 } } = rectangle_struct;
+        let point = |c: f32| Interval::checked_point(c as f64); // every `f32` fits in an `f64` exactly
+        let (x1, y1, x2, y2) = (point(*x1)?, point(*y1)?, point(*x2)?, point(*y2)?);
+
+        let width = Operations::Subtract.checked_run(x2, x1)?.abs();
+        let height = Operations::Subtract.checked_run(y1, y2)?.abs();
+        Operations::Multiply.checked_run(width, height)
     }
 
     let test_rectangle = Rectangle {
         top_left: Point { x: 10.5, y: 10.9 },
         bottom_right: Point { x: 9.5, y: 9.7 }
     };
+    // The bounds are for the `f32` coordinates, and 10.9 and 9.7 aren't exact in `f32`,
+    // which is why they land around 1.1999998 rather than on 1.2.
+    match rect_area_bounds(&test_rectangle) {
+        Some(bounds) => println!("area is within {:.9}", bounds),
+        None => println!("area can't be bounded: a coordinate is NaN"),
+    }
+    // Or leave the area as it comes out and compare it with what it should be, allowing
+    // a few ULPs (steps between neighbouring `f32`s) for the rounding along the way.
+    println!("area is 1.2 give or take rounding: {}", rect_area(test_rectangle).approx_eq_ulps(1.2, 4));
 
     fn square(square_struct: Point, width_height: f32) -> Rectangle {
-        let Point { x: x1, y: y1 } = square_struct; // the var isn't available, but the x1 and y1 are, which will be equal to the values given in the square_struct param
-        let bottom_measurement = ((y1 - width_height) * 100.0).round() / 100.0; // this is to deal with rounding
-        Rectangle { top_left: Point{ x: x1, y: y1 }, bottom_right: Point { x: x1 + width_height, y: bottom_measurement}} // we return the rectangle instance, which we also define throughout this line.
+        // Points can be added like vectors now, so the opposite corner is the top left
+        // moved right and down by the side length (down being a smaller y).
+        let Point { x: x2, y: y2 } = square_struct + Point { x: width_height, y: -width_height }; // destructuring the sum, the same way as any other point
+        Rectangle { top_left: square_struct, bottom_right: Point { x: x2, y: y2 }} // we return the rectangle instance, which we also define throughout this line.
         // the hardest part about these problems is just getting used to the syntax. Once you start to understand the syntax the problem becomes much easier
     }
 
-    // println!("{:?}", rect_area(test_rectangle))
     let x = 10.6;
     let y = 11.2; 
-    println!("{:#?}", square(Point{x, y}, 11.5))
+    let big_square = square(Point{x, y}, 11.5);
+    println!("{:#?}", big_square);
+    // The same goes for `square`: the bottom edge is a few ULPs off -0.3.
+    println!("bottom edge is -0.3 give or take rounding: {}", big_square.bottom_right.y.approx_eq_ulps(-0.3, 8));
+
+    // The same kind of question with methods instead of destructuring.
+    let window = Rectangle::from_corners(Point::new(0.0, 10.0), Point::new(20.0, 0.0));
+    let dialog = Rectangle::from_corners(Point::new(15.0, 8.0), Point::new(30.0, 2.0));
+    println!("window is {} by {}, centred on {:?}", window.width(), window.height(), window.center());
+    println!("overlap: {:?}", window.intersection(&dialog));
+    println!("both fit in {:?}", window.union(&dialog));
+
+    // Different shapes behind one trait, so they can share a `Vec`.
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(window),
+        Box::new(Circle::new(Point::new(5.0, 5.0), 2.0)),
+        Box::new(Triangle::new(Point::new(0.0, 0.0), Point::new(4.0, 0.0), Point::new(0.0, 3.0))),
+    ];
+    for shape in &shapes {
+        println!("{:?} has area {} and perimeter {}", shape, shape.area(), shape.perimeter());
+    }
+
+    // Points (and rectangles) can have other coordinate types, such as integers for
+    // a grid of cells.
+    let cell: Point<i32> = Point::new(3, 4);
+    let step = Point::new(1, -1);
+    println!("{:?} moves to {:?}, {} cells squared from the origin", cell, cell + step * 2, cell.length_squared());
+    let grid = Rectangle { top_left: Point::new(0, 8), bottom_right: Point::new(8, 0) };
+    println!("cell in grid: {}", grid.contains(cell + step * 5));
 }
@@ -0,0 +1,333 @@
+// Comparing floats without rounding them first, and geometric tests that always
+// give the right answer.
+//
+// `ApproxEq` is for results that went through some arithmetic and so can't be
+// compared with `==`: either by how many representable values apart they are
+// (ULPs, "units in the last place"), or by an epsilon.
+//
+// The predicates are for questions with yes-or-no answers, like which side of a line
+// a point is on. Working those out with plain floats goes wrong for points that are
+// almost in a line: the rounding error is bigger than the answer, and it can say a
+// point is on both sides of an edge, or on neither. These first try plain `f64`,
+// which settles almost every case, together with a bound on how wrong that could
+// be. Only when the answer is within the bound do they redo it exactly, keeping
+// every bit of every product as a sum of `f64`s (Shewchuk's adaptive predicates).
+//
+// The predicates take any points whose coordinates convert to `f64` without
+// rounding (`f32`, `i32`, `f64` and smaller ones) and are exact for all finite
+// `f32` and `i32` coordinates. For `f64` they're exact unless the products overflow
+// or underflow, which needs coordinates beyond about 1e150 or below 1e-150 apart
+// from zero. Infinite or NaN coordinates don't get a meaningful answer.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::geometry::Point;
+
+pub trait ApproxEq: Copy {
+    // How many representable values apart the two are: 0 when they're equal (0.0 and
+    // -0.0 included) and 1 for neighbours. `None` if either is NaN, or if they're
+    // different and either is infinite, as infinity isn't near anything.
+    fn ulps(self, other: Self) -> Option<u64>;
+
+    // Within `epsilon` of each other, either outright (which matters near zero, where
+    // relative error means nothing) or relative to the larger of the two. As with
+    // `ulps`, an infinity is only near itself.
+    fn approx_eq_eps(self, other: Self, epsilon: Self) -> bool;
+
+    fn approx_eq_ulps(self, other: Self, max_ulps: u64) -> bool {
+        self.ulps(other).is_some_and(|ulps| ulps <= max_ulps)
+    }
+}
+
+macro_rules! impl_approx_eq {
+    ($($t:ty => $signed:ty),*) => {
+        $(
+            impl ApproxEq for $t {
+                fn ulps(self, other: $t) -> Option<u64> {
+                    if self == other {
+                        return Some(0);
+                    }
+                    if !self.is_finite() || !other.is_finite() {
+                        return None;
+                    }
+                    // Reading the bits as an integer puts positive floats in order.
+                    // Negative ones have the sign bit set and go the other way, so
+                    // they're flipped to fall below zero in order too.
+                    let ordered = |x: $t| {
+                        let bits = x.to_bits() as $signed;
+                        if bits < 0 { <$signed>::MIN - bits } else { bits }
+                    };
+                    Some((ordered(self) as i128 - ordered(other) as i128).unsigned_abs() as u64)
+                }
+
+                fn approx_eq_eps(self, other: $t, epsilon: $t) -> bool {
+                    let difference = (self - other).abs();
+                    self == other || difference.is_finite() && (difference <= epsilon || difference <= epsilon * self.abs().max(other.abs()))
+                }
+            }
+        )*
+    };
+}
+
+impl_approx_eq!(f32 => i32, f64 => i64);
+
+// Points are as close as their furthest apart coordinates.
+impl<T: ApproxEq> ApproxEq for Point<T> {
+    fn ulps(self, other: Point<T>) -> Option<u64> {
+        Some(self.x.ulps(other.x)?.max(self.y.ulps(other.y)?))
+    }
+
+    fn approx_eq_eps(self, other: Point<T>, epsilon: Point<T>) -> bool {
+        self.x.approx_eq_eps(other.x, epsilon.x) && self.y.approx_eq_eps(other.y, epsilon.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    Collinear,
+    Counterclockwise,
+}
+
+impl Orientation {
+    // The orientation with the points the other way round.
+    pub fn reverse(self) -> Orientation {
+        match self {
+            Orientation::Clockwise => Orientation::Counterclockwise,
+            Orientation::Collinear => Orientation::Collinear,
+            Orientation::Counterclockwise => Orientation::Clockwise,
+        }
+    }
+
+    fn from_sign(sign: f64) -> Orientation {
+        if sign > 0.0 {
+            Orientation::Counterclockwise
+        } else if sign < 0.0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::Collinear
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CirclePosition {
+    Inside,
+    OnCircle,
+    Outside,
+}
+
+// Which way `a`, `b`, `c` go round: counterclockwise means `c` is to the left of the
+// line from `a` to `b` (with `y` up).
+pub fn orientation<T: Into<f64> + Copy>(a: Point<T>, b: Point<T>, c: Point<T>) -> Orientation {
+    Orientation::from_sign(orient2d(wide(a), wide(b), wide(c)))
+}
+
+// Where `d` is relative to the circle through `a`, `b` and `c`, in any order. `None`
+// if those three are in a line, when there's no such circle.
+pub fn in_circle<T: Into<f64> + Copy>(a: Point<T>, b: Point<T>, c: Point<T>, d: Point<T>) -> Option<CirclePosition> {
+    let (a, b, c, d) = (wide(a), wide(b), wide(c), wide(d));
+    let turn = Orientation::from_sign(orient2d(a, b, c));
+    let sign = in_circle_sign(a, b, c, d);
+    // The determinant is positive for inside when `a b c` go counterclockwise, and
+    // the other way round when they go clockwise.
+    let inside = match turn {
+        Orientation::Counterclockwise => sign > 0.0,
+        Orientation::Clockwise => sign < 0.0,
+        Orientation::Collinear => return None,
+    };
+    Some(if sign == 0.0 {
+        CirclePosition::OnCircle
+    } else if inside {
+        CirclePosition::Inside
+    } else {
+        CirclePosition::Outside
+    })
+}
+
+// Whether `p` is on the segment from `a` to `b`, ends included.
+pub fn on_segment<T: Into<f64> + Copy>(p: Point<T>, a: Point<T>, b: Point<T>) -> bool {
+    let (p, a, b) = (wide(p), wide(a), wide(b));
+    orient2d(a, b, p) == 0.0 && p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+}
+
+// Whether the segments `a b` and `c d` have any point in common. Touching counts,
+// as with rectangles.
+pub fn segments_intersect<T: Into<f64> + Copy>(a: Point<T>, b: Point<T>, c: Point<T>, d: Point<T>) -> bool {
+    let (a, b, c, d) = (wide(a), wide(b), wide(c), wide(d));
+    let sides = |p, q, r| Orientation::from_sign(orient2d(p, q, r));
+    let (c_side, d_side) = (sides(a, b, c), sides(a, b, d));
+    let (a_side, b_side) = (sides(c, d, a), sides(c, d, b));
+    // Each segment has the ends of the other on different sides of it, so they cross.
+    if c_side != d_side && a_side != b_side {
+        return true;
+    }
+    // Otherwise they only meet if an end of one lies on the other.
+    on_segment(c, a, b) || on_segment(d, a, b) || on_segment(a, c, d) || on_segment(b, c, d)
+}
+
+fn wide<T: Into<f64> + Copy>(p: Point<T>) -> Point<f64> {
+    p.map(Into::into)
+}
+
+// The unit roundoff of `f64`, half the gap between 1 and the next float up.
+const EPSILON: f64 = f64::EPSILON / 2.0;
+// Shewchuk's bounds on the error of the plain `f64` determinants, as a fraction of
+// the sum of the sizes of their terms.
+const ORIENT_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const IN_CIRCLE_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+// Twice the signed area of `a b c`, or at least a number with the same sign.
+fn orient2d(a: Point<f64>, b: Point<f64>, c: Point<f64>) -> f64 {
+    let left = (a.x - c.x) * (b.y - c.y);
+    let right = (a.y - c.y) * (b.x - c.x);
+    let det = left - right;
+    // When the two products have different signs (or one is zero), nothing cancels
+    // and the sign of the difference can be trusted as it is.
+    let sum = if left > 0.0 && right > 0.0 {
+        left + right
+    } else if left < 0.0 && right < 0.0 {
+        -left - right
+    } else {
+        return det;
+    };
+    if det.abs() >= ORIENT_BOUND * sum {
+        return det;
+    }
+    let (ax, ay, bx, by, cx, cy) = (Expansion::of(a.x), Expansion::of(a.y), Expansion::of(b.x), Expansion::of(b.y), Expansion::of(c.x), Expansion::of(c.y));
+    let exact = (&ax - &cx) * (&by - &cy) - (&ay - &cy) * (&bx - &cx);
+    exact.sign()
+}
+
+// Positive if `d` is inside the circle through `a b c` when those go counterclockwise.
+fn in_circle_sign(a: Point<f64>, b: Point<f64>, c: Point<f64>, d: Point<f64>) -> f64 {
+    let (adx, ady, bdx, bdy, cdx, cdy) = (a.x - d.x, a.y - d.y, b.x - d.x, b.y - d.y, c.x - d.x, c.y - d.y);
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let (alift, blift, clift) = (adx * adx + ady * ady, bdx * bdx + bdy * bdy, cdx * cdx + cdy * cdy);
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift + (cdxady.abs() + adxcdy.abs()) * blift + (adxbdy.abs() + bdxady.abs()) * clift;
+    if det.abs() > IN_CIRCLE_BOUND * permanent {
+        return det;
+    }
+    let difference = |p: f64, q: f64| &Expansion::of(p) - &Expansion::of(q);
+    let (adx, ady, bdx, bdy, cdx, cdy) = (difference(a.x, d.x), difference(a.y, d.y), difference(b.x, d.x), difference(b.y, d.y), difference(c.x, d.x), difference(c.y, d.y));
+    let lift = |x: &Expansion, y: &Expansion| x.clone() * x.clone() + y.clone() * y.clone();
+    let cross = |x1: &Expansion, y1: &Expansion, x2: &Expansion, y2: &Expansion| x1.clone() * y2.clone() - x2.clone() * y1.clone();
+    let exact = lift(&adx, &ady) * cross(&bdx, &bdy, &cdx, &cdy) + lift(&bdx, &bdy) * cross(&cdx, &cdy, &adx, &ady) + lift(&cdx, &cdy) * cross(&adx, &ady, &bdx, &bdy);
+    exact.sign()
+}
+
+// `a + b` exactly, as the rounded sum and the error the rounding made.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_part = sum - a;
+    let a_part = sum - b_part;
+    (sum, (a - a_part) + (b - b_part))
+}
+
+// `a * b` exactly, the same way. A fused multiply-add rounds only once, so it gives
+// the error of the rounded product without losing any of it.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    (product, a.mul_add(b, -product))
+}
+
+// A number held exactly as a sum of `f64`s that don't overlap (each one's lowest
+// bit is above the next one's highest), smallest first and with no zeros. The last
+// one is the biggest by far, so it has the sign of the whole sum.
+#[derive(Debug, Clone)]
+struct Expansion(Vec<f64>);
+
+impl Expansion {
+    fn of(x: f64) -> Expansion {
+        Expansion(if x == 0.0 { vec![] } else { vec![x] })
+    }
+
+    // Adds one `f64`, passing it up through the parts and keeping the errors.
+    fn grow(&self, b: f64) -> Expansion {
+        let mut parts = Vec::with_capacity(self.0.len() + 1);
+        let mut carry = b;
+        for &part in &self.0 {
+            let (sum, error) = two_sum(carry, part);
+            if error != 0.0 {
+                parts.push(error);
+            }
+            carry = sum;
+        }
+        if carry != 0.0 {
+            parts.push(carry);
+        }
+        Expansion(parts)
+    }
+
+    fn scale(&self, b: f64) -> Expansion {
+        let mut parts = Vec::with_capacity(self.0.len() * 2);
+        let Some((&first, rest)) = self.0.split_first() else { return Expansion(parts) };
+        let (mut carry, error) = two_product(first, b);
+        if error != 0.0 {
+            parts.push(error);
+        }
+        for &part in rest {
+            let (high, low) = two_product(part, b);
+            let (sum, error) = two_sum(carry, low);
+            if error != 0.0 {
+                parts.push(error);
+            }
+            let (sum, error) = two_sum(high, sum);
+            if error != 0.0 {
+                parts.push(error);
+            }
+            carry = sum;
+        }
+        if carry != 0.0 {
+            parts.push(carry);
+        }
+        Expansion(parts)
+    }
+
+    fn sign(&self) -> f64 {
+        self.0.last().copied().unwrap_or(0.0)
+    }
+}
+
+impl Add for Expansion {
+    type Output = Expansion;
+
+    fn add(self, other: Expansion) -> Expansion {
+        other.0.iter().fold(self, |sum, &part| sum.grow(part))
+    }
+}
+
+impl Neg for Expansion {
+    type Output = Expansion;
+
+    fn neg(self) -> Expansion {
+        Expansion(self.0.iter().map(|part| -part).collect())
+    }
+}
+
+impl Sub for Expansion {
+    type Output = Expansion;
+
+    fn sub(self, other: Expansion) -> Expansion {
+        self + -other
+    }
+}
+
+impl Sub for &Expansion {
+    type Output = Expansion;
+
+    fn sub(self, other: &Expansion) -> Expansion {
+        self.clone() - other.clone()
+    }
+}
+
+impl Mul for Expansion {
+    type Output = Expansion;
+
+    fn mul(self, other: Expansion) -> Expansion {
+        other.0.iter().fold(Expansion(vec![]), |sum, &part| sum + self.scale(part))
+    }
+}
@@ -0,0 +1,261 @@
+// What every flat shape can tell you about itself, so code can work with a mix of
+// them, like a `Vec<Box<dyn Shape>>` of everything on a canvas.
+//
+// Like `Rectangle`, shapes are closed: points on the outline are inside. Sums and
+// products are worked out in `f64`, where differences and products of `f32`
+// coordinates come out exact or very nearly, and only the answers are rounded back
+// to `f32`. Containment uses the exact predicates from `numerics`, so a point
+// exactly on an edge two shapes share is in both, and one next to it in one or the
+// other, never neither.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::geometry::{Point, Rectangle};
+use crate::numerics::{on_segment, orientation, Orientation};
+
+pub trait Shape: fmt::Debug {
+    fn area(&self) -> f32;
+    fn perimeter(&self) -> f32;
+    // The smallest upright rectangle the shape fits in.
+    fn bounding_box(&self) -> Rectangle;
+    // The centre of mass, which for a circle is its centre and for a rectangle the
+    // middle. Not always inside the shape: think of a crescent.
+    fn centroid(&self) -> Point;
+    fn contains(&self, point: Point) -> bool;
+}
+
+impl Shape for Rectangle {
+    // These call the methods `Rectangle` already has, which take priority over the
+    // trait's when the names are the same.
+    fn area(&self) -> f32 {
+        self.area()
+    }
+
+    fn perimeter(&self) -> f32 {
+        2.0 * (self.width() + self.height())
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        self.normalize()
+    }
+
+    fn centroid(&self) -> Point {
+        self.center()
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.contains(point)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: f32,
+}
+
+impl Circle {
+    // `None` unless the radius is zero or more (so not NaN).
+    pub fn checked_new(center: Point, radius: f32) -> Option<Circle> {
+        if radius >= 0.0 { Some(Circle { center, radius }) } else { None }
+    }
+
+    pub fn new(center: Point, radius: f32) -> Circle {
+        Circle::checked_new(center, radius).expect("a circle's radius can't be negative or NaN")
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f32 {
+        (PI * self.radius as f64 * self.radius as f64) as f32
+    }
+
+    fn perimeter(&self) -> f32 {
+        (2.0 * PI * self.radius as f64) as f32
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        let Point { x, y } = self.center;
+        let r = self.radius;
+        Rectangle { top_left: Point { x: x - r, y: y + r }, bottom_right: Point { x: x + r, y: y - r } }
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    // Compares squared distances, so there's no square root to round.
+    fn contains(&self, point: Point) -> bool {
+        let r = self.radius as f64;
+        wide(point).distance_squared(wide(self.center)) <= r * r
+    }
+}
+
+// A point's coordinates as `f64`, for working things out in.
+fn wide(p: Point) -> Point<f64> {
+    p.map(f64::from)
+}
+
+// Twice the signed area of the triangle `a b c`: positive if the corners go round
+// anticlockwise (with `y` up, as everywhere here), negative if clockwise, and zero
+// if they're in a line.
+fn cross(a: Point, b: Point, c: Point) -> f64 {
+    let a = wide(a);
+    (wide(b) - a).cross(wide(c) - a)
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    wide(a).distance(wide(b))
+}
+
+fn bounding_box_of(points: &[Point]) -> Rectangle {
+    let left = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+    let right = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+    let bottom = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+    let top = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+    Rectangle { top_left: Point { x: left, y: top }, bottom_right: Point { x: right, y: bottom } }
+}
+
+fn average_of(points: &[Point]) -> Point {
+    let n = points.len() as f64;
+    let x = points.iter().map(|p| p.x as f64).sum::<f64>() / n;
+    let y = points.iter().map(|p| p.y as f64).sum::<f64>() / n;
+    Point { x: x as f32, y: y as f32 }
+}
+
+// The corners can go round either way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    pub a: Point,
+    pub b: Point,
+    pub c: Point,
+}
+
+impl Triangle {
+    pub fn new(a: Point, b: Point, c: Point) -> Triangle {
+        Triangle { a, b, c }
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f32 {
+        (cross(self.a, self.b, self.c).abs() / 2.0) as f32
+    }
+
+    fn perimeter(&self) -> f32 {
+        (distance(self.a, self.b) + distance(self.b, self.c) + distance(self.c, self.a)) as f32
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        bounding_box_of(&[self.a, self.b, self.c])
+    }
+
+    fn centroid(&self) -> Point {
+        average_of(&[self.a, self.b, self.c])
+    }
+
+    // Inside means on the same side of all three edges (or on one of them). A flat
+    // triangle has no inside, only its edges.
+    fn contains(&self, point: Point) -> bool {
+        // Also keeps out NaN, which would be in line with every edge.
+        if !self.bounding_box().contains(point) {
+            return false;
+        }
+        if orientation(self.a, self.b, self.c) == Orientation::Collinear {
+            return on_segment(point, self.a, self.b) || on_segment(point, self.b, self.c) || on_segment(point, self.c, self.a);
+        }
+        let sides = [orientation(self.a, self.b, point), orientation(self.b, self.c, point), orientation(self.c, self.a, point)];
+        !sides.contains(&Orientation::Clockwise) || !sides.contains(&Orientation::Counterclockwise)
+    }
+}
+
+// A polygon with any number of corners, in order around the outline in either
+// direction. It should be simple, meaning its edges only meet at the corners they
+// share. That isn't checked, and a polygon that crosses itself gets an area and
+// centroid that don't mean much.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    points: Vec<Point>,
+}
+
+impl Polygon {
+    // `None` with fewer than three corners.
+    pub fn checked_new(points: Vec<Point>) -> Option<Polygon> {
+        if points.len() >= 3 { Some(Polygon { points }) } else { None }
+    }
+
+    pub fn new(points: Vec<Point>) -> Polygon {
+        Polygon::checked_new(points).expect("a polygon needs at least three corners")
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    // Each corner with the one after it, ending with the last and the first.
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.points.iter().copied().zip(self.points.iter().copied().cycle().skip(1))
+    }
+
+    // The shoelace formula: positive if the corners go round anticlockwise.
+    fn signed_area(&self) -> f64 {
+        self.edges().map(|(p, q)| wide(p).cross(wide(q))).sum::<f64>() / 2.0
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f32 {
+        self.signed_area().abs() as f32
+    }
+
+    fn perimeter(&self) -> f32 {
+        self.edges().map(|(p, q)| distance(p, q)).sum::<f64>() as f32
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        bounding_box_of(&self.points)
+    }
+
+    fn centroid(&self) -> Point {
+        let area = self.signed_area();
+        // All the corners in a line: there's no area to balance, so use the corners.
+        if area == 0.0 {
+            return average_of(&self.points);
+        }
+        let (mut x, mut y) = (0.0, 0.0);
+        for (p, q) in self.edges() {
+            let (p, q) = (wide(p), wide(q));
+            let sum = (p + q) * p.cross(q);
+            x += sum.x;
+            y += sum.y;
+        }
+        Point { x: (x / (6.0 * area)) as f32, y: (y / (6.0 * area)) as f32 }
+    }
+
+    // Counts how many edges a line going right from `point` crosses: an odd number
+    // means inside. Points on an edge are checked for first, since the count can go
+    // either way for them.
+    fn contains(&self, point: Point) -> bool {
+        if !self.bounding_box().contains(point) {
+            return false;
+        }
+        if self.edges().any(|(p, q)| on_segment(point, p, q)) {
+            return true;
+        }
+        let mut inside = false;
+        for (p, q) in self.edges() {
+            // Each edge includes its lower end and not its upper one, so a line
+            // passing exactly through a corner is counted once, not twice.
+            if (p.y > point.y) != (q.y > point.y) {
+                // Crossing means being left of an upward edge or right of a downward
+                // one. Being on the edge was dealt with above.
+                let left = orientation(p, q, point) == Orientation::Counterclockwise;
+                if left == (q.y > p.y) {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+}
@@ -0,0 +1,278 @@
+use enums::fuzz::Rng;
+use structs::numerics::{in_circle, on_segment, orientation, segments_intersect};
+use structs::{ApproxEq, CirclePosition, Orientation, Point, Polygon, Shape, Triangle};
+
+fn sign(n: i128) -> Orientation {
+    match n.signum() {
+        1 => Orientation::Counterclockwise,
+        -1 => Orientation::Clockwise,
+        _ => Orientation::Collinear,
+    }
+}
+
+// The right answers, from integers that can't round.
+fn exact_orientation(a: (i128, i128), b: (i128, i128), c: (i128, i128)) -> Orientation {
+    sign((a.0 - c.0) * (b.1 - c.1) - (a.1 - c.1) * (b.0 - c.0))
+}
+
+fn exact_in_circle(a: (i128, i128), b: (i128, i128), c: (i128, i128), d: (i128, i128)) -> Option<CirclePosition> {
+    let [(adx, ady), (bdx, bdy), (cdx, cdy)] = [a, b, c].map(|p| (p.0 - d.0, p.1 - d.1));
+    let det = (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy) + (bdx * bdx + bdy * bdy) * (cdx * ady - adx * cdy) + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady);
+    let det = match exact_orientation(a, b, c) {
+        Orientation::Counterclockwise => det,
+        Orientation::Clockwise => -det,
+        Orientation::Collinear => return None,
+    };
+    Some(match det.signum() {
+        1 => CirclePosition::Inside,
+        -1 => CirclePosition::Outside,
+        _ => CirclePosition::OnCircle,
+    })
+}
+
+// What you get without the predicates.
+fn naive_orientation(a: Point<f64>, b: Point<f64>, c: Point<f64>) -> Orientation {
+    let det = (b - a).cross(c - a);
+    if det > 0.0 {
+        Orientation::Counterclockwise
+    } else if det < 0.0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+fn random(rng: &mut Rng, bound: i128) -> i128 {
+    rng.below(2 * bound as u64 + 1) as i128 - bound
+}
+
+#[test]
+fn ulps_and_epsilons() {
+    // ULPs.
+    assert_eq!(1.0_f64.ulps(1.0_f64.next_up()), Some(1));
+    assert_eq!((0.1_f64 + 0.2).ulps(0.3), Some(1));
+    assert!((0.1_f64 + 0.2).approx_eq_ulps(0.3, 1) && !(0.1_f64 + 0.2).approx_eq_ulps(0.3, 0));
+    assert_eq!(0.0_f64.ulps(-0.0), Some(0));
+    // Across zero, counting the smallest values on both sides.
+    let smallest = f32::from_bits(1);
+    assert_eq!(smallest.ulps(-smallest), Some(2));
+    assert_eq!(f32::MAX.ulps(-f32::MAX), Some(2 * f32::MAX.to_bits() as u64));
+    assert_eq!(f64::MAX.ulps(-f64::MAX), Some(2 * f64::MAX.to_bits()));
+    // The gap between neighbours grows with size, and ULPs grow with it.
+    assert_eq!(1e20_f64.ulps(1e20 + 16384.0), Some(1));
+    assert_eq!(f64::NAN.ulps(f64::NAN), None);
+    assert_eq!(f64::INFINITY.ulps(f64::INFINITY), Some(0));
+    assert_eq!(f64::MAX.ulps(f64::INFINITY), None);
+    let tenths: f32 = (0..10).map(|_| 0.1_f32).sum();
+    assert_ne!(tenths, 1.0);
+    assert!(tenths.approx_eq_ulps(1.0, 16));
+    // Near zero, ULPs are useless: 1e-20 is a huge number of them from 0.
+    assert!(!1e-20_f64.approx_eq_ulps(0.0, 1_000_000));
+    assert!(1e-20_f64.approx_eq_eps(0.0, 1e-12));
+    // Away from zero, the epsilon is relative.
+    assert!(1e9_f64.approx_eq_eps(1e9 + 0.5, 1e-9) && !1.0_f64.approx_eq_eps(1.5, 1e-9));
+    assert!(!f64::INFINITY.approx_eq_eps(f64::MAX, 1e-9) && !f64::NAN.approx_eq_eps(f64::NAN, 1.0));
+    // Points compare by their coordinates.
+    let p = Point::new(0.1_f64, 0.7) * 3.0;
+    assert_ne!(p, Point::new(0.3, 2.1));
+    assert!(p.approx_eq_ulps(Point::new(0.3, 2.1), 1));
+    assert!(p.approx_eq_eps(Point::new(0.3, 2.1), Point::new(1e-12, 1e-12)));
+    assert!(!p.approx_eq_eps(Point::new(0.3, 2.2), Point::new(1e-12, 1e-12)));
+}
+
+#[test]
+fn orientation_basics() {
+    // The basic cases.
+    let origin = Point::new(0, 0);
+    assert_eq!(orientation(origin, Point::new(1, 0), Point::new(0, 1)), Orientation::Counterclockwise);
+    assert_eq!(orientation(origin, Point::new(0, 1), Point::new(1, 0)), Orientation::Clockwise);
+    assert_eq!(orientation(origin, Point::new(1, 1), Point::new(-3, -3)), Orientation::Collinear);
+    assert_eq!(Orientation::Clockwise.reverse(), Orientation::Counterclockwise);
+}
+
+#[test]
+fn orientation_near_a_line_is_exact() {
+    // Shewchuk's example: points a few ULPs apart near (0.5, 0.5), against a line
+    // through (12, 12) and (24, 24). Plain `f64` gets a good share of these wrong.
+    let tiny = 2f64.powi(-53);
+    let (b, c) = (Point::new(12.0, 12.0), Point::new(24.0, 24.0));
+    let scale = |x: f64| (x / tiny) as i128;
+    let mut naive_mistakes = 0;
+    for i in 0..64 {
+        for j in 0..64 {
+            let a = Point::new(0.5 + i as f64 * tiny, 0.5 + j as f64 * tiny);
+            let exact = exact_orientation((scale(a.x), scale(a.y)), (scale(b.x), scale(b.y)), (scale(c.x), scale(c.y)));
+            assert_eq!(orientation(a, b, c), exact, "{:?}", a);
+            if naive_orientation(a, b, c) != exact {
+                naive_mistakes += 1;
+            }
+        }
+    }
+    assert!(naive_mistakes > 100, "{}", naive_mistakes);
+}
+
+#[test]
+fn orientation_of_random_nearly_collinear_points() {
+    // Random points almost in a line, with coordinates that use all 53 bits of an
+    // `f64`, so the products can't be done exactly in `f64`.
+    let mut rng = Rng::new(2024);
+    let mut counts = [0; 3];
+    for _ in 0..20_000 {
+        // `a` and `b` are whole numbers of steps apart along a line, and `c` is a
+        // whole number of steps along it too, then nudged (or not).
+        let a = (random(&mut rng, 1 << 50), random(&mut rng, 1 << 50));
+        let step = (random(&mut rng, 1 << 30), random(&mut rng, 1 << 30));
+        let (to_b, to_c) = (random(&mut rng, 1 << 19), random(&mut rng, 1 << 19));
+        let b = (a.0 + step.0 * to_b, a.1 + step.1 * to_b);
+        let c = (a.0 + step.0 * to_c + random(&mut rng, 2), a.1 + step.1 * to_c + random(&mut rng, 2));
+        // Scaling by a power of two changes nothing but the exponent.
+        let point = |p: (i128, i128)| Point::new(p.0 as f64 * 2f64.powi(-60), p.1 as f64 * 2f64.powi(-60));
+        let (pa, pb, pc) = (point(a), point(b), point(c));
+        let exact = exact_orientation(a, b, c);
+        assert_eq!(orientation(pa, pb, pc), exact, "{:?}", (a, b, c));
+        // Every order of the same three points agrees.
+        assert_eq!(orientation(pb, pc, pa), exact);
+        assert_eq!(orientation(pc, pa, pb), exact);
+        assert_eq!(orientation(pb, pa, pc), exact.reverse());
+        assert_eq!(orientation(pa, pc, pb), exact.reverse());
+        counts[exact as usize] += 1;
+    }
+    // The nudges land on both sides and right on the line.
+    assert!(counts.iter().all(|&count| count > 500), "{:?}", counts);
+}
+
+#[test]
+fn segments() {
+    // Being on a segment, and segments meeting, for the same kind of points.
+    let (a, b) = (Point::new(0.0, 0.0), Point::new(3e15, 1e15));
+    let on = Point::new(3.0, 1.0);
+    assert!(on_segment(on, a, b) && !on_segment(on, a, Point::new(3e15, 1e15 + 0.125)));
+    let above = Point::new(3.0, 1.0_f64.next_up());
+    assert!(!on_segment(above, a, b));
+    assert!(segments_intersect(a, b, on, Point::new(3.0, 5.0)));
+    assert!(!segments_intersect(a, b, above, Point::new(3.0, 5.0)));
+    assert!(segments_intersect(a, b, above, Point::new(3.0, -5.0)));
+    // Collinear segments meet only if they overlap.
+    assert!(segments_intersect(a, on, on, b));
+    assert!(!segments_intersect(a, on, Point::new(6.0, 2.0), b));
+    // Parallel ones never do.
+    assert!(!segments_intersect(a, b, Point::new(0.0, 1.0), Point::new(3e15, 1e15 + 1.0)));
+    let mut rng = Rng::new(7);
+    for _ in 0..5_000 {
+        let bound = 1 << 40;
+        let ends: Vec<Point<f64>> = (0..3).map(|_| Point::new(random(&mut rng, bound) as f64, random(&mut rng, bound) as f64)).collect();
+        // The fourth end is close to the first segment.
+        let d = ends[0].lerp(ends[1], rng.below(1_000) as f64 / 999.0) + Point::new(random(&mut rng, 1) as f64, random(&mut rng, 1) as f64);
+        let (a, b, c) = (ends[0], ends[1], ends[2]);
+        let meet = segments_intersect(a, b, c, d);
+        assert_eq!(meet, segments_intersect(b, a, d, c));
+        assert_eq!(meet, segments_intersect(c, d, a, b));
+        assert!(!on_segment(d, a, b) || meet);
+    }
+}
+
+#[test]
+fn containment_is_consistent_along_a_shared_edge() {
+    // Containment stays consistent. A kite split along its long diagonal into two
+    // triangles: every point near the diagonal is in one of them if the kite has it,
+    // in both if it's exactly on the diagonal, and in neither otherwise.
+    let mut rng = Rng::new(11);
+    for _ in 0..200 {
+        let coordinate = |rng: &mut Rng| random(rng, 1 << 20) as f32 / 1024.0 + 0.1;
+        let (start, end) = (Point::new(coordinate(&mut rng), coordinate(&mut rng)), Point::new(coordinate(&mut rng), coordinate(&mut rng)));
+        let middle = start.lerp(end, 0.5);
+        let across = Point::new(start.y - end.y, end.x - start.x) * 0.25;
+        let (left, right) = (middle + across, middle - across);
+        let kite = Polygon::new(vec![start, right, end, left]);
+        let (first, second) = (Triangle::new(start, right, end), Triangle::new(start, end, left));
+        for _ in 0..50 {
+            let t = rng.below(1_001) as f32 / 1_000.0;
+            let mut q = start.lerp(end, t);
+            // Nudge one coordinate a few representable values either way.
+            let nudge = |x: f32, rng: &mut Rng| f32::from_bits((x.to_bits() as i64 + random(rng, 3) as i64) as u32);
+            q = if rng.below(2) == 0 { Point::new(nudge(q.x, &mut rng), q.y) } else { Point::new(q.x, nudge(q.y, &mut rng)) };
+            let (in_first, in_second) = (first.contains(q), second.contains(q));
+            assert_eq!(in_first || in_second, kite.contains(q), "{:?}", q);
+            assert_eq!(in_first && in_second, on_segment(q, start, end), "{:?}", q);
+            // And the answers match which side of the diagonal `q` is on.
+            match orientation(start, end, q) {
+                Orientation::Clockwise => assert!(!in_second),
+                Orientation::Counterclockwise => assert!(!in_first),
+                Orientation::Collinear => {},
+            }
+        }
+    }
+}
+
+#[test]
+fn in_circle_is_exact() {
+    // In-circle: a square's corners are on one circle, whichever way round.
+    let square = [Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(1.0, 1.0), Point::new(0.0, 1.0)];
+    assert_eq!(in_circle(square[0], square[1], square[2], square[3]), Some(CirclePosition::OnCircle));
+    for (a, b, c) in [(square[0], square[1], square[2]), (square[2], square[1], square[0])] {
+        assert_eq!(in_circle(a, b, c, square[3]), Some(CirclePosition::OnCircle));
+        assert_eq!(in_circle(a, b, c, Point::new(0.5, 0.5)), Some(CirclePosition::Inside));
+        assert_eq!(in_circle(a, b, c, Point::new(1.0, 1.0_f64.next_up())), Some(CirclePosition::Outside));
+        assert_eq!(in_circle(a, b, c, Point::new(2.0, 2.0)), Some(CirclePosition::Outside));
+    }
+    assert_eq!(in_circle(Point::new(0, 0), Point::new(1, 1), Point::new(2, 2), Point::new(0, 1)), None);
+    // Points of a Pythagorean triple, scaled up until the determinant needs more than
+    // 53 bits, are exactly on the circle, and one step off isn't.
+    let k = 1 << 26;
+    let center = (123_456, -654_321);
+    let on_circle = [(3, 4), (-4, 3), (-5, 0), (0, -5), (4, -3)].map(|(x, y)| (center.0 + x * k, center.1 + y * k));
+    let point = |p: (i128, i128)| Point::new(p.0 as i32, p.1 as i32);
+    let [a, b, c, d, e] = on_circle.map(point);
+    assert_eq!(in_circle(a, b, c, d), Some(CirclePosition::OnCircle));
+    assert_eq!(in_circle(c, e, a, b), Some(CirclePosition::OnCircle));
+    for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+        let nudged = (on_circle[3].0 + dx, on_circle[3].1 + dy);
+        let expected = exact_in_circle(on_circle[0], on_circle[1], on_circle[2], nudged);
+        assert_ne!(expected, Some(CirclePosition::OnCircle));
+        assert_eq!(in_circle(a, b, c, point(nudged)), expected);
+    }
+    // Points a few ULPs from (5, 0), on the circle of radius 5 through (3, 4),
+    // (-5, 0) and (0, -5). The determinant is far smaller than its rounding error,
+    // but the right answer is easy: whether `x * x + y * y` is below 25.
+    let tiny = 2f64.powi(-50);
+    let circle = [Point::new(3.0, 4.0), Point::new(-5.0, 0.0), Point::new(0.0, -5.0)];
+    for i in -16..=16_i128 {
+        for j in -16..=16_i128 {
+            let d = Point::new(5.0 + i as f64 * tiny, j as f64 * tiny);
+            // In steps of `tiny`, the point is at `(5 / tiny + i, j)`.
+            let x = (5 << 50) + i;
+            let expected = match (x * x + j * j).cmp(&(25 << 100)) {
+                std::cmp::Ordering::Less => CirclePosition::Inside,
+                std::cmp::Ordering::Equal => CirclePosition::OnCircle,
+                std::cmp::Ordering::Greater => CirclePosition::Outside,
+            };
+            let [a, b, c] = circle;
+            for (p, q, r) in [(a, b, c), (b, c, a), (c, b, a), (b, a, c)] {
+                assert_eq!(in_circle(p, q, r, d), Some(expected), "{:?}", (i, j));
+            }
+        }
+    }
+}
+
+#[test]
+fn in_circle_for_random_points_on_a_big_circle() {
+    let point = |p: (i128, i128)| Point::new(p.0 as i32, p.1 as i32);
+    // Random points rounded onto a big circle, against the exact answer, in every order.
+    let mut rng = Rng::new(99);
+    let mut counts = [0; 3];
+    for _ in 0..5_000 {
+        let radius = (1 << 28) as f64;
+        let mut on_circle = || {
+            let angle = rng.below(1 << 20) as f64 / (1 << 20) as f64 * std::f64::consts::TAU;
+            ((radius * angle.cos()).round() as i128, (radius * angle.sin()).round() as i128)
+        };
+        let (a, b, c, d) = (on_circle(), on_circle(), on_circle(), on_circle());
+        let Some(expected) = exact_in_circle(a, b, c, d) else { continue };
+        let [pa, pb, pc, pd] = [a, b, c, d].map(point);
+        for (p, q, r) in [(pa, pb, pc), (pb, pc, pa), (pc, pb, pa), (pa, pc, pb)] {
+            assert_eq!(in_circle(p, q, r, pd), Some(expected), "{:?}", (a, b, c, d));
+        }
+        counts[expected as usize] += 1;
+    }
+    assert!(counts[CirclePosition::Inside as usize] > 500 && counts[CirclePosition::Outside as usize] > 500, "{:?}", counts);
+}
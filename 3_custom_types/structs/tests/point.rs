@@ -0,0 +1,99 @@
+use structs::{Point, Rectangle};
+
+#[test]
+fn integer_points_are_exact() {
+    // Integer points: the arithmetic is exact.
+    let a: Point<i32> = Point::new(3, 4);
+    let b = Point::new(-1, 2);
+    assert_eq!(a + b, Point::new(2, 6));
+    assert_eq!(a - b, Point::new(4, 2));
+    assert_eq!(-a, Point::new(-3, -4));
+    assert_eq!(a * 3, Point::new(9, 12));
+    // Division rounds towards zero, as it does for integers.
+    assert_eq!(Point::new(7, -7) / 2, Point::new(3, -3));
+    let mut c = a;
+    c += b;
+    assert_eq!(c, Point::new(2, 6));
+    c -= b;
+    assert_eq!(c, a);
+    assert_eq!(Point::<i32>::origin(), Point::new(0, 0));
+    assert_eq!((a.dot(b), a.cross(b), b.cross(a)), (5, 10, -10));
+    // Perpendicular vectors have a zero dot product, parallel ones a zero cross product.
+    assert_eq!(Point::new(2, 1).dot(Point::new(-1, 2)), 0);
+    assert_eq!(Point::new(2, 1).cross(Point::new(-4, -2)), 0);
+    assert_eq!((a.length_squared(), a.distance_squared(b)), (25, 20));
+    // For lengths, change the coordinate type first.
+    assert_eq!(a.map(|c| c as f64).length(), 5.0);
+    let big: Point<i64> = Point::new(3_000_000_000, -4_000_000_000);
+    // Its squared length is too big for `i64`, but not for `i128`.
+    assert_eq!(big.map(i128::from).length_squared(), 25_000_000_000_000_000_000);
+    assert_eq!(big.map(|c| c as f64).length(), 5_000_000_000.0);
+}
+
+#[test]
+fn float_lengths_and_normalizing() {
+    // Float points.
+    let p = Point::new(3.0_f32, 4.0);
+    assert_eq!((p.length(), p.distance(Point::new(0.0, 0.0))), (5.0, 5.0));
+    assert_eq!(p.normalize(), Some(Point::new(0.6, 0.8)));
+    assert_eq!(Point::new(0.0_f32, -2.0).normalize(), Some(Point::new(0.0, -1.0)));
+    assert_eq!(Point::new(0.0_f32, 0.0).normalize(), None);
+    assert_eq!(Point::new(f32::NAN, 1.0).normalize(), None);
+    assert_eq!(Point::new(f32::INFINITY, 1.0).normalize(), None);
+    // `length` doesn't overflow even when the squared length would.
+    let huge = Point::new(3e30_f32, 4e30);
+    assert!(huge.length_squared().is_infinite());
+    assert_eq!(huge.length(), 5e30);
+    let unit = huge.normalize().unwrap();
+    assert!((unit.length() - 1.0).abs() < 1e-6);
+    // Tiny vectors still normalize.
+    let tiny = Point::new(3e-40_f32, 4e-40);
+    let unit = tiny.normalize().unwrap();
+    assert!((unit.x - 0.6).abs() < 1e-3 && (unit.y - 0.8).abs() < 1e-3);
+}
+
+#[test]
+fn interpolation() {
+    // Interpolation hits both ends exactly, and the middle halfway.
+    let from = Point::new(0.1_f64, -3.7);
+    let to = Point::new(1e10, 0.3);
+    assert_eq!(from.lerp(to, 0.0), from);
+    assert_eq!(from.lerp(to, 1.0), to);
+    assert_eq!(Point::new(0.0, 0.0).lerp(Point::new(4.0, -2.0), 0.5), Point::new(2.0, -1.0));
+    assert_eq!(Point::new(0.0, 0.0).lerp(Point::new(4.0, -2.0), 1.5), Point::new(6.0, -3.0));
+    // `f64` points keep the precision `f32` ones lose.
+    let fine = Point::new(1.0_f64, 1.0) + Point::new(1e-12, 0.0);
+    assert!(fine.x > 1.0);
+    let coarse = Point::new(1.0_f32, 1.0) + Point::new(1e-12, 0.0);
+    assert_eq!(coarse.x, 1.0);
+}
+
+#[test]
+fn the_tutorial_points_default_to_f32() {
+    // The tutorial's points still default to `f32`.
+    let default: Point = Point { x: 5.2, y: 0.4 };
+    let same: Point<f32> = default;
+    assert_eq!(same * 2.0, Point { x: 10.4, y: 0.8 });
+}
+
+#[test]
+fn rectangles_over_any_coordinate_type() {
+    // Rectangles work with any coordinate type too.
+    let grid: Rectangle<i32> = Rectangle::from_corners(Point::new(8, 0), Point::new(0, 6));
+    assert_eq!(grid, Rectangle { top_left: Point::new(0, 6), bottom_right: Point::new(8, 0) });
+    assert_eq!((grid.width(), grid.height(), grid.area(), grid.center()), (8, 6, 48, Point::new(4, 3)));
+    assert!(grid.contains(Point::new(8, 6)) && !grid.contains(Point::new(9, 6)));
+    let other = Rectangle::from_corners(Point::new(5, 3), Point::new(12, 10));
+    assert_eq!(grid.intersection(&other), Some(Rectangle::from_corners(Point::new(5, 3), Point::new(8, 6))));
+    assert_eq!(grid.union(&other), Rectangle::from_corners(Point::new(0, 0), Point::new(12, 10)));
+    assert_eq!(grid.inflate(1, 2), Rectangle::from_corners(Point::new(-1, -2), Point::new(9, 8)));
+    assert_eq!(grid.deflate(10, 1), Rectangle::from_corners(Point::new(4, 1), Point::new(4, 5)));
+    // An odd width puts the centre on a whole number, rounded down.
+    assert_eq!(Rectangle::from_corners(Point::new(0, 0), Point::new(3, 3)).center(), Point::new(1, 1));
+    let precise: Rectangle<f64> = Rectangle::from_corners(Point::new(9.5, 9.7), Point::new(10.5, 10.9));
+    assert!((precise.area() - 1.2).abs() < 1e-12);
+    // Moving a rectangle is adding to both corners.
+    let offset = Point::new(2, -1);
+    let moved = Rectangle { top_left: grid.top_left + offset, bottom_right: grid.bottom_right + offset };
+    assert_eq!(moved.center(), grid.center() + offset);
+}
@@ -0,0 +1,113 @@
+use structs::{ApproxEq, Point, Rectangle};
+
+fn rect(left: f32, top: f32, right: f32, bottom: f32) -> Rectangle {
+    Rectangle { top_left: Point::new(left, top), bottom_right: Point::new(right, bottom) }
+}
+
+#[test]
+fn any_two_opposite_corners_give_the_same_rectangle() {
+    // Any two opposite corners give the same rectangle. Down is a smaller `y`.
+    let expected = rect(0.0, 4.0, 6.0, 0.0);
+    for (a, b) in [((0.0, 4.0), (6.0, 0.0)), ((6.0, 0.0), (0.0, 4.0)), ((0.0, 0.0), (6.0, 4.0)), ((6.0, 4.0), (0.0, 0.0))] {
+        assert_eq!(Rectangle::from_corners(Point::new(a.0, a.1), Point::new(b.0, b.1)), expected);
+    }
+    let backwards = rect(6.0, 0.0, 0.0, 4.0);
+    assert!(!backwards.is_normalized());
+    assert_eq!(backwards.normalize(), expected);
+    assert!(expected.is_normalized());
+    // Measurements don't care which way round the corners are.
+    for r in [expected, backwards] {
+        assert_eq!((r.left(), r.right(), r.top(), r.bottom()), (0.0, 6.0, 4.0, 0.0));
+        assert_eq!((r.width(), r.height(), r.area()), (6.0, 4.0, 24.0));
+        assert_eq!(r.center(), Point::new(3.0, 2.0));
+    }
+}
+
+#[test]
+fn containment_includes_the_edges() {
+    // Containment includes the edges and corners.
+    let r = rect(0.0, 4.0, 6.0, 0.0);
+    for inside in [(3.0, 2.0), (0.0, 0.0), (6.0, 4.0), (0.0, 2.0), (3.0, 4.0)] {
+        assert!(r.contains(Point::new(inside.0, inside.1)), "{:?}", inside);
+    }
+    for outside in [(-0.1, 2.0), (6.1, 2.0), (3.0, 4.1), (3.0, -0.1), (f32::NAN, 1.0)] {
+        assert!(!r.contains(Point::new(outside.0, outside.1)), "{:?}", outside);
+    }
+    assert!(r.contains_rect(&rect(1.0, 3.0, 5.0, 1.0)));
+    assert!(r.contains_rect(&r));
+    assert!(!r.contains_rect(&rect(1.0, 5.0, 5.0, 1.0)));
+}
+
+#[test]
+fn intersection_and_union() {
+    let r = rect(0.0, 4.0, 6.0, 0.0);
+    // Overlapping.
+    let other = rect(4.0, 6.0, 10.0, 2.0);
+    assert!(r.intersects(&other) && other.intersects(&r));
+    assert_eq!(r.intersection(&other), Some(rect(4.0, 4.0, 6.0, 2.0)));
+    assert_eq!(r.union(&other), rect(0.0, 6.0, 10.0, 0.0));
+    // One inside the other.
+    let inner = rect(1.0, 3.0, 2.0, 1.0);
+    assert_eq!(r.intersection(&inner), Some(inner));
+    assert_eq!(r.union(&inner), r);
+    // Apart.
+    let far = rect(7.0, 4.0, 9.0, 0.0);
+    assert!(!r.intersects(&far));
+    assert_eq!(r.intersection(&far), None);
+    assert_eq!(r.union(&far), rect(0.0, 4.0, 9.0, 0.0));
+}
+
+#[test]
+fn touching_rectangles_intersect_in_a_line_or_point() {
+    let r = rect(0.0, 4.0, 6.0, 0.0);
+    // Touching along an edge, or at a single corner, counts, and the intersection
+    // is a line or a point.
+    let beside = rect(6.0, 4.0, 8.0, 0.0);
+    let edge = r.intersection(&beside).unwrap();
+    assert_eq!(edge, rect(6.0, 4.0, 6.0, 0.0));
+    assert_eq!((edge.width(), edge.height(), edge.area()), (0.0, 4.0, 0.0));
+    let below = rect(0.0, 0.0, 6.0, -3.0);
+    assert_eq!(r.intersection(&below), Some(rect(0.0, 0.0, 6.0, 0.0)));
+    let corner = rect(6.0, 0.0, 9.0, -3.0);
+    assert_eq!(r.intersection(&corner), Some(rect(6.0, 0.0, 6.0, 0.0)));
+}
+
+#[test]
+fn rectangles_with_no_area() {
+    let r = rect(0.0, 4.0, 6.0, 0.0);
+    // Rectangles with no area behave like any other.
+    let point = rect(3.0, 2.0, 3.0, 2.0);
+    assert_eq!((point.area(), point.center()), (0.0, Point::new(3.0, 2.0)));
+    assert!(point.contains(Point::new(3.0, 2.0)));
+    assert!(r.contains_rect(&point));
+    assert_eq!(r.intersection(&point), Some(point));
+    assert_eq!(point.intersection(&point), Some(point));
+    let line = rect(0.0, 2.0, 10.0, 2.0);
+    assert_eq!(r.intersection(&line), Some(rect(0.0, 2.0, 6.0, 2.0)));
+    assert_eq!(line.union(&point), line);
+}
+
+#[test]
+fn inflate_and_deflate() {
+    let r = rect(0.0, 4.0, 6.0, 0.0);
+    let point = rect(3.0, 2.0, 3.0, 2.0);
+    let backwards = rect(6.0, 0.0, 0.0, 4.0);
+    // Inflating and deflating move each edge by the amount given.
+    assert_eq!(r.inflate(1.0, 2.0), rect(-1.0, 6.0, 7.0, -2.0));
+    assert_eq!(r.deflate(1.0, 1.0), rect(1.0, 3.0, 5.0, 1.0));
+    assert_eq!(r.inflate(1.0, 1.0).deflate(1.0, 1.0), r);
+    // Deflating by too much leaves a line through the middle instead of turning inside out.
+    assert_eq!(r.deflate(4.0, 1.0), rect(3.0, 3.0, 3.0, 1.0));
+    assert_eq!(r.deflate(10.0, 10.0), point);
+    // Both work on rectangles given the wrong way round, and give back normal ones.
+    assert_eq!(backwards.inflate(1.0, 1.0), rect(-1.0, 5.0, 7.0, -1.0));
+}
+
+#[test]
+fn the_tutorial_rectangle() {
+    // The tutorial's test rectangle, whose corners are the other way round.
+    let test_rectangle = rect(10.5, 10.9, 9.5, 9.7);
+    assert!(!test_rectangle.is_normalized());
+    assert!(test_rectangle.area().approx_eq_ulps(1.2, 4));
+    assert!(test_rectangle.contains(Point::new(10.0, 10.0)));
+}
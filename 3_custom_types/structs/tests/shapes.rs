@@ -0,0 +1,153 @@
+use std::f32::consts::PI;
+
+use structs::{Circle, Point, Polygon, Rectangle, Shape, Triangle};
+
+fn p(x: f32, y: f32) -> Point {
+    Point::new(x, y)
+}
+
+fn rect(left: f32, top: f32, right: f32, bottom: f32) -> Rectangle {
+    Rectangle { top_left: p(left, top), bottom_right: p(right, bottom) }
+}
+
+fn rectangle() -> Rectangle {
+    rect(0.0, 4.0, 6.0, 0.0)
+}
+
+fn circle() -> Circle {
+    Circle::new(p(1.0, 2.0), 3.0)
+}
+
+fn triangle() -> Triangle {
+    Triangle::new(p(0.0, 0.0), p(4.0, 0.0), p(0.0, 3.0))
+}
+
+// The L shape drawn in `polygons`.
+fn l_shape() -> Polygon {
+    Polygon::new(vec![p(0.0, 0.0), p(4.0, 0.0), p(4.0, 2.0), p(2.0, 2.0), p(2.0, 4.0), p(0.0, 4.0)])
+}
+
+fn flat_polygon() -> Polygon {
+    Polygon::new(vec![p(0.0, 0.0), p(1.0, 0.0), p(5.0, 0.0)])
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-4 * a.abs().max(b.abs()).max(1.0)
+}
+
+#[test]
+fn rectangles() {
+    // Rectangles, through the trait.
+    let r = rectangle();
+    let shape: &dyn Shape = &r;
+    assert_eq!((shape.area(), shape.perimeter(), shape.centroid()), (24.0, 20.0, p(3.0, 2.0)));
+    assert!(shape.contains(p(6.0, 4.0)) && !shape.contains(p(6.1, 4.0)));
+    // The bounding box of a rectangle given the wrong way round is the normal one.
+    assert_eq!(Shape::bounding_box(&rect(6.0, 0.0, 0.0, 4.0)), r);
+}
+
+#[test]
+fn circles() {
+    // Circles.
+    let c = circle();
+    assert!(close(c.area(), 9.0 * PI) && close(c.perimeter(), 6.0 * PI));
+    assert_eq!(c.bounding_box(), rect(-2.0, 5.0, 4.0, -1.0));
+    assert_eq!(c.centroid(), p(1.0, 2.0));
+    assert!(c.contains(p(4.0, 2.0)) && c.contains(p(1.0, -1.0)));
+    assert!(!c.contains(p(4.0, 2.1)) && !c.contains(p(3.2, 4.2)));
+    assert_eq!(Circle::checked_new(p(0.0, 0.0), -1.0), None);
+    assert_eq!(Circle::checked_new(p(0.0, 0.0), f32::NAN), None);
+    let dot = Circle::new(p(1.0, 1.0), 0.0);
+    assert_eq!((dot.area(), dot.perimeter()), (0.0, 0.0));
+    assert!(dot.contains(p(1.0, 1.0)) && !dot.contains(p(1.0, 1.0001)));
+}
+
+#[test]
+fn triangles_either_way_round() {
+    // Triangles, with the corners either way round.
+    let t = triangle();
+    let backwards = Triangle::new(p(0.0, 3.0), p(4.0, 0.0), p(0.0, 0.0));
+    for t in [t, backwards] {
+        assert_eq!((t.area(), t.perimeter()), (6.0, 12.0));
+        assert_eq!(t.bounding_box(), rect(0.0, 3.0, 4.0, 0.0));
+        assert!(close(t.centroid().x, 4.0 / 3.0) && close(t.centroid().y, 1.0));
+        for inside in [(1.0, 1.0), (0.0, 0.0), (4.0, 0.0), (2.0, 1.5), (0.0, 1.0), (2.0, 0.0)] {
+            assert!(t.contains(p(inside.0, inside.1)), "{:?}", inside);
+        }
+        for outside in [(2.1, 1.5), (-0.1, 1.0), (1.0, -0.1), (4.0, 3.0), (f32::NAN, 0.0)] {
+            assert!(!t.contains(p(outside.0, outside.1)), "{:?}", outside);
+        }
+    }
+    // A flat triangle is only its edges.
+    let flat = Triangle::new(p(0.0, 0.0), p(2.0, 2.0), p(4.0, 4.0));
+    assert_eq!(flat.area(), 0.0);
+    assert!(flat.contains(p(1.0, 1.0)) && flat.contains(p(4.0, 4.0)));
+    assert!(!flat.contains(p(1.0, 1.5)) && !flat.contains(p(5.0, 5.0)));
+}
+
+#[test]
+fn polygons() {
+    let (r, c, t) = (rectangle(), circle(), triangle());
+    // Polygons need three corners.
+    assert_eq!(Polygon::checked_new(vec![p(0.0, 0.0), p(1.0, 1.0)]), None);
+    // A square agrees with the rectangle it is, going round either way.
+    let square = Polygon::new(vec![p(0.0, 0.0), p(6.0, 0.0), p(6.0, 4.0), p(0.0, 4.0)]);
+    let clockwise = Polygon::new(square.points().iter().rev().copied().collect());
+    for poly in [&square, &clockwise] {
+        assert_eq!((poly.area(), poly.perimeter(), poly.centroid()), (r.area(), 20.0, r.center()));
+        assert_eq!(poly.bounding_box(), r);
+        for x in [-1.0, 0.0, 3.0, 6.0, 7.0] {
+            for y in [-1.0, 0.0, 2.0, 4.0, 5.0] {
+                assert_eq!(poly.contains(p(x, y)), r.contains(p(x, y)), "{:?}", (x, y));
+            }
+        }
+    }
+    // An L shape, which isn't convex: its centroid is pulled into the corner, and the
+    // notch cut out of it is outside.
+    //   +--+
+    //   |  |
+    //   |  +--+
+    //   |     |
+    //   +-----+
+    let l = l_shape();
+    assert_eq!((l.area(), l.perimeter()), (12.0, 16.0));
+    assert!(close(l.centroid().x, 5.0 / 3.0) && close(l.centroid().y, 5.0 / 3.0));
+    assert!(l.contains(p(1.0, 3.0)) && l.contains(p(3.0, 1.0)) && l.contains(p(3.0, 2.0)) && l.contains(p(2.0, 3.0)));
+    assert!(!l.contains(p(3.0, 3.0)) && !l.contains(p(2.1, 2.1)));
+    // A line from the point crosses exactly through corners: on `y = 2` from the left,
+    // and on `y = 4` just above the top.
+    assert!(l.contains(p(1.0, 2.0)) && l.contains(p(-0.0, 2.0)));
+    assert!(!l.contains(p(-1.0, 2.0)) && !l.contains(p(-1.0, 4.0)) && !l.contains(p(-1.0, 0.0)));
+    // A polygon with its corners in a line has no area, and its centroid is the
+    // average of the corners.
+    let line = flat_polygon();
+    assert_eq!((line.area(), line.perimeter(), line.centroid()), (0.0, 10.0, p(2.0, 0.0)));
+    assert!(line.contains(p(3.0, 0.0)) && !line.contains(p(3.0, 0.5)));
+    // A triangle as a polygon gives the same answers as `Triangle`.
+    let as_polygon = Polygon::new(vec![t.a, t.b, t.c]);
+    assert_eq!((as_polygon.area(), as_polygon.perimeter(), as_polygon.bounding_box()), (t.area(), t.perimeter(), t.bounding_box()));
+    assert!(close(as_polygon.centroid().x, t.centroid().x) && close(as_polygon.centroid().y, t.centroid().y));
+    // A many-sided polygon around a circle gets close to the circle's measurements.
+    let round = Polygon::new((0..1_000).map(|i| {
+        let angle = i as f32 / 1_000.0 * 2.0 * PI;
+        p(1.0 + 3.0 * angle.cos(), 2.0 + 3.0 * angle.sin())
+    }).collect());
+    assert!((round.area() - c.area()).abs() < 0.01 && (round.perimeter() - c.perimeter()).abs() < 0.01);
+    assert!(close(round.centroid().x, 1.0) && close(round.centroid().y, 2.0));
+}
+
+#[test]
+fn shapes_together() {
+    let (r, c, t, l, line) = (rectangle(), circle(), triangle(), l_shape(), flat_polygon());
+    // All of them together.
+    let shapes: Vec<Box<dyn Shape>> = vec![Box::new(r), Box::new(c), Box::new(t), Box::new(l.clone()), Box::new(line)];
+    let total: f32 = shapes.iter().map(|shape| shape.area()).sum();
+    assert!(close(total, 24.0 + 9.0 * PI + 6.0 + 12.0));
+    let everything = shapes.iter().map(|shape| shape.bounding_box()).reduce(|a, b| a.union(&b)).unwrap();
+    assert_eq!(everything, rect(-2.0, 5.0, 6.0, -1.0));
+    assert!(shapes.iter().all(|shape| everything.contains_rect(&shape.bounding_box())));
+    let hit: Vec<usize> = (0..shapes.len()).filter(|&i| shapes[i].contains(p(1.0, 0.0))).collect();
+    assert_eq!(hit, [0, 1, 2, 3, 4]);
+    let hit: Vec<usize> = (0..shapes.len()).filter(|&i| shapes[i].contains(p(3.0, 3.0))).collect();
+    assert_eq!(hit, [0, 1]);
+}